@@ -0,0 +1,613 @@
+//! A small multi-line text editor: click and drag to select, arrow keys, Home and End to
+//! move the caret, Shift to extend the selection, Ctrl+A to select everything and an input
+//! method to compose text, e.g. for CJK input.
+//!
+//! Caret positions and hit testing are derived from the laid out glyphs of
+//! `GlyphCruncher::glyphs`, the text is clipped to the editor box with
+//! `draw_queued_clipped`. Selection and caret are drawn as plain quads.
+
+#[macro_use]
+extern crate glium;
+extern crate glium_glyph;
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use glium::glutin::{Api, GlProfile, GlRequest};
+use glium::{glutin, Program, Surface};
+
+use glium_glyph::glyph_brush::ab_glyph::{Font, FontRef, ScaleFont};
+use glium_glyph::glyph_brush::{GlyphCruncher, Section, SectionGlyph, Text};
+use glium_glyph::{GlyphBrush, GlyphBrushBuilder};
+
+use glutin::dpi::PhysicalPosition;
+use glutin::event::{
+    ElementState, Event, Ime, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode,
+    WindowEvent,
+};
+use glutin::event_loop::{ControlFlow, EventLoop};
+
+const SCALE: f32 = 24.0;
+/// distance of the editor box from the window edges
+const PADDING: f32 = 20.0;
+const TEXT_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
+const PREEDIT_COLOR: [f32; 4] = [0.1, 0.3, 0.8, 1.0];
+const SELECTION_COLOR: [f32; 4] = [0.7, 0.8, 1.0, 1.0];
+
+const RECT_VERTEX_SHADER: &str = r#"
+#version 150
+in vec2 position;
+uniform vec2 screen;
+void main() {
+    gl_Position = vec4(position / screen * vec2(2.0, -2.0) + vec2(-1.0, 1.0), 0.0, 1.0);
+}
+"#;
+
+const RECT_FRAGMENT_SHADER: &str = r#"
+#version 150
+uniform vec4 color;
+out vec4 out_color;
+void main() {
+    out_color = color;
+}
+"#;
+
+#[derive(Copy, Clone)]
+struct RectVertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(RectVertex, position);
+
+/// A position the caret can be placed at, before the character at `index`, relative to
+/// the origin of the text.
+#[derive(Copy, Clone, Debug)]
+struct Caret {
+    index: usize,
+    x: f32,
+    baseline: f32,
+}
+
+struct Editor {
+    text: String,
+    /// byte index of the caret
+    caret: usize,
+    /// other end of the selection, if any
+    anchor: Option<usize>,
+    /// text being composed with an input method, shown at the caret
+    preedit: String,
+    /// caret positions of the text as last laid out, in text order
+    carets: Vec<Caret>,
+    ascent: f32,
+    descent: f32,
+    line_height: f32,
+    scroll: f32,
+    /// scroll to the caret on the next draw
+    reveal_caret: bool,
+    /// x position kept when moving between lines of different lengths
+    column_x: Option<f32>,
+    shift: bool,
+    ctrl: bool,
+    dragging: bool,
+}
+
+impl Editor {
+    fn section(&self, position: (f32, f32), width: f32) -> Section<'_> {
+        let (before, after) = self.text.split_at(self.caret);
+        Section::default()
+            .add_text(Text::new(before).with_scale(SCALE).with_color(TEXT_COLOR))
+            .add_text(
+                Text::new(&self.preedit)
+                    .with_scale(SCALE)
+                    .with_color(PREEDIT_COLOR),
+            )
+            .add_text(Text::new(after).with_scale(SCALE).with_color(TEXT_COLOR))
+            .with_screen_position(position)
+            .with_bounds((width, f32::INFINITY))
+    }
+
+    fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor.filter(|&anchor| anchor != self.caret)?;
+        Some(anchor.min(self.caret)..anchor.max(self.caret))
+    }
+
+    fn caret_position(&self) -> Option<Caret> {
+        self.carets.iter().find(|c| c.index == self.caret).copied()
+    }
+
+    /// Moves the caret, extending the selection while Shift is held.
+    fn move_caret(&mut self, index: usize) {
+        if self.shift {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+        self.caret = index;
+        self.reveal_caret = true;
+    }
+
+    fn move_horizontally(&mut self, forward: bool) {
+        self.column_x = None;
+        if let (Some(selection), false) = (self.selection(), self.shift) {
+            self.anchor = None;
+            self.caret = if forward {
+                selection.end
+            } else {
+                selection.start
+            };
+            return;
+        }
+        let index = if forward {
+            self.text[self.caret..]
+                .chars()
+                .next()
+                .map_or(self.caret, |c| self.caret + c.len_utf8())
+        } else {
+            self.text[..self.caret]
+                .chars()
+                .next_back()
+                .map_or(self.caret, |c| self.caret - c.len_utf8())
+        };
+        self.move_caret(index);
+    }
+
+    fn move_vertically(&mut self, down: bool) {
+        let current = match self.caret_position() {
+            Some(current) => current,
+            None => return,
+        };
+        let x = *self.column_x.get_or_insert(current.x);
+        let line = self
+            .carets
+            .iter()
+            .map(|c| c.baseline)
+            .filter(|&b| match down {
+                true => b > current.baseline,
+                false => b < current.baseline,
+            })
+            .min_by(|a, b| {
+                let (a, b) = ((a - current.baseline).abs(), (b - current.baseline).abs());
+                a.total_cmp(&b)
+            });
+        let index = match line {
+            Some(line) => self.closest_on_line(line, x),
+            None if down => self.text.len(),
+            None => 0,
+        };
+        self.move_caret(index);
+    }
+
+    /// Moves the caret to the start or end of its visual line.
+    fn move_to_line_edge(&mut self, end: bool) {
+        self.column_x = None;
+        let current = match self.caret_position() {
+            Some(current) => current,
+            None => return,
+        };
+        let line = self
+            .carets
+            .iter()
+            .filter(|c| c.baseline == current.baseline)
+            .map(|c| c.index);
+        let index = match end {
+            true => line.max(),
+            false => line.min(),
+        };
+        self.move_caret(index.unwrap_or(self.caret));
+    }
+
+    fn closest_on_line(&self, baseline: f32, x: f32) -> usize {
+        self.carets
+            .iter()
+            .filter(|c| c.baseline == baseline)
+            .min_by(|a, b| (a.x - x).abs().total_cmp(&(b.x - x).abs()))
+            .map_or(0, |c| c.index)
+    }
+
+    /// Returns the caret position closest to `(x, y)`, relative to the origin of the text.
+    fn hit(&self, x: f32, y: f32) -> usize {
+        let distance = |c: &Caret| (y - (c.baseline - self.ascent / 2.0)).abs();
+        match self
+            .carets
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        {
+            Some(closest) => self.closest_on_line(closest.baseline, x),
+            None => 0,
+        }
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some(selection) => {
+                self.text.replace_range(selection.clone(), "");
+                self.caret = selection.start;
+                self.anchor = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        self.text.insert_str(self.caret, text);
+        self.caret += text.len();
+        self.anchor = None;
+        self.column_x = None;
+        self.reveal_caret = true;
+    }
+
+    fn delete(&mut self, forward: bool) {
+        self.column_x = None;
+        self.reveal_caret = true;
+        if self.delete_selection() {
+            return;
+        }
+        if forward {
+            if let Some(c) = self.text[self.caret..].chars().next() {
+                self.text
+                    .replace_range(self.caret..self.caret + c.len_utf8(), "");
+            }
+        } else if let Some(c) = self.text[..self.caret].chars().next_back() {
+            self.caret -= c.len_utf8();
+            self.text.remove(self.caret);
+        }
+    }
+
+    fn key(&mut self, key: VirtualKeyCode) {
+        match key {
+            VirtualKeyCode::Left => self.move_horizontally(false),
+            VirtualKeyCode::Right => self.move_horizontally(true),
+            VirtualKeyCode::Up => self.move_vertically(false),
+            VirtualKeyCode::Down => self.move_vertically(true),
+            VirtualKeyCode::Home => self.move_to_line_edge(false),
+            VirtualKeyCode::End => self.move_to_line_edge(true),
+            VirtualKeyCode::Back => self.delete(false),
+            VirtualKeyCode::Delete => self.delete(true),
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => self.insert("\n"),
+            VirtualKeyCode::A if self.ctrl => {
+                self.anchor = Some(0);
+                self.caret = self.text.len();
+            }
+            _ => {}
+        }
+    }
+
+    /// Rectangles `[left, top, right, bottom]` covering the selected text.
+    fn selection_rects(&self) -> Vec<[f32; 4]> {
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return Vec::new(),
+        };
+        self.carets
+            .windows(2)
+            .filter(|pair| {
+                pair[0].index >= selection.start
+                    && pair[1].index <= selection.end
+                    && pair[0].baseline == pair[1].baseline
+            })
+            .map(|pair| {
+                [
+                    pair[0].x,
+                    pair[0].baseline - self.ascent,
+                    pair[1].x,
+                    pair[0].baseline - self.descent,
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Returns the caret positions of the text of `editor`, laid out in `section`.
+fn layout_carets<F: Font + Sync>(
+    glyph_brush: &mut GlyphBrush<F>,
+    editor: &Editor,
+    section: &Section,
+) -> Vec<Caret> {
+    let glyphs: Vec<SectionGlyph> = glyph_brush.glyphs(section).cloned().collect();
+
+    // positions before and after each glyph, glyphs of the preedit text are skipped
+    let mut found = HashMap::new();
+    for glyph in &glyphs {
+        let index = match glyph.section_index {
+            0 => glyph.byte_index,
+            2 => editor.caret + glyph.byte_index,
+            _ => continue,
+        };
+        let font = &glyph_brush.fonts()[glyph.font_id.0];
+        let advance = font.as_scaled(glyph.glyph.scale).h_advance(glyph.glyph.id);
+        let position = glyph.glyph.position;
+        let end = index
+            + editor.text[index..]
+                .chars()
+                .next()
+                .map_or(0, char::len_utf8);
+        found.insert(
+            index,
+            Caret {
+                index,
+                x: position.x,
+                baseline: position.y,
+            },
+        );
+        found.entry(end).or_insert(Caret {
+            index: end,
+            x: position.x + advance,
+            baseline: position.y,
+        });
+    }
+
+    // line breaks aren't glyphs, place the positions after them at the next line start
+    let boundaries = editor.text.char_indices().map(|(i, _)| i);
+    let mut carets: Vec<Caret> = Vec::new();
+    for index in boundaries.chain(Some(editor.text.len())) {
+        let caret = match (found.get(&index), carets.last()) {
+            (Some(caret), _) => *caret,
+            (None, Some(previous)) if editor.text[..index].ends_with('\n') => Caret {
+                index,
+                x: 0.0,
+                baseline: previous.baseline + editor.line_height,
+            },
+            (None, Some(previous)) => Caret { index, ..*previous },
+            (None, None) => Caret {
+                index,
+                x: 0.0,
+                baseline: editor.ascent,
+            },
+        };
+        carets.push(caret);
+    }
+    carets
+}
+
+fn rect_vertices(rects: &[[f32; 4]], offset: (f32, f32)) -> Vec<RectVertex> {
+    let mut vertices = Vec::with_capacity(rects.len() * 6);
+    for &[left, top, right, bottom] in rects {
+        let (left, right) = (left + offset.0, right + offset.0);
+        let (top, bottom) = (top + offset.1, bottom + offset.1);
+        for &position in &[
+            [left, top],
+            [right, top],
+            [left, bottom],
+            [right, top],
+            [right, bottom],
+            [left, bottom],
+        ] {
+            vertices.push(RectVertex { position });
+        }
+    }
+    vertices
+}
+
+fn draw_rects<S: Surface>(
+    display: &glium::Display,
+    surface: &mut S,
+    program: &Program,
+    rects: &[[f32; 4]],
+    offset: (f32, f32),
+    color: [f32; 4],
+    params: &glium::DrawParameters,
+) {
+    let (width, height) = surface.get_dimensions();
+    let vertices = glium::VertexBuffer::new(display, &rect_vertices(rects, offset)).unwrap();
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+    let uniforms = uniform! {
+        screen: [width as f32, height as f32],
+        color: color,
+    };
+    surface
+        .draw(&vertices, indices, program, &uniforms, params)
+        .unwrap();
+}
+
+pub fn main() {
+    if cfg!(target_os = "linux") {
+        // winit wayland has rendering problems on some setups
+        if std::env::var("WINIT_UNIX_BACKEND").is_err() {
+            std::env::set_var("WINIT_UNIX_BACKEND", "x11");
+        }
+    }
+
+    let event_loop = EventLoop::new();
+    let window = glutin::window::WindowBuilder::new().with_title("glium-glyph editor");
+    let context = glutin::ContextBuilder::new()
+        .with_gl_profile(GlProfile::Core)
+        .with_gl(GlRequest::Specific(Api::OpenGl, (3, 2)))
+        .with_srgb(true);
+    let display = glium::Display::new(window, context, &event_loop).unwrap();
+    display.gl_window().window().set_ime_allowed(true);
+
+    let dejavu: &[u8] = include_bytes!("../fonts/DejaVuSans-2.37.ttf");
+    let dejavu_font = FontRef::try_from_slice(dejavu).unwrap();
+    let metrics = dejavu_font.clone().into_scaled(SCALE);
+
+    let mut glyph_brush = GlyphBrushBuilder::using_font(dejavu_font).build(&display);
+    let rect_program =
+        Program::from_source(&display, RECT_VERTEX_SHADER, RECT_FRAGMENT_SHADER, None).unwrap();
+
+    let text = "Select text with the mouse or with Shift and the arrow keys.\n\n\
+                Long lines wrap at the edge of the box, resize the window to see the \
+                caret follow the wrapped lines.";
+    let mut editor = Editor {
+        text: text.to_owned(),
+        caret: text.len(),
+        anchor: None,
+        preedit: String::new(),
+        carets: Vec::new(),
+        ascent: metrics.ascent(),
+        descent: metrics.descent(),
+        line_height: metrics.height() + metrics.line_gap(),
+        scroll: 0.0,
+        reveal_caret: true,
+        column_x: None,
+        shift: false,
+        ctrl: false,
+        dragging: false,
+    };
+    let mut mouse = (0.0, 0.0);
+
+    event_loop.run(move |event, _tgt, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        let event = match event {
+            Event::WindowEvent { event, .. } => event,
+            Event::RedrawRequested(_) => {
+                draw(&display, &mut glyph_brush, &rect_program, &mut editor);
+                return;
+            }
+            _ => return,
+        };
+        // mouse position relative to the origin of the text
+        let hit = |editor: &Editor, (x, y): (f32, f32)| {
+            editor.hit(x - PADDING, y - PADDING + editor.scroll)
+        };
+        match event {
+            WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+            WindowEvent::ModifiersChanged(modifiers) => {
+                editor.shift = modifiers.shift();
+                editor.ctrl = modifiers.ctrl();
+            }
+            WindowEvent::ReceivedCharacter(c) if !c.is_control() && !editor.ctrl => {
+                editor.insert(c.encode_utf8(&mut [0; 4]));
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } => editor.key(key),
+            WindowEvent::Ime(Ime::Preedit(text, _)) => editor.preedit = text,
+            WindowEvent::Ime(Ime::Commit(text)) => {
+                editor.preedit.clear();
+                editor.insert(&text);
+            }
+            WindowEvent::Ime(Ime::Disabled) => editor.preedit.clear(),
+            WindowEvent::CursorMoved { position, .. } => {
+                mouse = (position.x as f32, position.y as f32);
+                if editor.dragging {
+                    let index = hit(&editor, mouse);
+                    editor.anchor.get_or_insert(editor.caret);
+                    editor.caret = index;
+                    editor.reveal_caret = true;
+                }
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                editor.dragging = state == ElementState::Pressed;
+                if editor.dragging {
+                    editor.column_x = None;
+                    let index = hit(&editor, mouse);
+                    editor.move_caret(index);
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y * editor.line_height,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32,
+                };
+                editor.scroll = (editor.scroll - lines).max(0.0);
+            }
+            _ => return,
+        }
+        display.gl_window().window().request_redraw();
+    });
+}
+
+fn draw(
+    display: &glium::Display,
+    glyph_brush: &mut GlyphBrush<FontRef<'static>>,
+    rect_program: &Program,
+    editor: &mut Editor,
+) {
+    let (width, height) = display.get_framebuffer_dimensions();
+    let box_size = (
+        (width as f32 - 2.0 * PADDING).max(1.0),
+        (height as f32 - 2.0 * PADDING).max(1.0),
+    );
+
+    let section = editor.section((0.0, 0.0), box_size.0);
+    editor.carets = layout_carets(glyph_brush, editor, &section);
+    if editor.reveal_caret {
+        editor.reveal_caret = false;
+        if let Some(caret) = editor.caret_position() {
+            let top = caret.baseline - editor.ascent;
+            let bottom = caret.baseline - editor.descent;
+            if bottom - editor.scroll > box_size.1 {
+                editor.scroll = bottom - box_size.1;
+            }
+            if top < editor.scroll {
+                editor.scroll = top.max(0.0);
+            }
+        }
+    }
+    let origin = (PADDING, PADDING - editor.scroll);
+    if let Some(caret) = editor.caret_position() {
+        let position = PhysicalPosition::new(origin.0 + caret.x, origin.1 + caret.baseline);
+        display.gl_window().window().set_ime_position(position);
+    }
+
+    let clip = glium::Rect {
+        left: PADDING as u32,
+        bottom: PADDING as u32,
+        width: box_size.0 as u32,
+        height: box_size.1 as u32,
+    };
+    let params = glium::DrawParameters {
+        scissor: Some(clip),
+        ..Default::default()
+    };
+
+    let mut target = display.draw();
+    target.clear_color(0.85, 0.85, 0.85, 1.0);
+    let editor_box = [0.0, 0.0, box_size.0, box_size.1];
+    let white = [1.0, 1.0, 1.0, 1.0];
+    let background = (PADDING, PADDING);
+    draw_rects(
+        display,
+        &mut target,
+        rect_program,
+        &[editor_box],
+        background,
+        white,
+        &params,
+    );
+    draw_rects(
+        display,
+        &mut target,
+        rect_program,
+        &editor.selection_rects(),
+        origin,
+        SELECTION_COLOR,
+        &params,
+    );
+
+    glyph_brush.queue(editor.section(origin, box_size.0));
+    glyph_brush
+        .draw_queued_clipped(display, &mut target, clip)
+        .unwrap();
+
+    if let Some(caret) = editor.caret_position() {
+        let caret_rect = [
+            caret.x - 1.0,
+            caret.baseline - editor.ascent,
+            caret.x + 1.0,
+            caret.baseline - editor.descent,
+        ];
+        draw_rects(
+            display,
+            &mut target,
+            rect_program,
+            &[caret_rect],
+            origin,
+            TEXT_COLOR,
+            &params,
+        );
+    }
+    target.finish().unwrap();
+}
@@ -0,0 +1,54 @@
+//! Renders text without a window and writes it to `headless.ppm`.
+
+extern crate glium;
+extern crate glium_glyph;
+
+use glium::glutin;
+use glium::glutin::dpi::PhysicalSize;
+use glium::HeadlessRenderer;
+
+use glium_glyph::glyph_brush::{ab_glyph::FontRef, Section, Text};
+use glium_glyph::GlyphBrushBuilder;
+
+use std::fs::File;
+use std::io::Write;
+
+const SIZE: (u32, u32) = (512, 128);
+
+pub fn main() {
+    let event_loop = glutin::event_loop::EventLoop::new();
+    // the default framebuffer of the context is never drawn to
+    let context = glutin::ContextBuilder::new()
+        .build_headless(&event_loop, PhysicalSize::new(1, 1))
+        .unwrap();
+    let context = unsafe { context.treat_as_current() };
+    let renderer = HeadlessRenderer::new(context).unwrap();
+
+    let dejavu: &[u8] = include_bytes!("../fonts/DejaVuSans-2.37.ttf");
+    let dejavu_font = FontRef::try_from_slice(dejavu).unwrap();
+    let mut glyph_brush = GlyphBrushBuilder::using_font(dejavu_font).build(&renderer);
+
+    let section = Section::default()
+        .add_text(
+            Text::new("Hello from a headless context")
+                .with_scale(40.0)
+                .with_color([1.0, 1.0, 1.0, 1.0]),
+        )
+        .with_screen_position((16.0, 40.0));
+    let pixels = glyph_brush
+        .render_to_pixels(&renderer, Some(section), SIZE)
+        .unwrap();
+
+    let mut file = File::create("headless.ppm").unwrap();
+    writeln!(file, "P6 {} {} 255", SIZE.0, SIZE.1).unwrap();
+    let rgb: Vec<u8> = pixels
+        .chunks(4)
+        .flat_map(|p| {
+            // composite onto black
+            let alpha = u32::from(p[3]);
+            let channel = move |c: u8| (u32::from(c) * alpha / 255) as u8;
+            vec![channel(p[0]), channel(p[1]), channel(p[2])]
+        })
+        .collect();
+    file.write_all(&rgb).unwrap();
+}
@@ -34,14 +34,12 @@ pub fn main() {
     let mut glyph_brush = GlyphBrushBuilder::using_font(dejavu_font).build(&display);
 
     event_loop.run(move |event, _tgt, control_flow| {
-        match event {
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::CloseRequested => {
-                    *control_flow = ControlFlow::Exit;
-                }
-                _ => (),
-            },
-            _ => (),
+        if let Event::WindowEvent {
+            event: WindowEvent::CloseRequested,
+            ..
+        } = event
+        {
+            *control_flow = ControlFlow::Exit;
         }
         let screen_dims = display.get_framebuffer_dimensions();
 
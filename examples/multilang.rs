@@ -0,0 +1,120 @@
+//! Draws paragraphs mixing Latin, CJK, Arabic and emoji with per character font
+//! fallback: every character is drawn with the first font that has a glyph for it, see
+//! `GlyphBrush::set_font_fallbacks`.
+//!
+//! The bundled font only covers Latin and a few other scripts, pass more font files to
+//! cover the rest, e.g.
+//! `cargo run --features color-glyphs --example multilang NotoSansCJK.ttc NotoSansArabic.ttf NotoColorEmoji.ttf`.
+//! Emoji are drawn in color with the `color-glyphs` feature. The crate doesn't shape
+//! text or reorder right to left scripts, so Arabic shows its unjoined letters left to
+//! right in logical order.
+//!
+//! `tests/multilang.rs` draws the same paragraphs headless and checks the pixels.
+
+extern crate glium;
+extern crate glium_glyph;
+
+use glium::glutin::{Api, GlProfile, GlRequest};
+use glium::{glutin, Surface};
+
+use glium_glyph::glyph_brush::ab_glyph::{Font, FontArc};
+use glium_glyph::glyph_brush::{FontId, GlyphCruncher, Section, Text};
+use glium_glyph::GlyphBrushBuilder;
+
+use glutin::event::{Event, WindowEvent};
+use glutin::event_loop::{ControlFlow, EventLoop};
+
+const PARAGRAPHS: &[&str] = &[
+    "Latin: The quick brown fox jumps over the lazy dog.",
+    "CJK: 日本語のテキストと中文文本，还有한국어 텍스트。",
+    "Arabic: مرحبا بالعالم",
+    "Emoji: 😀 🎉 🦀 👍🏽",
+    "Mixed: glium-glyph 描画 نص 🚀 done.",
+];
+
+pub fn main() {
+    if cfg!(target_os = "linux") {
+        // winit wayland has rendering problems on some setups
+        if std::env::var("WINIT_UNIX_BACKEND").is_err() {
+            std::env::set_var("WINIT_UNIX_BACKEND", "x11");
+        }
+    }
+
+    let event_loop = EventLoop::new();
+    let window = glutin::window::WindowBuilder::new().with_title("glium-glyph multilang");
+    let context = glutin::ContextBuilder::new()
+        .with_gl_profile(GlProfile::Core)
+        .with_gl(GlRequest::Specific(Api::OpenGl, (3, 2)))
+        .with_srgb(true);
+    let display = glium::Display::new(window, context, &event_loop).unwrap();
+
+    let dejavu: &[u8] = include_bytes!("../fonts/DejaVuSans-2.37.ttf");
+    let dejavu_font = FontArc::try_from_slice(dejavu).unwrap();
+    let mut glyph_brush = GlyphBrushBuilder::using_font(dejavu_font).build(&display);
+
+    // fonts that fail to load are replaced with the bundled one
+    glyph_brush.set_fallback_font(Some(FontId(0)));
+    let mut fallbacks = Vec::new();
+    for path in std::env::args().skip(1) {
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                continue;
+            }
+        };
+        match glyph_brush.try_add_font_bytes(data) {
+            Ok(font_id) => fallbacks.push(font_id),
+            Err(e) => eprintln!("{}: {}", path, e),
+        }
+    }
+    glyph_brush.set_font_fallbacks(fallbacks);
+
+    for paragraph in PARAGRAPHS {
+        let missing: String = paragraph
+            .chars()
+            .filter(|&c| {
+                !c.is_whitespace()
+                    && glyph_brush
+                        .fonts()
+                        .iter()
+                        .all(|font| font.glyph_id(c).0 == 0)
+            })
+            .collect();
+        if !missing.is_empty() {
+            eprintln!("no font for {:?}", missing);
+        }
+    }
+
+    event_loop.run(move |event, _tgt, control_flow| {
+        if let Event::WindowEvent {
+            event: WindowEvent::CloseRequested,
+            ..
+        } = event
+        {
+            *control_flow = ControlFlow::Exit;
+        }
+        let (width, _) = display.get_framebuffer_dimensions();
+
+        let mut y = 20.0;
+        for paragraph in PARAGRAPHS {
+            let text = Text::new(paragraph)
+                .with_scale(32.0)
+                .with_color([0.1, 0.1, 0.1, 1.0]);
+            let section = Section::default()
+                .add_text(text)
+                .with_screen_position((20.0, y))
+                .with_bounds((width as f32 - 40.0, f32::INFINITY));
+            let height = glyph_brush
+                .glyph_bounds(&section)
+                .map_or(32.0, |bounds| bounds.height());
+            glyph_brush.queue(section);
+            y += height + 16.0;
+        }
+
+        let mut target = display.draw();
+        target.clear_color_and_depth((1.0, 1.0, 1.0, 0.0), 1.0);
+        glyph_brush.draw_queued(&display, &mut target);
+        target.finish().unwrap();
+    });
+}
@@ -0,0 +1,141 @@
+//! Draws thousands of sections with text changing every frame and shows how long
+//! each phase of a frame took, e.g. to compare brush options or spot regressions.
+//!
+//! Run with `cargo run --release --example stress [sections]`.
+
+extern crate glium;
+extern crate glium_glyph;
+
+use std::time::{Duration, Instant};
+
+use glium::glutin::{Api, GlProfile, GlRequest};
+use glium::{glutin, Surface};
+
+use glium_glyph::glyph_brush::{ab_glyph::FontRef, Section, Text};
+use glium_glyph::GlyphBrushBuilder;
+
+use glutin::event::{Event, WindowEvent};
+use glutin::event_loop::{ControlFlow, EventLoop};
+
+/// Frames the shown timings are averaged over.
+const AVERAGED_FRAMES: u32 = 60;
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn main() {
+    if cfg!(target_os = "linux") {
+        // winit wayland has rendering problems on some setups
+        if std::env::var("WINIT_UNIX_BACKEND").is_err() {
+            std::env::set_var("WINIT_UNIX_BACKEND", "x11");
+        }
+    }
+    let sections: usize = std::env::args()
+        .nth(1)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(5000);
+
+    let event_loop = EventLoop::new();
+    let window = glutin::window::WindowBuilder::new().with_title("glium-glyph stress");
+    let context = glutin::ContextBuilder::new()
+        .with_gl_profile(GlProfile::Core)
+        .with_gl(GlRequest::Specific(Api::OpenGl, (3, 2)))
+        .with_vsync(false)
+        .with_srgb(true);
+    let display = glium::Display::new(window, context, &event_loop).unwrap();
+
+    let dejavu: &[u8] = include_bytes!("../fonts/DejaVuSans-2.37.ttf");
+    let dejavu_font = FontRef::try_from_slice(dejavu).unwrap();
+
+    let mut glyph_brush = GlyphBrushBuilder::using_font(dejavu_font).build(&display);
+
+    let mut frame: u64 = 0;
+    // summed `(queue, draw, finish)` durations of the current averaging window
+    let mut phases = [Duration::default(); 3];
+    let mut averaged_frames = 0;
+    let mut stats = String::from("measuring...");
+
+    event_loop.run(move |event, _tgt, control_flow| {
+        *control_flow = ControlFlow::Poll;
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+            Event::MainEventsCleared => {}
+            _ => return,
+        }
+        let (width, height) = display.get_framebuffer_dimensions();
+        let columns = (width / 80).max(1) as usize;
+        // sections beyond the window height wrap around to the top and overlap
+        let rows = (height.saturating_sub(40) / 14).max(1) as usize;
+        frame += 1;
+
+        let start = Instant::now();
+        let mut texts = Vec::with_capacity(sections);
+        for i in 0..sections {
+            texts.push(format!("{}", (frame + i as u64 * 7919) % 100_000));
+        }
+        for (i, text) in texts.iter().enumerate() {
+            let (column, row) = (i % columns, i / columns);
+            let color = [
+                (column as f32 / columns as f32),
+                0.3,
+                1.0 - (row % rows) as f32 / rows as f32,
+                1.0,
+            ];
+            glyph_brush.queue(
+                Section::default()
+                    .add_text(Text::new(text).with_scale(14.0).with_color(color))
+                    .with_screen_position((
+                        column as f32 * 80.0,
+                        40.0 + (row % rows) as f32 * 14.0,
+                    )),
+            );
+        }
+        glyph_brush.queue(
+            Section::default()
+                .add_text(
+                    Text::new(&stats)
+                        .with_scale(18.0)
+                        .with_color([1.0, 1.0, 1.0, 1.0]),
+                )
+                .with_screen_position((8.0, 2.0)),
+        );
+        let queued = Instant::now();
+
+        let mut target = display.draw();
+        target.clear_color(0.0, 0.0, 0.0, 1.0);
+        glyph_brush.draw_queued(&display, &mut target);
+        let drawn = Instant::now();
+        target.finish().unwrap();
+        let finished = Instant::now();
+
+        phases[0] += queued - start;
+        phases[1] += drawn - queued;
+        phases[2] += finished - drawn;
+        averaged_frames += 1;
+        if averaged_frames == AVERAGED_FRAMES {
+            let metrics = glyph_brush.metrics();
+            let [queue, draw, finish] = phases;
+            stats = format!(
+                "{} sections, {} glyphs/frame, {} uploads ({} bytes), cache {:.0}% full\n\
+                 queue {:.2} ms, draw {:.2} ms, finish {:.2} ms",
+                metrics.sections_processed,
+                metrics.glyphs_drawn,
+                metrics.texture_uploads,
+                metrics.uploaded_bytes,
+                metrics.atlas_occupancy(),
+                ms(queue) / AVERAGED_FRAMES as f64,
+                ms(draw) / AVERAGED_FRAMES as f64,
+                ms(finish) / AVERAGED_FRAMES as f64,
+            );
+            phases = [Duration::default(); 3];
+            averaged_frames = 0;
+        }
+    });
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+use glyph_brush::ab_glyph::Rect;
+use glyph_brush::{SectionGeometry, SectionGlyph, ToSectionText};
+
+/// Step glyph advances are rounded to by an
+/// [`AdvanceRoundingLayout`](struct.AdvanceRoundingLayout.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AdvanceRounding {
+    /// Keeps the advances as laid out.
+    None,
+    /// Rounds advances to half pixels.
+    HalfPixel,
+    /// Rounds advances to whole pixels, crispest at small sizes.
+    FullPixel,
+}
+
+impl AdvanceRounding {
+    fn step(self) -> Option<f32> {
+        match self {
+            AdvanceRounding::None => None,
+            AdvanceRounding::HalfPixel => Some(0.5),
+            AdvanceRounding::FullPixel => Some(1.0),
+        }
+    }
+}
+
+/// Wraps a layout, rounding the advance from every glyph to the next one on its line,
+/// and the start of every line, to a pixel step.
+///
+/// At small sizes, glyphs starting at the same subpixel offset look crisper and more
+/// consistent, at the cost of some spacing fidelity. Lines get slightly longer or
+/// shorter, so centered or right aligned lines may be off by a few pixels.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::{AdvanceRounding, AdvanceRoundingLayout};
+/// # use glium_glyph::glyph_brush::{Layout, Section};
+/// # fn main() {
+/// let small_print = Section::default();
+/// let layout = AdvanceRoundingLayout::new(Layout::default(), AdvanceRounding::FullPixel);
+/// # let _ = (small_print, layout);
+/// // glyph_brush.queue_custom_layout(small_print, &layout);
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AdvanceRoundingLayout<L> {
+    layout: L,
+    rounding: AdvanceRounding,
+}
+
+impl<L: GlyphPositioner> AdvanceRoundingLayout<L> {
+    pub fn new(layout: L, rounding: AdvanceRounding) -> Self {
+        AdvanceRoundingLayout { layout, rounding }
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for AdvanceRoundingLayout<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs = self.layout.calculate_glyphs(fonts, geometry, sections);
+        let step = match self.rounding.step() {
+            Some(step) => step,
+            None => return glyphs,
+        };
+        let round = |x: f32| (x / step).round() * step;
+
+        // glyphs come line by line, the glyphs of a line share their baseline,
+        // `(baseline, laid out x, rounded x)` of the previous glyph
+        let mut previous: Option<(f32, f32, f32)> = None;
+        for g in &mut glyphs {
+            let (x, baseline) = (g.glyph.position.x, g.glyph.position.y);
+            g.glyph.position.x = match previous {
+                Some((b, laid_out, rounded)) if b == baseline => rounded + round(x - laid_out),
+                _ => round(x),
+            };
+            previous = Some((baseline, x, g.glyph.position.x));
+        }
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
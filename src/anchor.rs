@@ -0,0 +1,132 @@
+use super::*;
+
+use std::hash::Hasher;
+
+use glyph_brush::ab_glyph::{Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, HorizontalAlign, Layout, SectionGeometry, SectionGlyph, ToSectionText,
+    VerticalAlign,
+};
+
+/// Layout for text anchored at its bottom edge that grows upwards as lines are added,
+/// e.g. a chat log in a screen corner.
+///
+/// The section's `screen_position` is the anchor: the bottom right corner of the text
+/// by default, see [`h_align`](#method.h_align). Lines wrap at the section's width
+/// bound. With a [`max_height`](#method.max_height), the oldest lines at the top that
+/// don't fit are dropped.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::BottomAnchoredLayout;
+/// # use glium_glyph::glyph_brush::Section;
+/// # fn main() {
+/// # let (width, height) = (800.0, 600.0);
+/// let chat = Section::default().with_screen_position((width - 10.0, height - 10.0));
+/// let layout = BottomAnchoredLayout::default().max_height(200.0);
+/// # let _ = (chat, layout);
+/// // glyph_brush.queue_custom_layout(chat, &layout);
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BottomAnchoredLayout {
+    line_breaker: BuiltInLineBreaker,
+    h_align: HorizontalAlign,
+    max_height: Option<f32>,
+}
+
+impl Default for BottomAnchoredLayout {
+    fn default() -> Self {
+        BottomAnchoredLayout {
+            line_breaker: BuiltInLineBreaker::default(),
+            h_align: HorizontalAlign::Right,
+            max_height: None,
+        }
+    }
+}
+
+impl BottomAnchoredLayout {
+    /// Sets which side of the anchor lines are aligned to.
+    ///
+    /// Defaults to `HorizontalAlign::Right`
+    pub fn h_align(mut self, h_align: HorizontalAlign) -> Self {
+        self.h_align = h_align;
+        self
+    }
+
+    pub fn line_breaker(mut self, line_breaker: BuiltInLineBreaker) -> Self {
+        self.line_breaker = line_breaker;
+        self
+    }
+
+    /// Limits the height of the text above the anchor, dropping whole lines
+    /// starting with the oldest one at the top.
+    ///
+    /// Defaults to no limit.
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    fn layout(&self) -> Layout<BuiltInLineBreaker> {
+        Layout::Wrap {
+            line_breaker: self.line_breaker,
+            h_align: self.h_align,
+            v_align: VerticalAlign::Bottom,
+        }
+    }
+}
+
+impl Hash for BottomAnchoredLayout {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.line_breaker.hash(state);
+        self.h_align.hash(state);
+        self.max_height.map(f32::to_bits).hash(state);
+    }
+}
+
+impl GlyphPositioner for BottomAnchoredLayout {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs = self.layout().calculate_glyphs(fonts, geometry, sections);
+        let max_height = match self.max_height {
+            Some(max_height) => max_height,
+            None => return glyphs,
+        };
+
+        // Glyphs of a line share their baseline, a line is dropped if
+        // its tallest glyph reaches above the limit.
+        let ascent = |g: &SectionGlyph| fonts[g.font_id.0].as_scaled(g.glyph.scale).ascent();
+        let mut line_tops: Vec<(f32, f32)> = Vec::new();
+        for g in &glyphs {
+            let (baseline, top) = (g.glyph.position.y, g.glyph.position.y - ascent(g));
+            match line_tops.iter_mut().find(|(b, _)| *b == baseline) {
+                Some((_, line_top)) => *line_top = line_top.min(top),
+                None => line_tops.push((baseline, top)),
+            }
+        }
+        let min_y = geometry.screen_position.1 - max_height;
+        glyphs.retain(|g| {
+            line_tops
+                .iter()
+                .any(|&(b, top)| b == g.glyph.position.y && top >= min_y)
+        });
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        let mut rect = self.layout().bounds_rect(geometry);
+        if let Some(max_height) = self.max_height {
+            rect.min.y = rect.min.y.max(geometry.screen_position.1 - max_height);
+        }
+        rect
+    }
+}
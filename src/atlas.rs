@@ -0,0 +1,233 @@
+/// Packs rectangles into the RGBA atlas of color glyphs, see
+/// [`GlyphBrushBuilder::color_atlas_allocator`](struct.GlyphBrushBuilder.html#method.color_atlas_allocator).
+///
+/// Only covers the color atlas: outline glyphs are packed into the main cache texture
+/// by the glyph_brush draw cache, which always uses rows and can't be replaced.
+///
+/// Rectangles are allocated from largest to smallest height. If one doesn't fit, the
+/// atlas is packed again from scratch with twice the height.
+pub trait AtlasAllocator {
+    /// Starts packing a new, empty atlas of `width` by `height` texels.
+    fn reset(&mut self, width: u32, height: u32);
+
+    /// Reserves a `width` by `height` rectangle, returning its left top corner
+    /// or `None` if it doesn't fit.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)>;
+}
+
+/// Packs rectangles left to right into rows as high as their first rectangle.
+///
+/// Fast and tight for rectangles of similar height, e.g. the glyphs of one font size.
+#[derive(Clone, Debug, Default)]
+pub struct ShelfAllocator {
+    size: (u32, u32),
+    x: u32,
+    y: u32,
+    row_height: u32,
+}
+
+impl AtlasAllocator for ShelfAllocator {
+    fn reset(&mut self, width: u32, height: u32) {
+        *self = ShelfAllocator {
+            size: (width, height),
+            ..ShelfAllocator::default()
+        };
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if self.x + width > self.size.0 {
+            self.x = 0;
+            self.y += self.row_height;
+            self.row_height = 0;
+        }
+        if width > self.size.0 || self.y + height > self.size.1 {
+            return None;
+        }
+        let position = (self.x, self.y);
+        self.x += width;
+        self.row_height = self.row_height.max(height);
+        Some(position)
+    }
+}
+
+/// Tracks the top edge of the packed rectangles and puts every rectangle where it
+/// ends up lowest, leftmost on ties.
+///
+/// Wastes less space than shelves for rectangles of varying heights.
+#[derive(Clone, Debug, Default)]
+pub struct SkylineAllocator {
+    size: (u32, u32),
+    /// `(x, y, width)` segments of the skyline, left to right
+    skyline: Vec<(u32, u32, u32)>,
+}
+
+impl SkylineAllocator {
+    /// Top of a `width` wide rectangle put at segment `i`, `None` if it sticks out.
+    fn fit(&self, i: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.skyline[i].0;
+        if x + width > self.size.0 {
+            return None;
+        }
+        let mut y = 0;
+        let mut covered = 0;
+        for &(_, segment_y, segment_width) in &self.skyline[i..] {
+            if covered >= width {
+                break;
+            }
+            y = y.max(segment_y);
+            covered += segment_width;
+        }
+        if y + height > self.size.1 {
+            return None;
+        }
+        Some(y)
+    }
+}
+
+impl AtlasAllocator for SkylineAllocator {
+    fn reset(&mut self, width: u32, height: u32) {
+        self.size = (width, height);
+        self.skyline.clear();
+        self.skyline.push((0, 0, width));
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (i, y) = (0..self.skyline.len())
+            .filter_map(|i| Some((i, self.fit(i, width, height)?)))
+            .min_by_key(|&(i, y)| (y, self.skyline[i].0))?;
+        let x = self.skyline[i].0;
+
+        // replace the covered segments with the new top edge
+        let end = x + width;
+        let mut j = i;
+        while j < self.skyline.len() && self.skyline[j].0 < end {
+            let (segment_x, segment_y, segment_width) = self.skyline[j];
+            if segment_x + segment_width > end {
+                self.skyline[j] = (end, segment_y, segment_x + segment_width - end);
+                break;
+            }
+            j += 1;
+        }
+        self.skyline.splice(i..j, Some((x, y + height, width)));
+        // merge neighbours of the same height
+        self.skyline.dedup_by(|next, previous| {
+            let merge = previous.1 == next.1;
+            if merge {
+                previous.2 += next.2;
+            }
+            merge
+        });
+        Some((x, y))
+    }
+}
+
+/// Keeps a list of free rectangles, putting every rectangle into the free one it
+/// fills best and splitting the rest of that one in two.
+///
+/// Packs few large rectangles of very different sizes well.
+#[derive(Clone, Debug, Default)]
+pub struct GuillotineAllocator {
+    /// `(x, y, width, height)` of the free rectangles
+    free: Vec<(u32, u32, u32, u32)>,
+}
+
+impl AtlasAllocator for GuillotineAllocator {
+    fn reset(&mut self, width: u32, height: u32) {
+        self.free.clear();
+        self.free.push((0, 0, width, height));
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let i = (0..self.free.len())
+            .filter(|&i| self.free[i].2 >= width && self.free[i].3 >= height)
+            .min_by_key(|&i| self.free[i].2 as u64 * self.free[i].3 as u64)?;
+        let (x, y, free_width, free_height) = self.free.swap_remove(i);
+        let (right, below) = (free_width - width, free_height - height);
+        // split along the shorter leftover side, keeping the larger free rectangle whole
+        if right < below {
+            self.free.push((x + width, y, right, height));
+            self.free.push((x, y + height, free_width, below));
+        } else {
+            self.free.push((x + width, y, right, free_height));
+            self.free.push((x, y + height, width, below));
+        }
+        self.free.retain(|&(_, _, w, h)| w > 0 && h > 0);
+        Some((x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packs rectangles of `sizes` into a `size` square, checking that they lie within
+    /// it and don't overlap.
+    fn pack<A: AtlasAllocator>(mut allocator: A, size: u32, sizes: &[(u32, u32)]) -> bool {
+        allocator.reset(size, size);
+        let mut packed: Vec<(u32, u32, u32, u32)> = Vec::new();
+        for &(width, height) in sizes {
+            let (x, y) = match allocator.allocate(width, height) {
+                Some(position) => position,
+                None => return false,
+            };
+            assert!(x + width <= size && y + height <= size);
+            for &(px, py, pw, ph) in &packed {
+                let apart = x >= px + pw || px >= x + width || y >= py + ph || py >= y + height;
+                assert!(apart, "({}, {}) overlaps ({}, {})", x, y, px, py);
+            }
+            packed.push((x, y, width, height));
+        }
+        true
+    }
+
+    fn glyph_sizes() -> Vec<(u32, u32)> {
+        (0..40).map(|i| (4 + i % 7 * 3, 20 - i % 5 * 3)).collect()
+    }
+
+    #[test]
+    fn shelf() {
+        assert!(pack(ShelfAllocator::default(), 128, &glyph_sizes()));
+        assert!(pack(
+            ShelfAllocator::default(),
+            8,
+            &[(4, 4), (4, 4), (8, 4)]
+        ));
+        assert!(!pack(
+            ShelfAllocator::default(),
+            8,
+            &[(4, 4), (4, 4), (8, 4), (1, 1)]
+        ));
+    }
+
+    #[test]
+    fn skyline() {
+        assert!(pack(SkylineAllocator::default(), 128, &glyph_sizes()));
+        // fills the gap next to a tall rectangle
+        let mut allocator = SkylineAllocator::default();
+        allocator.reset(8, 8);
+        assert_eq!(allocator.allocate(4, 8), Some((0, 0)));
+        assert_eq!(allocator.allocate(4, 2), Some((4, 0)));
+        assert_eq!(allocator.allocate(4, 6), Some((4, 2)));
+        assert_eq!(allocator.allocate(1, 1), None);
+    }
+
+    #[test]
+    fn guillotine() {
+        assert!(pack(GuillotineAllocator::default(), 128, &glyph_sizes()));
+        assert!(pack(
+            GuillotineAllocator::default(),
+            8,
+            &[(8, 4), (4, 4), (4, 4)]
+        ));
+        assert!(!pack(GuillotineAllocator::default(), 8, &[(9, 1)]));
+    }
+
+    #[test]
+    fn reset_forgets_rectangles() {
+        let mut allocator = SkylineAllocator::default();
+        allocator.reset(4, 4);
+        assert_eq!(allocator.allocate(4, 4), Some((0, 0)));
+        allocator.reset(4, 4);
+        assert_eq!(allocator.allocate(4, 4), Some((0, 0)));
+    }
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter};
+
+#[derive(Copy, Clone, Debug)]
+struct DebugVertex {
+    position: [f32; 2],
+    tex_pos: [f32; 2],
+}
+
+implement_vertex!(DebugVertex, position, tex_pos);
+
+/// Regions of the cache texture written since it was last cleared.
+#[derive(Default)]
+pub(crate) struct CacheRegions {
+    regions: Vec<Rectangle<u32>>,
+}
+
+impl CacheRegions {
+    /// Records a newly written region, dropping the evicted ones it overwrites.
+    pub(crate) fn track(&mut self, rect: Rectangle<u32>) {
+        let overlaps = |r: &Rectangle<u32>| {
+            r.min[0] < rect.max[0]
+                && rect.min[0] < r.max[0]
+                && r.min[1] < rect.max[1]
+                && rect.min[1] < r.max[1]
+        };
+        self.regions.retain(|r| !overlaps(r));
+        self.regions.push(rect);
+    }
+
+    /// Forgets all regions, for when the cache was cleared.
+    pub(crate) fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    /// Number of texels covered by the regions.
+    pub(crate) fn area(&self) -> u64 {
+        self.regions
+            .iter()
+            .map(|r| r.width() as u64 * r.height() as u64)
+            .sum()
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Draws the glyph cache texture into `rect` of `surface`, in pixels from the left
+    /// bottom, e.g. to diagnose cache thrashing or pick an initial cache size. Coverage is
+    /// shown from black to white.
+    ///
+    /// With `regions` set, the glyphs written into the texture are outlined in green.
+    pub fn draw_atlas_debug<C: Facade, S: Surface>(
+        &mut self,
+        facade: &C,
+        surface: &mut S,
+        rect: glium::Rect,
+        regions: bool,
+    ) -> Result<(), DrawError> {
+        if self.atlas_debug.is_none() {
+            static VERTEX_SHADER: &str = include_str!("shader/vert_debug.glsl");
+            static FRAGMENT_SHADER: &str = include_str!("shader/frag_debug.glsl");
+            let program = Program::from_source(facade, VERTEX_SHADER, FRAGMENT_SHADER, None)?;
+            self.atlas_debug = Some(program);
+        }
+        let program = self.atlas_debug.as_ref().unwrap();
+
+        let (left, bottom) = (rect.left as f32, rect.bottom as f32);
+        let (width, height) = (rect.width as f32, rect.height as f32);
+        let vertex = |x: f32, y: f32| DebugVertex {
+            // texture rows go down the screen like the glyphs
+            position: [left + x * width, bottom + (1.0 - y) * height],
+            tex_pos: [x, y],
+        };
+        let quad = [
+            vertex(0.0, 0.0),
+            vertex(1.0, 0.0),
+            vertex(0.0, 1.0),
+            vertex(1.0, 1.0),
+        ];
+        let transform = screen_transform(surface.get_dimensions());
+        let sampler = glium::uniforms::Sampler::new(&*self.texture)
+            .minify_filter(MinifySamplerFilter::Linear)
+            .magnify_filter(MagnifySamplerFilter::Nearest);
+        let params = glium::DrawParameters::default();
+        surface.draw(
+            &glium::VertexBuffer::new(facade, &quad)?,
+            glium::index::NoIndices(PrimitiveType::TriangleStrip),
+            program,
+            &uniform! { font_tex: sampler, transform: transform, outline: false },
+            &params,
+        )?;
+
+        if regions && !self.cache_regions.regions.is_empty() {
+            let (tex_width, tex_height) = self.texture.dimensions();
+            let (tex_width, tex_height) = (tex_width as f32, tex_height as f32);
+            let lines: Vec<DebugVertex> = self
+                .cache_regions
+                .regions
+                .iter()
+                .flat_map(|r| {
+                    let (x0, y0) = (r.min[0] as f32 / tex_width, r.min[1] as f32 / tex_height);
+                    let (x1, y1) = (r.max[0] as f32 / tex_width, r.max[1] as f32 / tex_height);
+                    let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+                    (0..4).flat_map(move |i| {
+                        let ((ax, ay), (bx, by)) = (corners[i], corners[(i + 1) % 4]);
+                        vec![vertex(ax, ay), vertex(bx, by)]
+                    })
+                })
+                .collect();
+            surface.draw(
+                &glium::VertexBuffer::new(facade, &lines)?,
+                glium::index::NoIndices(PrimitiveType::LinesList),
+                program,
+                &uniform! { font_tex: sampler, transform: transform, outline: true },
+                &params,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+
+use glyph_brush::{HorizontalAlign, Layout, Text, VerticalAlign};
+
+/// Tick labels of a horizontal chart axis, placed so that they don't overlap.
+///
+/// Labels are centered below their tick. If neighbouring labels would overlap,
+/// only every n-th label is kept, or, if allowed and fewer labels need to be
+/// skipped that way, the labels are rotated to read upwards.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::AxisLabels;
+/// # use glium_glyph::glyph_brush::Text;
+/// # fn main() {
+/// let years: Vec<String> = (1990..2030).map(|y| y.to_string()).collect();
+/// let mut labels = AxisLabels::new().allow_rotation(true);
+/// for (i, year) in years.iter().enumerate() {
+///     labels = labels.label(50.0 + i as f32 * 15.0, Text::new(year));
+/// }
+/// // let placement = labels.place(&mut glyph_brush, 400.0);
+/// // for section in placement.sections {
+/// //     glyph_brush.queue(section);
+/// // }
+/// // glyph_brush.draw_queued_with_transform(
+/// //     placement.transform(display.get_framebuffer_dimensions()), &display, &mut target)?;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AxisLabels<'a> {
+    labels: Vec<(f32, Text<'a>)>,
+    spacing: f32,
+    allow_rotation: bool,
+}
+
+/// Labels chosen by [`AxisLabels::place`](struct.AxisLabels.html#method.place).
+#[derive(Clone, Debug)]
+pub struct AxisLabelPlacement<'a> {
+    /// Every `skip`-th label is kept, starting with the first.
+    pub skip: usize,
+    /// Whether the labels are rotated to read upwards.
+    pub rotated: bool,
+    /// One section per kept label. Rotated labels are positioned in a rotated
+    /// coordinate system, see [`transform`](#method.transform).
+    pub sections: Vec<Section<'a>>,
+}
+
+impl<'a> AxisLabelPlacement<'a> {
+    /// Returns the transform to draw the label sections with, for a render target
+    /// of the given size, e.g. with
+    /// [`GlyphBrush::draw_queued_with_transform`](struct.GlyphBrush.html#method.draw_queued_with_transform).
+    ///
+    /// Rotated labels have to be drawn separately from other text using this transform.
+    pub fn transform(&self, dimensions: (u32, u32)) -> [[f32; 4]; 4] {
+        let transform = screen_transform(dimensions);
+        if !self.rotated {
+            return transform;
+        }
+        // Maps the section coordinates (u, v) to pixels at (v, -u).
+        let negated = |c: [f32; 4]| [-c[0], -c[1], -c[2], -c[3]];
+        [
+            negated(transform[1]),
+            transform[0],
+            transform[2],
+            transform[3],
+        ]
+    }
+}
+
+impl<'a> AxisLabels<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a label for the tick at the horizontal pixel position `x`.
+    ///
+    /// Labels have to be added in order of their position.
+    pub fn label(mut self, x: f32, text: Text<'a>) -> Self {
+        self.labels.push((x, text));
+        self
+    }
+
+    /// Sets the minimum space between labels in pixels.
+    ///
+    /// Defaults to 0.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Allows rotating labels instead of skipping them.
+    ///
+    /// Defaults to `false`.
+    pub fn allow_rotation(mut self, allow_rotation: bool) -> Self {
+        self.allow_rotation = allow_rotation;
+        self
+    }
+
+    /// Returns the smallest skip factor at which labels of the given extents around
+    /// their ticks don't overlap.
+    fn skip_factor(&self, extents: &[f32]) -> usize {
+        (1..self.labels.len().max(1))
+            .find(|&skip| {
+                let kept: Vec<usize> = (0..self.labels.len()).step_by(skip).collect();
+                kept.windows(2).all(|pair| {
+                    let (a, b) = (pair[0], pair[1]);
+                    let gap = self.labels[b].0 - self.labels[a].0;
+                    gap >= (extents[a] + extents[b]) / 2.0 + self.spacing
+                })
+            })
+            .unwrap_or_else(|| self.labels.len().max(1))
+    }
+
+    /// Measures the labels and picks the ones to show below the axis at `y`.
+    pub fn place<F: Font, G: GlyphCruncher<F>>(
+        &self,
+        cruncher: &mut G,
+        y: f32,
+    ) -> AxisLabelPlacement<'a> {
+        let (widths, heights): (Vec<f32>, Vec<f32>) = self
+            .labels
+            .iter()
+            .map(|(_, text)| {
+                let section = Section::default().add_text(*text);
+                cruncher
+                    .glyph_bounds(section)
+                    .map_or((0.0, 0.0), |b| (b.width(), b.height()))
+            })
+            .unzip();
+
+        let skip = self.skip_factor(&widths);
+        let rotated_skip = self.skip_factor(&heights);
+        let rotated = self.allow_rotation && rotated_skip < skip;
+        let skip = if rotated { rotated_skip } else { skip };
+
+        let sections = self
+            .labels
+            .iter()
+            .step_by(skip)
+            .map(|&(x, text)| {
+                let section = Section::default().add_text(text);
+                if rotated {
+                    // ends at the axis, reading upwards
+                    section.with_screen_position((-y, x)).with_layout(
+                        Layout::default_single_line()
+                            .h_align(HorizontalAlign::Right)
+                            .v_align(VerticalAlign::Center),
+                    )
+                } else {
+                    section
+                        .with_screen_position((x, y))
+                        .with_layout(Layout::default_single_line().h_align(HorizontalAlign::Center))
+                }
+            })
+            .collect();
+        AxisLabelPlacement {
+            skip,
+            rotated,
+            sections,
+        }
+    }
+}
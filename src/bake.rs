@@ -0,0 +1,716 @@
+use super::*;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "baked-glyphs")]
+use glyph_brush::ab_glyph::{GlyphId, PxScale, Rect};
+#[cfg(feature = "baked-glyphs")]
+use glyph_brush::SectionGeometry;
+
+#[cfg(feature = "atlas-files")]
+const MAGIC: &[u8; 8] = b"GGATLAS\0";
+#[cfg(feature = "atlas-files")]
+const FORMAT_VERSION: u32 = 1;
+
+/// What a baked quad belongs to.
+#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+enum Baked {
+    /// index of a named section
+    Text(u32),
+    /// index of a glyph of a character set
+    #[cfg(feature = "baked-glyphs")]
+    Glyph(u32),
+}
+
+/// Identifies a rasterized glyph, the scale as bits to be hashable.
+#[cfg(feature = "baked-glyphs")]
+type GlyphKey = (FontId, GlyphId, (u32, u32));
+
+#[cfg(feature = "baked-glyphs")]
+fn glyph_key(font_id: FontId, glyph_id: GlyphId, scale: PxScale) -> GlyphKey {
+    (font_id, glyph_id, (scale.x.to_bits(), scale.y.to_bits()))
+}
+
+/// Where a glyph of a character set is in the atlas texture.
+#[cfg(feature = "baked-glyphs")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct AtlasGlyph {
+    /// glyph bitmap relative to the glyph origin at a whole pixel, in pixels
+    pixel_coords: Rect,
+    tex_coords: Rect,
+}
+
+/// Lays out and rasterizes a fixed set of named sections into a [`BakedAtlas`].
+///
+/// Applications that only ever draw a fixed set of strings (kiosks, embedded
+/// devices) can lay out and rasterize them once, typically in a build script,
+/// and embed the result in the binary. At runtime a [`BakedBrush`] draws the
+/// baked strings without rasterizing anything, so no font files need to ship.
+///
+/// With the `baked-glyphs` feature, whole character sets can be baked as single glyphs
+/// with [`add_glyphs`](#method.add_glyphs) too, so any text using them can be drawn.
+///
+/// With the `atlas-files` feature, the atlas can be stored as a file:
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::AtlasBaker;
+/// # use glium_glyph::glyph_brush::{ab_glyph::FontArc, Section, Text};
+/// # #[cfg(feature = "atlas-files")]
+/// # fn main() {
+/// # let font: FontArc = unimplemented!();
+/// // build.rs
+/// let mut baker = AtlasBaker::using_font(font);
+/// baker.add("title", Section::default().add_text(Text::new("Welcome").with_scale(48.0)));
+/// let out_dir = std::env::var("OUT_DIR").unwrap();
+/// std::fs::write(format!("{}/text.atlas", out_dir), baker.bake().to_bytes()).unwrap();
+/// # }
+/// # #[cfg(not(feature = "atlas-files"))]
+/// # fn main() {}
+/// ```
+///
+/// and then pulled into the binary with
+/// `include_baked_atlas!(concat!(env!("OUT_DIR"), "/text.atlas"))`.
+pub struct AtlasBaker<F: Font> {
+    glyph_brush: glyph_brush::GlyphBrush<(Baked, GlyphVertex), (Extra, Baked), F>,
+    names: Vec<String>,
+    #[cfg(feature = "baked-glyphs")]
+    glyphs: Vec<GlyphKey>,
+}
+
+impl<F: Font + Sync> AtlasBaker<F> {
+    #[inline]
+    pub fn using_font(font_0: F) -> Self {
+        Self::using_fonts(vec![font_0])
+    }
+
+    pub fn using_fonts<V: Into<Vec<F>>>(fonts: V) -> Self {
+        AtlasBaker {
+            glyph_brush: glyph_brush::GlyphBrushBuilder::using_fonts(fonts.into()).build(),
+            names: Vec::new(),
+            #[cfg(feature = "baked-glyphs")]
+            glyphs: Vec::new(),
+        }
+    }
+
+    /// Adds a section to the atlas, drawn at runtime by referencing `name`.
+    ///
+    /// The section's screen position becomes the offset relative to the position
+    /// passed to [`BakedBrush::queue`]. Returns `false` and doesn't add the section if
+    /// one named `name` was added already.
+    pub fn add<'a, S>(&mut self, name: &str, section: S) -> bool
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        if self.names.iter().any(|n| n == name) {
+            return false;
+        }
+        let section = section.into();
+        let index = Baked::Text(self.names.len() as u32);
+        let text = section
+            .text
+            .iter()
+            .map(|t| t.with_extra((t.extra, index)))
+            .collect();
+        self.glyph_brush.queue(Section {
+            screen_position: section.screen_position,
+            bounds: section.bounds,
+            layout: section.layout,
+            text,
+        });
+        self.names.push(name.to_owned());
+        true
+    }
+
+    /// Adds the glyphs of `chars` in `font_id` at `scale` to the atlas, to draw any text
+    /// using them with [`BakedBrush::queue_text`].
+    #[cfg(feature = "baked-glyphs")]
+    pub fn add_glyphs(&mut self, font_id: FontId, scale: impl Into<PxScale>, chars: &str) {
+        let scale = scale.into();
+        let mut glyphs = Vec::new();
+        let mut extra = Vec::new();
+        for c in chars.chars() {
+            let glyph_id = self.glyph_brush.fonts()[font_id.0].glyph_id(c);
+            let key = glyph_key(font_id, glyph_id, scale);
+            if self.glyphs.contains(&key) {
+                continue;
+            }
+            // rasterized at the origin, runtime positions are rounded to whole pixels
+            glyphs.push(SectionGlyph {
+                section_index: extra.len(),
+                byte_index: 0,
+                glyph: glyph_id.with_scale(scale),
+                font_id,
+            });
+            extra.push((Extra::default(), Baked::Glyph(self.glyphs.len() as u32)));
+            self.glyphs.push(key);
+        }
+        let bounds = Rect {
+            min: point(f32::MIN, f32::MIN),
+            max: point(f32::MAX, f32::MAX),
+        };
+        self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds);
+    }
+
+    /// Rasterizes all added sections.
+    pub fn bake(mut self) -> BakedAtlas {
+        let (mut width, mut height) = self.glyph_brush.texture_dimensions();
+        let mut pixels = vec![0; width as usize * height as usize];
+        let verts = loop {
+            let action = self.glyph_brush.process_queued(
+                |rect, tex_data| {
+                    let row_len = rect.width() as usize;
+                    for (row, y) in (rect.min[1]..rect.max[1]).enumerate() {
+                        let start = (y * width + rect.min[0]) as usize;
+                        pixels[start..start + row_len]
+                            .copy_from_slice(&tex_data[row * row_len..(row + 1) * row_len]);
+                    }
+                },
+                |v| {
+                    let vertex = to_vertex(glyph_brush::GlyphVertex {
+                        tex_coords: v.tex_coords,
+                        pixel_coords: v.pixel_coords,
+                        bounds: v.bounds,
+                        extra: &GlyphExtra::from(v.extra.0),
+                    });
+                    (v.extra.1, vertex)
+                },
+            );
+            match action {
+                Ok(BrushAction::Draw(verts)) => break verts,
+                Ok(BrushAction::ReDraw) => break Vec::new(),
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    width = suggested.0;
+                    height = suggested.1;
+                    pixels = vec![0; width as usize * height as usize];
+                    self.glyph_brush.resize_texture(width, height);
+                }
+            }
+        };
+
+        let mut texts: Vec<(String, Vec<GlyphVertex>)> =
+            self.names.into_iter().map(|n| (n, Vec::new())).collect();
+        #[cfg(feature = "baked-glyphs")]
+        let mut glyphs = HashMap::new();
+        for (baked, vertex) in verts {
+            match baked {
+                Baked::Text(index) => texts[index as usize].1.push(vertex),
+                #[cfg(feature = "baked-glyphs")]
+                Baked::Glyph(index) => {
+                    let glyph = AtlasGlyph {
+                        pixel_coords: Rect {
+                            min: point(vertex.left_top[0], vertex.left_top[1]),
+                            max: point(vertex.right_bottom[0], vertex.right_bottom[1]),
+                        },
+                        tex_coords: Rect {
+                            min: point(vertex.tex_left_top[0], vertex.tex_left_top[1]),
+                            max: point(vertex.tex_right_bottom[0], vertex.tex_right_bottom[1]),
+                        },
+                    };
+                    glyphs.insert(self.glyphs[index as usize], glyph);
+                }
+            }
+        }
+
+        BakedAtlas {
+            width,
+            height,
+            pixels,
+            texts,
+            #[cfg(feature = "baked-glyphs")]
+            glyphs,
+        }
+    }
+}
+
+/// Cache texture contents plus the positioned glyph quads of every baked section and
+/// the position of every glyph of baked character sets.
+///
+/// Created by an [`AtlasBaker`]. With the `atlas-files` feature, stored with
+/// [`to_bytes`](#method.to_bytes) and loaded again with
+/// [`from_bytes`](#method.from_bytes) or `include_baked_atlas!`.
+#[derive(Clone, Debug)]
+pub struct BakedAtlas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    texts: Vec<(String, Vec<GlyphVertex>)>,
+    #[cfg(feature = "baked-glyphs")]
+    glyphs: HashMap<GlyphKey, AtlasGlyph>,
+}
+
+impl BakedAtlas {
+    /// Returns the texture dimensions `(width, height)` of the atlas.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the coverage of the atlas texture, one byte per texel, rows ordered
+    /// top to bottom, e.g. to save it as an image for inspection.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the names of all baked sections.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.texts.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns whether the atlas holds the glyph `glyph_id` of `font_id` at `scale` of a
+    /// baked character set.
+    #[cfg(feature = "baked-glyphs")]
+    pub fn contains(&self, font_id: FontId, glyph_id: GlyphId, scale: PxScale) -> bool {
+        self.glyphs
+            .contains_key(&glyph_key(font_id, glyph_id, scale))
+    }
+
+    /// Serializes the atlas into a compact binary representation.
+    ///
+    /// The same atlas always serializes to the same bytes.
+    #[cfg(feature = "atlas-files")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.pixels.len() + 64);
+        out.extend_from_slice(MAGIC);
+        put_u32(&mut out, FORMAT_VERSION);
+        put_u32(&mut out, self.width);
+        put_u32(&mut out, self.height);
+        out.extend_from_slice(&self.pixels);
+        put_u32(&mut out, self.texts.len() as u32);
+        for (name, verts) in &self.texts {
+            put_u32(&mut out, name.len() as u32);
+            out.extend_from_slice(name.as_bytes());
+            put_u32(&mut out, verts.len() as u32);
+            for v in verts {
+                let fields = v
+                    .left_top
+                    .iter()
+                    .chain(&v.right_bottom)
+                    .chain(&v.tex_left_top)
+                    .chain(&v.tex_right_bottom)
+                    .chain(&v.color);
+                for f in fields {
+                    put_u32(&mut out, f.to_bits());
+                }
+            }
+        }
+        // character sets go last, so that atlases without them parse either way
+        #[cfg(feature = "baked-glyphs")]
+        {
+            let mut glyphs: Vec<_> = self.glyphs.iter().collect();
+            glyphs.sort_by_key(|&(key, _)| *key);
+            put_u32(&mut out, glyphs.len() as u32);
+            for (&(font_id, glyph_id, (scale_x, scale_y)), glyph) in glyphs {
+                for &f in &[font_id.0 as u32, u32::from(glyph_id.0), scale_x, scale_y] {
+                    put_u32(&mut out, f);
+                }
+                let (pixel, tex) = (glyph.pixel_coords, glyph.tex_coords);
+                let fields = [
+                    pixel.min.x,
+                    pixel.min.y,
+                    pixel.max.x,
+                    pixel.max.y,
+                    tex.min.x,
+                    tex.min.y,
+                    tex.max.x,
+                    tex.max.y,
+                ];
+                for f in &fields {
+                    put_u32(&mut out, f.to_bits());
+                }
+            }
+        }
+        out
+    }
+
+    /// Parses an atlas previously serialized with [`to_bytes`](#method.to_bytes).
+    #[cfg(feature = "atlas-files")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AtlasParseError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(AtlasParseError::InvalidHeader);
+        }
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(AtlasParseError::UnsupportedVersion(version));
+        }
+        let width = reader.u32()?;
+        let height = reader.u32()?;
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or(AtlasParseError::UnexpectedEnd)?;
+        let pixels = reader.take(len)?.to_vec();
+        let text_count = reader.u32()?;
+        let mut texts = Vec::new();
+        for _ in 0..text_count {
+            let name_len = reader.u32()? as usize;
+            let name = std::str::from_utf8(reader.take(name_len)?)
+                .map_err(|_| AtlasParseError::InvalidName)?
+                .to_owned();
+            if texts.iter().any(|(n, _)| *n == name) {
+                return Err(AtlasParseError::DuplicateName);
+            }
+            let vert_count = reader.u32()?;
+            let mut verts = Vec::new();
+            for _ in 0..vert_count {
+                let mut f = [0.0; 13];
+                for f in f.iter_mut() {
+                    *f = f32::from_bits(reader.u32()?);
+                }
+                verts.push(GlyphVertex {
+                    left_top: [f[0], f[1], f[2]],
+                    right_bottom: [f[3], f[4]],
+                    tex_left_top: [f[5], f[6]],
+                    tex_right_bottom: [f[7], f[8]],
+                    color: [f[9], f[10], f[11], f[12]],
+                    transform_x: GlyphTransform::IDENTITY.0[0],
+                    transform_y: GlyphTransform::IDENTITY.0[1],
+                    fill_x: [0.0; 3],
+                    fill_y: [0.0; 3],
+                    procedural: [0.0; 4],
+                    procedural_color: [0.0; 4],
+                    hollow: 0.0,
+                });
+            }
+            texts.push((name, verts));
+        }
+        #[cfg(feature = "baked-glyphs")]
+        let mut glyphs = HashMap::new();
+        #[cfg(feature = "baked-glyphs")]
+        {
+            // written without the feature
+            let glyph_count = if reader.bytes.is_empty() {
+                0
+            } else {
+                reader.u32()?
+            };
+            for _ in 0..glyph_count {
+                let mut k = [0; 4];
+                for k in k.iter_mut() {
+                    *k = reader.u32()?;
+                }
+                let mut f = [0.0; 8];
+                for f in f.iter_mut() {
+                    *f = f32::from_bits(reader.u32()?);
+                }
+                let key = (FontId(k[0] as usize), GlyphId(k[1] as u16), (k[2], k[3]));
+                let glyph = AtlasGlyph {
+                    pixel_coords: Rect {
+                        min: point(f[0], f[1]),
+                        max: point(f[2], f[3]),
+                    },
+                    tex_coords: Rect {
+                        min: point(f[4], f[5]),
+                        max: point(f[6], f[7]),
+                    },
+                };
+                glyphs.insert(key, glyph);
+            }
+        }
+        Ok(BakedAtlas {
+            width,
+            height,
+            pixels,
+            texts,
+            #[cfg(feature = "baked-glyphs")]
+            glyphs,
+        })
+    }
+}
+
+pub(crate) fn put_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], AtlasParseError> {
+        if self.bytes.len() < len {
+            return Err(AtlasParseError::UnexpectedEnd);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, AtlasParseError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+/// Error returned when loading a [`BakedAtlas`] or a [`LayoutSnapshot`] from bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AtlasParseError {
+    /// The data doesn't start with the expected header.
+    InvalidHeader,
+    /// The data was written by an incompatible version of this crate.
+    UnsupportedVersion(u32),
+    /// A section name isn't valid UTF-8.
+    InvalidName,
+    /// Two sections have the same name.
+    DuplicateName,
+    /// The data ended prematurely.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for AtlasParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AtlasParseError::InvalidHeader => write!(f, "unrecognized header"),
+            AtlasParseError::UnsupportedVersion(v) => {
+                write!(f, "unsupported format version {}", v)
+            }
+            AtlasParseError::InvalidName => write!(f, "section name is not valid UTF-8"),
+            AtlasParseError::DuplicateName => write!(f, "section name is not unique"),
+            AtlasParseError::UnexpectedEnd => write!(f, "data is truncated"),
+        }
+    }
+}
+
+impl Error for AtlasParseError {}
+
+/// Includes a baked atlas file in the binary, evaluating to a
+/// `Result<BakedAtlas, AtlasParseError>`.
+#[cfg(feature = "atlas-files")]
+#[macro_export]
+macro_rules! include_baked_atlas {
+    ($path:expr) => {
+        $crate::BakedAtlas::from_bytes(include_bytes!($path))
+    };
+}
+
+/// Draws sections of a [`BakedAtlas`] without any font or rasterization at runtime.
+pub struct BakedBrush {
+    texts: HashMap<String, Vec<GlyphVertex>>,
+    #[cfg(feature = "baked-glyphs")]
+    glyphs: HashMap<GlyphKey, AtlasGlyph>,
+    queued: Vec<GlyphVertex>,
+    params: glium::DrawParameters<'static>,
+    program: Program,
+    texture: Texture2d,
+    quads: QuadBuffer,
+}
+
+impl BakedBrush {
+    /// Uploads the atlas texture and creates the GPU resources for drawing.
+    pub fn new<C: Facade>(facade: &C, atlas: &BakedAtlas) -> Result<Self, BuildError> {
+        let texture = Texture2d::empty(facade, atlas.width, atlas.height)?;
+        update_texture(
+            &texture,
+            Rectangle {
+                min: [0, 0],
+                max: [atlas.width, atlas.height],
+            },
+            &atlas.pixels,
+        );
+        Ok(BakedBrush {
+            texts: atlas.texts.iter().cloned().collect(),
+            #[cfg(feature = "baked-glyphs")]
+            glyphs: atlas.glyphs.clone(),
+            queued: Vec::new(),
+            params: glium::DrawParameters {
+                blend: glium::Blend::alpha_blending(),
+                ..Default::default()
+            },
+            program: create_program(facade, RasterMode::AlphaMask)?,
+            texture,
+            quads: QuadBuffer::empty(facade, true)?,
+        })
+    }
+
+    pub fn params(mut self, params: glium::DrawParameters<'static>) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Queues the baked section `name` to be drawn at `position`.
+    ///
+    /// Returns `false` if the atlas contains no section with that name.
+    pub fn queue(&mut self, name: &str, (x, y): (f32, f32)) -> bool {
+        let verts = match self.texts.get(name) {
+            Some(verts) => verts,
+            None => return false,
+        };
+        self.queued.extend(verts.iter().map(|v| GlyphVertex {
+            left_top: [v.left_top[0] + x, v.left_top[1] + y, v.left_top[2]],
+            right_bottom: [v.right_bottom[0] + x, v.right_bottom[1] + y],
+            ..*v
+        }));
+        true
+    }
+
+    /// Lays out a section with the fonts the atlas was baked from and queues it to be
+    /// drawn from the glyphs of baked character sets.
+    ///
+    /// `fonts` are indexed by the `FontId`s used in the atlas and the section. Glyphs are
+    /// placed at whole pixel positions, glyphs missing from the atlas, e.g. other
+    /// characters or scales, are skipped.
+    #[cfg(feature = "baked-glyphs")]
+    pub fn queue_text<'s, F, S>(&mut self, fonts: &[F], section: S)
+    where
+        F: Font,
+        S: Into<Cow<'s, Section<'s>>>,
+    {
+        let section = section.into();
+        let geometry = SectionGeometry::from(section.as_ref());
+        let bounds = section.layout.bounds_rect(&geometry);
+        let glyphs = section
+            .layout
+            .calculate_glyphs(fonts, &geometry, &section.text);
+        for section_glyph in glyphs {
+            let glyph = &section_glyph.glyph;
+            let key = glyph_key(section_glyph.font_id, glyph.id, glyph.scale);
+            let atlas_glyph = match self.glyphs.get(&key) {
+                Some(atlas_glyph) => atlas_glyph,
+                None => continue,
+            };
+            let origin = point(glyph.position.x.round(), glyph.position.y.round());
+            let pixel_coords = Rect {
+                min: origin + atlas_glyph.pixel_coords.min,
+                max: origin + atlas_glyph.pixel_coords.max,
+            };
+            self.queued.push(to_vertex(glyph_brush::GlyphVertex {
+                tex_coords: atlas_glyph.tex_coords,
+                pixel_coords,
+                bounds,
+                extra: &GlyphExtra::from(section.text[section_glyph.section_index].extra),
+            }));
+        }
+    }
+
+    /// Draws all queued sections onto a render target.
+    pub fn draw_queued<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<(), DrawError> {
+        let transform = screen_transform(surface.get_dimensions());
+        self.draw_queued_with_transform(transform, facade, surface)
+    }
+
+    /// Draws all queued sections onto a render target, applying a position transform.
+    pub fn draw_queued_with_transform<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        transform: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<(), DrawError> {
+        let uploaded = self.quads.upload(facade, &self.queued);
+        self.queued.clear();
+        uploaded?;
+        draw_glyphs(
+            surface,
+            &self.program,
+            &self.texture,
+            self.quads.all(),
+            DrawUniforms {
+                transform,
+                gamma: 1.0,
+                texture_filter: (MinifySamplerFilter::Linear, MagnifySamplerFilter::Linear),
+                time: 0.0,
+                opacity: 1.0,
+                tint: NO_TINT,
+                fill_tex: None,
+                user: &[],
+            },
+            &self.params,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::FontArc;
+    use glyph_brush::Text;
+
+    #[cfg(feature = "atlas-files")]
+    fn bake() -> BakedAtlas {
+        let font = FontArc::try_from_slice(include_bytes!("../fonts/DejaVuSans-2.37.ttf"));
+        let mut baker = AtlasBaker::using_font(font.unwrap());
+        assert!(baker.add("title", Section::default().add_text(Text::new("Welcome"))));
+        #[cfg(feature = "baked-glyphs")]
+        baker.add_glyphs(FontId(0), 24.0, "0123456789");
+        baker.bake()
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let font = FontArc::try_from_slice(include_bytes!("../fonts/DejaVuSans-2.37.ttf"));
+        let mut baker = AtlasBaker::using_font(font.unwrap());
+        assert!(baker.add("a", Section::default().add_text(Text::new("first"))));
+        assert!(!baker.add("a", Section::default().add_text(Text::new("second"))));
+        assert_eq!(baker.bake().names().collect::<Vec<_>>(), ["a"]);
+    }
+
+    #[cfg(feature = "atlas-files")]
+    #[test]
+    fn bytes_roundtrip() {
+        let atlas = bake();
+        let bytes = atlas.to_bytes();
+        let parsed = BakedAtlas::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.dimensions(), atlas.dimensions());
+        assert_eq!(parsed.pixels(), atlas.pixels());
+        assert_eq!(parsed.names().collect::<Vec<_>>(), ["title"]);
+        assert_eq!(parsed.to_bytes(), bytes);
+    }
+
+    #[cfg(feature = "atlas-files")]
+    #[test]
+    fn bytes_are_deterministic() {
+        assert_eq!(bake().to_bytes(), bake().to_bytes());
+    }
+
+    #[cfg(all(feature = "baked-glyphs", feature = "atlas-files"))]
+    #[test]
+    fn glyphs_roundtrip() {
+        let atlas = bake();
+        let parsed = BakedAtlas::from_bytes(&atlas.to_bytes()).unwrap();
+        assert_eq!(parsed.glyphs, atlas.glyphs);
+        assert_eq!(parsed.glyphs.len(), 10);
+        let scale = PxScale::from(24.0);
+        let font = FontArc::try_from_slice(include_bytes!("../fonts/DejaVuSans-2.37.ttf"));
+        let font = font.unwrap();
+        assert!(parsed.contains(FontId(0), font.glyph_id('7'), scale));
+        assert!(!parsed.contains(FontId(0), font.glyph_id('A'), scale));
+    }
+
+    #[cfg(feature = "atlas-files")]
+    #[test]
+    fn truncated_bytes() {
+        let bytes = bake().to_bytes();
+        let parsed = BakedAtlas::from_bytes(&bytes[..bytes.len() / 2]);
+        assert_eq!(parsed.unwrap_err(), AtlasParseError::UnexpectedEnd);
+        let parsed = BakedAtlas::from_bytes(b"GGSNAP\0\0");
+        assert_eq!(parsed.unwrap_err(), AtlasParseError::InvalidHeader);
+    }
+
+    #[cfg(feature = "atlas-files")]
+    #[test]
+    fn invalid_bytes() {
+        let mut bytes = MAGIC.to_vec();
+        for &v in &[FORMAT_VERSION, u32::MAX, u32::MAX] {
+            put_u32(&mut bytes, v);
+        }
+        let parsed = BakedAtlas::from_bytes(&bytes);
+        assert_eq!(parsed.unwrap_err(), AtlasParseError::UnexpectedEnd);
+
+        let mut bytes = MAGIC.to_vec();
+        for &v in &[FORMAT_VERSION, 0, 0, 2] {
+            put_u32(&mut bytes, v);
+        }
+        for _ in 0..2 {
+            put_u32(&mut bytes, 1);
+            bytes.push(b'a');
+            put_u32(&mut bytes, 0);
+        }
+        let parsed = BakedAtlas::from_bytes(&bytes);
+        assert_eq!(parsed.unwrap_err(), AtlasParseError::DuplicateName);
+    }
+}
@@ -0,0 +1,117 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, HorizontalAlign, Layout, SectionGeometry, SectionGlyph, ToSectionText,
+    VerticalAlign,
+};
+
+/// Which part of the text a [`BaselineLayout`](struct.BaselineLayout.html) puts at the
+/// section's `screen_position`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BaselineAnchor {
+    /// The baseline of the first line, e.g. to line up a label with other UI text.
+    FirstBaseline,
+    /// The baseline of the last line.
+    LastBaseline,
+    /// The bottom of the last line, its baseline plus the font's descent.
+    LastLineBottom,
+}
+
+/// Layout positioning wrapped text by one of its lines instead of its bounding box.
+///
+/// Lines wrap at the section's width bound and are laid out top to bottom, then moved
+/// vertically so that the [anchor](enum.BaselineAnchor.html) ends up at the section's
+/// `screen_position`. The height bound doesn't clip the text.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::{BaselineAnchor, BaselineLayout};
+/// # use glium_glyph::glyph_brush::Section;
+/// # fn main() {
+/// let label = Section::default().with_screen_position((10.0, 40.0));
+/// let layout = BaselineLayout::new(BaselineAnchor::FirstBaseline);
+/// # let _ = (label, layout);
+/// // glyph_brush.queue_custom_layout(label, &layout);
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BaselineLayout {
+    line_breaker: BuiltInLineBreaker,
+    h_align: HorizontalAlign,
+    anchor: BaselineAnchor,
+}
+
+impl BaselineLayout {
+    pub fn new(anchor: BaselineAnchor) -> Self {
+        BaselineLayout {
+            line_breaker: BuiltInLineBreaker::default(),
+            h_align: HorizontalAlign::Left,
+            anchor,
+        }
+    }
+
+    /// Sets which side of the screen position lines are aligned to.
+    ///
+    /// Defaults to `HorizontalAlign::Left`
+    pub fn h_align(mut self, h_align: HorizontalAlign) -> Self {
+        self.h_align = h_align;
+        self
+    }
+
+    pub fn line_breaker(mut self, line_breaker: BuiltInLineBreaker) -> Self {
+        self.line_breaker = line_breaker;
+        self
+    }
+
+    fn layout(&self) -> Layout<BuiltInLineBreaker> {
+        Layout::Wrap {
+            line_breaker: self.line_breaker,
+            h_align: self.h_align,
+            v_align: VerticalAlign::Top,
+        }
+    }
+}
+
+impl GlyphPositioner for BaselineLayout {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs = self.layout().calculate_glyphs(fonts, geometry, sections);
+        // glyph positions are on their line's baseline
+        let baselines = glyphs.iter().map(|g| g.glyph.position.y);
+        let anchor_y = match self.anchor {
+            BaselineAnchor::FirstBaseline => baselines.fold(f32::INFINITY, f32::min),
+            BaselineAnchor::LastBaseline => baselines.fold(f32::NEG_INFINITY, f32::max),
+            BaselineAnchor::LastLineBottom => glyphs
+                .iter()
+                .map(|g| {
+                    let descent = fonts[g.font_id.0].as_scaled(g.glyph.scale).descent();
+                    g.glyph.position.y - descent
+                })
+                .fold(f32::NEG_INFINITY, f32::max),
+        };
+        if !anchor_y.is_finite() {
+            return glyphs;
+        }
+        let offset = geometry.screen_position.1 - anchor_y;
+        for g in &mut glyphs {
+            g.glyph.position.y += offset;
+        }
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        let mut rect = self.layout().bounds_rect(geometry);
+        rect.min.y = f32::NEG_INFINITY;
+        rect.max.y = f32::INFINITY;
+        rect
+    }
+}
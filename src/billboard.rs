@@ -0,0 +1,113 @@
+use super::*;
+
+use world::{mat_mul, INVERT_Y_AXIS};
+
+/// A label facing the camera at a point in world space, e.g. a nameplate above an
+/// entity or a damage number.
+///
+/// The pixel coordinates of a section drawn as billboard are relative to the anchor
+/// point, so a section centered on `(0, 0)` is centered on it.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::Billboard;
+/// # fn main() {
+/// // keeps its size on screen at any distance
+/// let nameplate = Billboard::screen([3.0, 1.8, -7.0]);
+/// // glyph_brush.draw_billboard(section, &nameplate, view, projection, &display, &mut target)?;
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Billboard {
+    /// Anchor point in world space.
+    pub position: [f32; 3],
+    /// World units per pixel, `None` to keep the text at the same size on screen.
+    pub pixel_size: Option<f32>,
+}
+
+impl Billboard {
+    /// A billboard shrinking with distance like the scene around it, `pixel_size` world
+    /// units per pixel.
+    pub fn world(position: [f32; 3], pixel_size: f32) -> Self {
+        Billboard {
+            position,
+            pixel_size: Some(pixel_size),
+        }
+    }
+
+    /// A billboard staying at the same size on screen, laid out in pixels.
+    pub fn screen(position: [f32; 3]) -> Self {
+        Billboard {
+            position,
+            pixel_size: None,
+        }
+    }
+
+    /// Returns the transform to draw the billboard's sections with, for column major
+    /// `view` and `projection` matrices and a render target of the given size, e.g. with
+    /// [`GlyphBrush::draw_queued_with_transform`](struct.GlyphBrush.html#method.draw_queued_with_transform).
+    ///
+    /// The text is depth tested at the anchor point and hidden behind the camera.
+    pub fn transform(
+        &self,
+        view: [[f32; 4]; 4],
+        projection: [[f32; 4]; 4],
+        (width, height): (u32, u32),
+    ) -> [[f32; 4]; 4] {
+        let [x, y, z] = self.position;
+        match self.pixel_size {
+            Some(pixel_size) => {
+                // the camera's right and up axes in world space
+                let right = [view[0][0], view[1][0], view[2][0]];
+                let up = [view[0][1], view[1][1], view[2][1]];
+                let plane = TextPlane::new(
+                    self.position,
+                    right.map(|v| v * pixel_size),
+                    up.map(|v| -v * pixel_size),
+                );
+                world::world_transform(plane.model(), view, projection)
+            }
+            None => {
+                let m = mat_mul(projection, view);
+                let clip = [0, 1, 2, 3]
+                    .map(|row| m[0][row] * x + m[1][row] * y + m[2][row] * z + m[3][row]);
+                // Offsets are scaled by w to stay the same number of pixels after the
+                // perspective divide.
+                let w = clip[3];
+                let screen = [
+                    [2.0 / width as f32 * w, 0.0, 0.0, 0.0],
+                    [0.0, -2.0 / height as f32 * w, 0.0, 0.0],
+                    [0.0, 0.0, 0.0, 0.0],
+                    [clip[0], clip[1], clip[2], w],
+                ];
+                mat_mul(INVERT_Y_AXIS, screen)
+            }
+        }
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Queues `section` and draws it, along with any sections queued before, as
+    /// `billboard` for column major `view` and `projection` matrices as used by glium.
+    ///
+    /// Set a [depth test](struct.GlyphBrushBuilder.html#method.depth) to have scene
+    /// geometry occlude the text.
+    pub fn draw_billboard<'a, T, C, S>(
+        &mut self,
+        section: T,
+        billboard: &Billboard,
+        view: [[f32; 4]; 4],
+        projection: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError>
+    where
+        T: Into<Cow<'a, Section<'a, X>>>,
+        C: Facade + Deref<Target = Context>,
+        S: Surface,
+    {
+        self.queue(section);
+        let transform = billboard.transform(view, projection, surface.get_dimensions());
+        self.try_draw_queued_with_transform(transform, facade, surface)
+    }
+}
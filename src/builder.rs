@@ -1,6 +1,14 @@
 use super::*;
 use glium::backend::Facade;
 use glium::draw_parameters::DrawParameters;
+use glium::program::ProgramCreationError;
+use glium::texture::TextureCreationError;
+use glium::vertex::BufferCreationError;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+#[cfg(feature = "shader-hot-reload")]
+use std::path::PathBuf;
 
 /*
 /// Builder for a [`GlyphBrush`](struct.GlyphBrush.html).
@@ -28,12 +36,41 @@ use glium::draw_parameters::DrawParameters;
 /// ```
 */
 
-pub struct GlyphBrushBuilder<'a, F: Font, H = DefaultSectionHasher> {
+pub struct GlyphBrushBuilder<F: Font, H = DefaultSectionHasher, X = Extra> {
     inner: glyph_brush::GlyphBrushBuilder<F, H>,
-    params: DrawParameters<'a>,
+    params: DrawParameters<'static>,
+    glyph_filter: Option<GlyphFilter>,
+    missing_glyph: MissingGlyph,
+    shaders: Option<(String, String)>,
+    program: Option<Rc<Program>>,
+    memory_budget: Option<usize>,
+    gpu_error_policy: GpuErrorPolicy,
+    raster_mode: RasterMode,
+    subpixel_order: Option<SubpixelOrder>,
+    gamma: f32,
+    texture_filter: (MinifySamplerFilter, MagnifySamplerFilter),
+    glyph_cap: Option<usize>,
+    depth_sorted: bool,
+    order_independent: bool,
+    max_glyphs_per_draw: usize,
+    buffer_storage: BufferStorage,
+    instancing: bool,
+    deterministic: bool,
+    hardened: bool,
+    pixel_snapping: bool,
+    pixel_fonts: Vec<FontId>,
+    shared: Option<GlyphBrushShared>,
+    texture_uploader: Option<Box<TextureUploader>>,
+    cache_texture: Option<(Rc<Texture2d>, glium::Rect)>,
+    #[cfg(feature = "color-glyphs")]
+    color_atlas_allocator: Box<dyn AtlasAllocator>,
+    #[cfg(feature = "shader-hot-reload")]
+    shader_watch: Option<ShaderWatch>,
+    custom_vertices: Option<CustomVertices<X>>,
+    glyph_data: PhantomData<X>,
 }
 
-impl<'a, F: Font> GlyphBrushBuilder<'a, F> {
+impl<F: Font> GlyphBrushBuilder<F> {
     /// Specifies the default font used to render glyphs.
     /// Referenced with `FontId(0)`, which is default.
     #[inline]
@@ -41,6 +78,18 @@ impl<'a, F: Font> GlyphBrushBuilder<'a, F> {
         Self::using_fonts(vec![font_0])
     }
 
+    /// Specifies the face at `index` of a font collection, e.g. a `.ttc` file as many
+    /// CJK fonts are shipped, as the default font like [`using_font`](#method.using_font).
+    /// Index 0 is the first face.
+    ///
+    /// Returns an error if the data isn't a font collection with a face at `index`.
+    pub fn using_font_collection_bytes(font_data: Vec<u8>, index: u32) -> Result<Self, InvalidFont>
+    where
+        F: From<FontVec>,
+    {
+        FontVec::try_from_vec_and_index(font_data, index).map(|font| Self::using_font(font.into()))
+    }
+
     pub fn using_fonts<V: Into<Vec<F>>>(fonts: V) -> Self {
         GlyphBrushBuilder {
             inner: glyph_brush::GlyphBrushBuilder::using_fonts(fonts.into()),
@@ -48,13 +97,60 @@ impl<'a, F: Font> GlyphBrushBuilder<'a, F> {
                 blend: glium::Blend::alpha_blending(),
                 ..Default::default()
             },
+            glyph_filter: None,
+            missing_glyph: MissingGlyph::default(),
+            shaders: None,
+            program: None,
+            memory_budget: None,
+            gpu_error_policy: GpuErrorPolicy::default(),
+            raster_mode: RasterMode::default(),
+            subpixel_order: None,
+            gamma: 1.0,
+            texture_filter: (MinifySamplerFilter::Linear, MagnifySamplerFilter::Linear),
+            glyph_cap: None,
+            depth_sorted: false,
+            order_independent: false,
+            max_glyphs_per_draw: MAX_GLYPHS_PER_DRAW,
+            buffer_storage: BufferStorage::default(),
+            instancing: true,
+            deterministic: false,
+            hardened: false,
+            pixel_snapping: false,
+            pixel_fonts: Vec::new(),
+            shared: None,
+            texture_uploader: None,
+            cache_texture: None,
+            #[cfg(feature = "color-glyphs")]
+            color_atlas_allocator: Box::new(ShelfAllocator::default()),
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watch: None,
+            custom_vertices: None,
+            glyph_data: PhantomData,
         }
     }
 }
 
-impl<'a, F: Font, H: BuildHasher> GlyphBrushBuilder<'a, F, H> {
+impl<F: Font, H: BuildHasher, X: GlyphData> GlyphBrushBuilder<F, H, X> {
     delegate_glyph_brush_builder_fns!(inner);
 
+    /// Sets whether glyphs in the cache texture get a one texel transparent border, so
+    /// that linear filtering doesn't bleed neighbouring glyphs into scaled or transformed
+    /// text. Without it, more glyphs fit into the same texture.
+    ///
+    /// Together with [`draw_cache_scale_tolerance`](#method.draw_cache_scale_tolerance),
+    /// [`draw_cache_position_tolerance`](#method.draw_cache_position_tolerance) and
+    /// [`draw_cache_align_4x4`](#method.draw_cache_align_4x4) this trades cache churn and
+    /// texture space against accuracy, e.g. a position tolerance of 1.0 rasterizes each
+    /// glyph once for all subpixel positions, which suits scrolling text.
+    ///
+    /// Defaults to `true`
+    ///
+    /// See docs for `glyph_brush_draw_cache::DrawCache`
+    pub fn draw_cache_pad_glyphs(mut self, pad: bool) -> Self {
+        self.inner.draw_cache_builder = self.inner.draw_cache_builder.pad_glyphs(pad);
+        self
+    }
+
     /*
     /// Sets the depth test to use on the text section **z** values.
     ///
@@ -126,47 +222,680 @@ impl<'a, F: Font, H: BuildHasher> GlyphBrushBuilder<'a, F, H> {
     /// ```
     	*/
 
-    pub fn section_hasher<T: BuildHasher>(self, section_hasher: T) -> GlyphBrushBuilder<'a, F, T> {
+    pub fn section_hasher<T: BuildHasher>(self, section_hasher: T) -> GlyphBrushBuilder<F, T, X> {
         GlyphBrushBuilder {
             inner: self.inner.section_hasher(section_hasher),
             params: self.params,
+            glyph_filter: self.glyph_filter,
+            missing_glyph: self.missing_glyph,
+            shaders: self.shaders,
+            program: self.program,
+            memory_budget: self.memory_budget,
+            gpu_error_policy: self.gpu_error_policy,
+            raster_mode: self.raster_mode,
+            subpixel_order: self.subpixel_order,
+            gamma: self.gamma,
+            texture_filter: self.texture_filter,
+            glyph_cap: self.glyph_cap,
+            depth_sorted: self.depth_sorted,
+            order_independent: self.order_independent,
+            max_glyphs_per_draw: self.max_glyphs_per_draw,
+            buffer_storage: self.buffer_storage,
+            instancing: self.instancing,
+            deterministic: self.deterministic,
+            hardened: self.hardened,
+            pixel_snapping: self.pixel_snapping,
+            pixel_fonts: self.pixel_fonts,
+            shared: self.shared,
+            texture_uploader: self.texture_uploader,
+            cache_texture: self.cache_texture,
+            #[cfg(feature = "color-glyphs")]
+            color_atlas_allocator: self.color_atlas_allocator,
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watch: self.shader_watch,
+            custom_vertices: self.custom_vertices,
+            glyph_data: self.glyph_data,
         }
     }
 
-    pub fn params(self, params: DrawParameters<'a>) -> GlyphBrushBuilder<'a, F, H> {
+    /// Sets the type of the per glyph data carried by the texts of queued sections, see
+    /// [`GlyphData`](trait.GlyphData.html). Methods of the brush that rely on the fields of
+    /// `Extra`, e.g. [`snapshot`](struct.GlyphBrush.html#method.snapshot), are only
+    /// available with the default `Extra`. Resets a
+    /// [`custom_vertex`](#method.custom_vertex) conversion set before.
+    ///
+    /// Defaults to `Extra`.
+    pub fn glyph_data<Y: GlyphData>(self) -> GlyphBrushBuilder<F, H, Y> {
         GlyphBrushBuilder {
             inner: self.inner,
-            params,
+            params: self.params,
+            glyph_filter: self.glyph_filter,
+            missing_glyph: self.missing_glyph,
+            shaders: self.shaders,
+            program: self.program,
+            memory_budget: self.memory_budget,
+            gpu_error_policy: self.gpu_error_policy,
+            raster_mode: self.raster_mode,
+            subpixel_order: self.subpixel_order,
+            gamma: self.gamma,
+            texture_filter: self.texture_filter,
+            glyph_cap: self.glyph_cap,
+            depth_sorted: self.depth_sorted,
+            order_independent: self.order_independent,
+            max_glyphs_per_draw: self.max_glyphs_per_draw,
+            buffer_storage: self.buffer_storage,
+            instancing: self.instancing,
+            deterministic: self.deterministic,
+            hardened: self.hardened,
+            pixel_snapping: self.pixel_snapping,
+            pixel_fonts: self.pixel_fonts,
+            shared: self.shared,
+            texture_uploader: self.texture_uploader,
+            cache_texture: self.cache_texture,
+            #[cfg(feature = "color-glyphs")]
+            color_atlas_allocator: self.color_atlas_allocator,
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watch: self.shader_watch,
+            custom_vertices: None,
+            glyph_data: PhantomData,
         }
     }
 
-    /// Builds a `GlyphBrush` using the input glium facade
-    pub fn build<C: Facade>(self, facade: &C) -> GlyphBrush<'a, F, H> {
-        let glyph_brush = self.inner.build();
-        let (cache_width, cache_height) = glyph_brush.texture_dimensions();
+    /// Sets the draw parameters the glyphs are drawn with.
+    ///
+    /// Stored by the brush for its whole lifetime, so that it doesn't borrow anything and
+    /// can live in long-lived structs. Parameters borrowing e.g. queries can't be used.
+    ///
+    /// Defaults to alpha blending.
+    pub fn params(mut self, params: DrawParameters<'static>) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Sets the depth test used on the text section **z** values, leaving the
+    /// other draw parameters as they are.
+    ///
+    /// Defaults to: *Always pass the depth test, never write to the depth buffer*
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # extern crate glium;
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::GlyphBrushBuilder;
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # fn main() {
+    /// # let font: FontArc = unimplemented!();
+    /// GlyphBrushBuilder::using_font(font)
+    ///     .depth(glium::Depth {
+    ///         test: glium::DepthTest::IfLessOrEqual,
+    ///         write: true,
+    ///         ..Default::default()
+    ///     })
+    ///     // ...
+    /// # ;
+    /// # }
+    /// ```
+    pub fn depth(mut self, depth: glium::Depth) -> Self {
+        self.params.depth = depth;
+        self
+    }
+
+    /// Sets the depth test and writes to one of the common setups for text drawn with
+    /// 3D geometry, see [`DepthPreset`](enum.DepthPreset.html).
+    ///
+    /// Defaults to `DepthPreset::Overlay`.
+    pub fn depth_preset(self, preset: DepthPreset) -> Self {
+        self.depth(preset.depth())
+    }
+
+    /// Sets the stencil test and operations, leaving the other draw parameters as they are.
+    pub fn stencil(mut self, stencil: glium::draw_parameters::Stencil) -> Self {
+        self.params.stencil = stencil;
+        self
+    }
+
+    /// Sets the blending, leaving the other draw parameters as they are.
+    ///
+    /// Defaults to `glium::Blend::alpha_blending()`
+    pub fn blend(mut self, blend: glium::Blend) -> Self {
+        self.params.blend = blend;
+        self
+    }
+
+    /// Restricts the characters this brush will draw, see
+    /// [`GlyphFilter`](struct.GlyphFilter.html).
+    ///
+    /// Defaults to allowing all characters.
+    pub fn glyph_filter(mut self, glyph_filter: GlyphFilter) -> Self {
+        self.glyph_filter = Some(glyph_filter);
+        self
+    }
+
+    /// Sets what to do with characters no font has a glyph for, see
+    /// [`GlyphBrush::set_missing_glyph`](struct.GlyphBrush.html#method.set_missing_glyph).
+    ///
+    /// Defaults to `MissingGlyph::Keep`
+    pub fn missing_glyph(mut self, policy: MissingGlyph) -> Self {
+        self.missing_glyph = policy;
+        self
+    }
+
+    /// Sets a soft limit for the GPU memory used by the brush in bytes, see
+    /// [`GlyphBrush::set_memory_budget`](struct.GlyphBrush.html#method.set_memory_budget).
+    ///
+    /// Defaults to no limit.
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Sets how GPU allocation failures during drawing are handled.
+    ///
+    /// Defaults to `GpuErrorPolicy::ReturnError`
+    pub fn gpu_error_policy(mut self, policy: GpuErrorPolicy) -> Self {
+        self.gpu_error_policy = policy;
+        self
+    }
+
+    /// Sets how glyphs are stored in the cache texture and rendered,
+    /// see [`RasterMode`](enum.RasterMode.html).
+    ///
+    /// Defaults to `RasterMode::AlphaMask`
+    pub fn raster_mode(mut self, raster_mode: RasterMode) -> Self {
+        self.raster_mode = raster_mode;
+        self
+    }
+
+    /// Enables subpixel antialiasing for LCDs with the given subpixel order,
+    /// sharpening small text horizontally.
+    ///
+    /// Glyphs are rasterized at three times the horizontal resolution and every color
+    /// channel is blended with its own coverage, using dual source blending where
+    /// available and two passes otherwise. Only supported with `RasterMode::AlphaMask`
+    /// and the built-in shaders, custom shaders are ignored when it's enabled.
+    ///
+    /// Defaults to grayscale antialiasing.
+    pub fn subpixel_aa(mut self, order: SubpixelOrder) -> Self {
+        self.subpixel_order = Some(order);
+        self
+    }
+
+    /// Blends text in linear space by linearizing its color with the given gamma,
+    /// typically 2.2, in the fragment shader.
+    ///
+    /// Meant for sRGB framebuffers, which glium converts the blended linear output
+    /// back into sRGB for, so that text weight doesn't depend on the background.
+    /// Custom shaders receive the value as the `gamma` uniform.
+    ///
+    /// Defaults to 1, blending in the framebuffer's color space.
+    pub fn gamma_correct(mut self, gamma: f32) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Sets the filters used when sampling the glyph cache texture, e.g. `Nearest`
+    /// for crisp pixel fonts. Subpixel antialiasing always samples with `Nearest`.
+    ///
+    /// Defaults to `Linear` for both.
+    pub fn texture_filter(
+        mut self,
+        minify: MinifySamplerFilter,
+        magnify: MagnifySamplerFilter,
+    ) -> Self {
+        self.texture_filter = (minify, magnify);
+        self
+    }
 
-        static VERTEX_SHADER: &str = include_str!("shader/vert.glsl");
-        static FRAGMENT_SHADER: &str = include_str!("shader/frag.glsl");
-        let program = Program::from_source(facade, VERTEX_SHADER, FRAGMENT_SHADER, None).unwrap();
+    /// Sets the maximum number of glyphs queued per frame, see
+    /// [`GlyphBrush::set_glyph_cap`](struct.GlyphBrush.html#method.set_glyph_cap).
+    ///
+    /// Defaults to no limit.
+    pub fn glyph_cap(mut self, glyphs: usize) -> Self {
+        self.glyph_cap = Some(glyphs);
+        self
+    }
 
-        let texture = Texture2d::empty(facade, cache_width, cache_height).unwrap();
-        let index_buffer = glium::index::NoIndices(PrimitiveType::TriangleStrip);
+    /// Sorts glyph quads back to front by their `z` within each draw call, see
+    /// [`GlyphBrush::set_depth_sorted`](struct.GlyphBrush.html#method.set_depth_sorted).
+    ///
+    /// Defaults to `false`, drawing glyphs in queue order.
+    pub fn depth_sorted(mut self, sorted: bool) -> Self {
+        self.depth_sorted = sorted;
+        self
+    }
 
-        // We only need this so that we have groups of four
-        // instances each which is what the shader expects.
-        // Dunno if there is a nicer way to do this than this
-        // hack.
-        let instances = glium::VertexBuffer::new(facade, &[InstanceVertex { v: 0.0 }; 4]).unwrap();
-        let vertex_buffer = glium::VertexBuffer::empty(facade, 0).unwrap();
+    /// Blends glyphs with weighted blended order-independent transparency, e.g. for
+    /// thousands of overlapping translucent labels in a point cloud.
+    ///
+    /// Glyphs are accumulated into offscreen textures the size of the surface, which are
+    /// then composited onto it, so the result doesn't depend on the drawing order but
+    /// only approximates correct blending. Depth testing is disabled while accumulating,
+    /// nearer glyphs by `z` are weighted more. Only supported with `RasterMode::AlphaMask`
+    /// and the built-in shaders, without subpixel antialiasing, fills or hollow text.
+    ///
+    /// Defaults to `false`.
+    pub fn order_independent_transparency(mut self, enabled: bool) -> Self {
+        self.order_independent = enabled;
+        self
+    }
+
+    /// Splits drawing into several draw calls of at most `glyphs` glyphs each, e.g. for
+    /// drivers that fail or slow down on large draw calls.
+    ///
+    /// Defaults to 16384, the most glyphs that can be addressed with 16-bit indices,
+    /// which is also the upper limit.
+    pub fn max_glyphs_per_draw(mut self, glyphs: usize) -> Self {
+        self.max_glyphs_per_draw = glyphs;
+        self
+    }
+
+    /// Sets how the vertex buffer holding the glyphs is stored and updated,
+    /// see [`BufferStorage`](enum.BufferStorage.html).
+    ///
+    /// Defaults to `BufferStorage::Dynamic`.
+    pub fn buffer_storage(mut self, storage: BufferStorage) -> Self {
+        self.buffer_storage = storage;
+        self
+    }
 
-        GlyphBrush {
+    /// Draws each glyph as a triangle strip instanced from a shared unit quad. If disabled,
+    /// glyphs are expanded into four vertices each on the CPU and drawn as an indexed
+    /// triangle list with six indices per glyph from a shared index buffer, e.g. to work
+    /// around drivers with broken `ARB_instanced_arrays` or to get plain indexed draw calls
+    /// in graphics debuggers like RenderDoc.
+    ///
+    /// Contexts without instanced arrays always use the indexed triangle list, see
+    /// [`GlyphBrush::is_instanced`](struct.GlyphBrush.html#method.is_instanced).
+    ///
+    /// Defaults to `true`.
+    pub fn instancing(mut self, enabled: bool) -> Self {
+        self.instancing = enabled;
+        self
+    }
+
+    /// Makes layout reproducible across runs and platforms, e.g. for snapshot tests
+    /// of text placement with [`GlyphBrush::snapshot`](struct.GlyphBrush.html#method.snapshot).
+    ///
+    /// Glyph positions are rounded to multiples of 1/64 pixel and glyphs are rasterized
+    /// on a single thread. Texture coordinates still depend on which glyphs were cached
+    /// before. Layout only uses the section hasher for caching, so the default
+    /// `DefaultSectionHasher` and any other hasher give the same results.
+    ///
+    /// Defaults to `false`.
+    pub fn deterministic(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    /// Sanitizes queued sections so that arbitrary input can't make the brush panic, see
+    /// [`GlyphBrush::set_hardened`](struct.GlyphBrush.html#method.set_hardened).
+    ///
+    /// Defaults to `false`.
+    pub fn hardened(mut self, enabled: bool) -> Self {
+        self.hardened = enabled;
+        self
+    }
+
+    /// Rounds glyph positions to whole pixels, see
+    /// [`GlyphBrush::set_pixel_snapping`](struct.GlyphBrush.html#method.set_pixel_snapping).
+    ///
+    /// Defaults to `false`.
+    pub fn pixel_snapping(mut self, enabled: bool) -> Self {
+        self.pixel_snapping = enabled;
+        self
+    }
+
+    /// Reuses the text shader program of other brushes built with the same `shared`
+    /// handle instead of compiling it again. Custom shaders aren't shared.
+    ///
+    /// Defaults to compiling a program for this brush.
+    pub fn shared(mut self, shared: &GlyphBrushShared) -> Self {
+        self.shared = Some(shared.clone());
+        self
+    }
+
+    /// Samples the glyphs of `font_id` with nearest filtering, see
+    /// [`GlyphBrush::set_pixel_font`](struct.GlyphBrush.html#method.set_pixel_font).
+    /// Can be called for several fonts.
+    pub fn pixel_font(mut self, font_id: FontId) -> Self {
+        self.pixel_fonts.push(font_id);
+        self
+    }
+
+    /// Replaces how newly rasterized glyphs are written into the cache texture, e.g. to
+    /// route the uploads through a central transfer queue.
+    ///
+    /// `upload` receives the cache texture, the region to write and its data: one byte
+    /// per texel, in the layout `Texture2d::write` expects for `ClientFormat::U8`. The data
+    /// must reach the texture before the draw calls of the same `draw_queued` call execute
+    /// on the GPU. Glyphs of [baked atlases](struct.BakedBrush.html) and color glyphs are always written
+    /// directly.
+    ///
+    /// Defaults to writing the data into the texture right away.
+    pub fn texture_upload<U>(mut self, upload: U) -> Self
+    where
+        U: FnMut(&Texture2d, glium::Rect, &[u8]) + 'static,
+    {
+        self.texture_uploader = Some(Box::new(upload));
+        self
+    }
+
+    /// Caches glyphs in `region` of an existing texture instead of a texture owned by the
+    /// brush, e.g. a reserved area of the sprite atlas of an engine, so that text quads can
+    /// be batched with other quads sampling the same texture. Pass the full rectangle of
+    /// the texture to use all of it.
+    ///
+    /// Coverage is written into the red channel of the region, the other channels of the
+    /// region are overwritten. Texture coordinates of the glyphs, e.g. in a
+    /// [`snapshot`](struct.GlyphBrush.html#method.snapshot), refer to the whole texture.
+    /// The region can't grow: the glyphs of frames that don't fit are drawn in batches,
+    /// rasterizing them again in every frame.
+    ///
+    /// Panics if `region` doesn't lie within the texture.
+    ///
+    /// Defaults to a texture owned by the brush, grown as needed.
+    pub fn cache_texture(mut self, texture: Rc<Texture2d>, region: glium::Rect) -> Self {
+        let (width, height) = texture.dimensions();
+        assert!(
+            region.left + region.width <= width && region.bottom + region.height <= height,
+            "cache region {:?} exceeds the texture size {:?}",
+            region,
+            (width, height)
+        );
+        self.cache_texture = Some((texture, region));
+        self
+    }
+
+    /// Replaces the built-in glyph vertices with vertices of type `V`, e.g. to match the
+    /// attribute layout of a custom shader with per glyph rotation or a second set of
+    /// texture coordinates.
+    ///
+    /// `to_vertex` converts each glyph quad into its left top, right top, left bottom and
+    /// right bottom corners, drawn as two triangles with `program`. The program receives
+    /// the uniforms `font_tex`, the glyph cache texture, `transform`, `gamma`, `time`,
+    /// `opacity` and `tint`, and those set with
+    /// [`GlyphBrush::set_uniform`](struct.GlyphBrush.html#method.set_uniform).
+    /// Fills, subpixel antialiasing and order-independent transparency don't apply to
+    /// custom vertices.
+    ///
+    /// Defaults to the built-in vertices and shaders.
+    pub fn custom_vertex<V, P, T>(mut self, program: P, to_vertex: T) -> Self
+    where
+        V: glium::Vertex + Send + 'static,
+        P: Into<Rc<Program>>,
+        T: Fn(&GlyphQuad<X>) -> [V; 4] + 'static,
+    {
+        self.custom_vertices = Some(CustomVertices::new(program.into(), to_vertex));
+        self
+    }
+
+    /// Sets the allocator packing the atlas of color glyphs, e.g. a
+    /// [`SkylineAllocator`](struct.SkylineAllocator.html) for emoji of many different sizes.
+    ///
+    /// Defaults to a [`ShelfAllocator`](struct.ShelfAllocator.html).
+    #[cfg(feature = "color-glyphs")]
+    pub fn color_atlas_allocator<A: AtlasAllocator + 'static>(mut self, allocator: A) -> Self {
+        self.color_atlas_allocator = Box::new(allocator);
+        self
+    }
+
+    /// Uses custom vertex and fragment shader sources instead of the built-in ones.
+    ///
+    /// The shaders receive the same inputs as the built-in ones: the `vec2 corner`
+    /// of the unit quad each glyph is drawn over, from `(0, 0)` at the left top to `(1, 1)`,
+    /// the per glyph vertex attributes `left_top`, `right_bottom`, `tex_left_top`,
+    /// `tex_right_bottom`, `color`, `transform_x`, `transform_y`, `fill_x`, `fill_y`,
+    /// `procedural`, `procedural_color` and `hollow`, plus the `font_tex` and `transform`
+    /// uniforms. Filled text
+    /// additionally needs the `fill_tex`, `fill_enabled` and `time` uniforms, and fading
+    /// with [`GlyphBrush::set_alpha`](struct.GlyphBrush.html#method.set_alpha) the
+    /// `opacity` uniform, tinting with
+    /// [`GlyphBrush::draw_queued_tinted`](struct.GlyphBrush.html#method.draw_queued_tinted)
+    /// the `tint` uniform. Effects can use further uniforms set with
+    /// [`GlyphBrush::set_uniform`](struct.GlyphBrush.html#method.set_uniform).
+    /// A line `#include "fill.glsl"` in the fragment shader is replaced with the fills of
+    /// the built-in shaders, `fill_color()` and `inner_coverage()` for hollow glyphs,
+    /// which declare these inputs and need a `float coverage_at(vec2 pos)` function.
+    /// If the shaders fail to compile, use attributes that aren't provided or
+    /// lack one of the two uniforms, the default shaders are used instead,
+    /// see [`GlyphBrush::has_custom_shaders`](struct.GlyphBrush.html#method.has_custom_shaders).
+    pub fn with_shaders<V: Into<String>, Fr: Into<String>>(
+        mut self,
+        vertex_shader: V,
+        fragment_shader: Fr,
+    ) -> Self {
+        self.shaders = Some((vertex_shader.into(), fragment_shader.into()));
+        self
+    }
+
+    /// Uses an already created shader program instead of compiling one, e.g. one
+    /// precompiled or cached by the application's shader manager. An `Rc<Program>` can be
+    /// passed to share the program with other users.
+    ///
+    /// The program needs the same inputs as [custom shaders](#method.with_shaders), an
+    /// incompatible program is ignored like custom shaders that fail to compile. Takes
+    /// precedence over custom shader sources.
+    pub fn with_program<P: Into<Rc<Program>>>(mut self, program: P) -> Self {
+        self.program = Some(program.into());
+        self
+    }
+
+    /// Loads the vertex and fragment shaders from files, which can be reloaded
+    /// at runtime with [`GlyphBrush::reload_shaders`](struct.GlyphBrush.html#method.reload_shaders)
+    /// or [`GlyphBrush::poll_shaders`](struct.GlyphBrush.html#method.poll_shaders).
+    ///
+    /// Meant for iterating on text effects during development. The shaders have the
+    /// same requirements as the ones passed to [`with_shaders`](#method.with_shaders),
+    /// the default shaders are used until the files can be loaded successfully.
+    #[cfg(feature = "shader-hot-reload")]
+    pub fn shader_paths<V: Into<PathBuf>, Fr: Into<PathBuf>>(
+        mut self,
+        vertex_shader: V,
+        fragment_shader: Fr,
+    ) -> Self {
+        self.shader_watch = Some(ShaderWatch::new(
+            vertex_shader.into(),
+            fragment_shader.into(),
+        ));
+        self
+    }
+
+    #[cfg(feature = "shader-hot-reload")]
+    fn take_shader_sources(&mut self) -> Option<(String, String)> {
+        match self.shader_watch.as_mut() {
+            Some(watch) => watch.read().ok(),
+            None => self.shaders.take(),
+        }
+    }
+
+    #[cfg(not(feature = "shader-hot-reload"))]
+    fn take_shader_sources(&mut self) -> Option<(String, String)> {
+        self.shaders.take()
+    }
+
+    /// Builds a `GlyphBrush` using the input glium facade
+    ///
+    /// Panics if the shader program or the cache texture can't be created,
+    /// see [`try_build`](#method.try_build) for a fallible variant.
+    pub fn build<C: Facade>(self, facade: &C) -> GlyphBrush<F, H, X> {
+        self.try_build(facade).unwrap()
+    }
+
+    /// Builds a `GlyphBrush` using the input glium facade, returning an error
+    /// instead of panicking if any of the GPU resources can't be created.
+    pub fn try_build<C: Facade>(mut self, facade: &C) -> Result<GlyphBrush<F, H, X>, BuildError> {
+        let shaders = self.take_shader_sources();
+        if self.deterministic {
+            self.inner = self.inner.multithread(false);
+        }
+        let mut glyph_brush = self.inner.build();
+        let max_texture_size = facade.get_context().get_capabilities().max_texture_size as u32;
+        let (cache_width, cache_height) = glyph_brush.texture_dimensions();
+        if cache_width > max_texture_size || cache_height > max_texture_size {
+            glyph_brush.resize_texture(
+                cache_width.min(max_texture_size),
+                cache_height.min(max_texture_size),
+            );
+        }
+        if let Some((_, region)) = &self.cache_texture {
+            glyph_brush.resize_texture(region.width, region.height);
+        }
+        let (cache_width, cache_height) = glyph_brush.texture_dimensions();
+
+        let custom_program = match self.program.take() {
+            Some(program) => Some(program).filter(|p| is_compatible_program(p)),
+            None => shaders.and_then(|(vert, frag)| {
+                Program::from_source(facade, &vert, &include_fill(&frag), None)
+                    .ok()
+                    .filter(is_compatible_program)
+                    .map(Rc::new)
+            }),
+        };
+        let custom_shaders = custom_program.is_some();
+        let program = match (custom_program, &self.shared) {
+            (Some(program), _) => program,
+            (None, Some(shared)) => shared.program(facade, self.raster_mode)?,
+            (None, None) => Rc::new(create_program(facade, self.raster_mode)?),
+        };
+        let subpixel = match (self.subpixel_order, self.raster_mode) {
+            (Some(order), RasterMode::AlphaMask) if self.custom_vertices.is_none() => {
+                Some(Subpixel::new(facade, order)?)
+            }
+            _ => None,
+        };
+        let oit = match (self.order_independent, self.raster_mode, &subpixel) {
+            (true, RasterMode::AlphaMask, None) if self.custom_vertices.is_none() => {
+                Some(Oit::new(facade)?)
+            }
+            _ => None,
+        };
+        let (texture, cache_region) = match self.cache_texture {
+            Some((texture, region)) => (texture, Some(region)),
+            None => (
+                Rc::new(Texture2d::empty(facade, cache_width, cache_height)?),
+                None,
+            ),
+        };
+        let quads = QuadBuffer::empty(facade, self.instancing)?
+            .with_max_glyphs_per_draw(self.max_glyphs_per_draw)
+            .with_storage(self.buffer_storage);
+
+        Ok(GlyphBrush {
             glyph_brush,
             params: self.params,
+            glyph_filter: self.glyph_filter,
+            missing_glyph: self.missing_glyph,
             program,
+            custom_shaders,
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watch: self.shader_watch,
             texture,
-            index_buffer,
-            vertex_buffer,
-            instances,
+            cache_region,
+            texture_uploader: self.texture_uploader,
+            quads,
+            initial_cache_size: (cache_width, cache_height),
+            max_texture_size,
+            atlas_debug: None,
+            cache_regions: CacheRegions::default(),
+            memory_budget: self.memory_budget,
+            trim_pending: false,
+            gpu_error_policy: self.gpu_error_policy,
+            raster_mode: self.raster_mode,
+            subpixel,
+            oit,
+            gamma: self.gamma,
+            texture_filter: self.texture_filter,
+            fill_textures: FillTextures::default(),
+            draw_params: DrawParamsSets::default(),
+            layers: Layers::default(),
+            damage: Damage::default(),
+            time: 0.0,
+            opacity: 1.0,
+            user_uniforms: Vec::new(),
+            fill_batches: Vec::new(),
+            glyph_cap: self.glyph_cap,
+            depth_sorted: self.depth_sorted,
+            deterministic: self.deterministic,
+            hardened: self.hardened,
+            pixel_snapping: self.pixel_snapping,
+            pixel_fonts: self.pixel_fonts,
+            pinned_glyphs: Vec::new(),
+            retained: Retained::default(),
+            custom_vertices: self.custom_vertices,
+            overflow: Overflow::default(),
+            vertices_taken: false,
+            fallback_font: None,
+            font_fallbacks: Vec::new(),
+            missing_glyph_hook: None,
+            variable_fonts: VariableFonts::default(),
+            font_faces: FontFaces::default(),
+            queued_glyphs: 0,
+            queued_sections: 0,
+            truncated_glyphs: 0,
+            metrics: Metrics::default(),
+            #[cfg(feature = "color-glyphs")]
+            color_glyphs: ColorGlyphs::new(facade, self.instancing, self.color_atlas_allocator)?,
+            #[cfg(feature = "color-glyphs")]
+            color_queue: Vec::new(),
+            #[cfg(feature = "color-glyphs")]
+            color_layers: ColorLayers::default(),
+        })
+    }
+}
+
+/// Error returned by [`GlyphBrushBuilder::try_build`](struct.GlyphBrushBuilder.html#method.try_build).
+#[derive(Debug)]
+pub enum BuildError {
+    /// The text shaders failed to compile or link, e.g. because
+    /// the driver doesn't support the GLSL version.
+    ShaderCompilation(ProgramCreationError),
+    /// The glyph cache texture couldn't be allocated.
+    TextureAllocation(TextureCreationError),
+    /// One of the vertex buffers couldn't be allocated.
+    VertexBufferAllocation(BufferCreationError),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::ShaderCompilation(e) => {
+                write!(f, "failed to create text shader program: {}", e)
+            }
+            BuildError::TextureAllocation(e) => {
+                write!(f, "failed to create glyph cache texture: {}", e)
+            }
+            BuildError::VertexBufferAllocation(e) => {
+                write!(f, "failed to create vertex buffer: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BuildError::ShaderCompilation(e) => Some(e),
+            BuildError::TextureAllocation(e) => Some(e),
+            BuildError::VertexBufferAllocation(e) => Some(e),
         }
     }
 }
+
+impl From<ProgramCreationError> for BuildError {
+    fn from(e: ProgramCreationError) -> Self {
+        BuildError::ShaderCompilation(e)
+    }
+}
+
+impl From<TextureCreationError> for BuildError {
+    fn from(e: TextureCreationError) -> Self {
+        BuildError::TextureAllocation(e)
+    }
+}
+
+impl From<BufferCreationError> for BuildError {
+    fn from(e: BufferCreationError) -> Self {
+        BuildError::VertexBufferAllocation(e)
+    }
+}
@@ -0,0 +1,361 @@
+use super::*;
+
+use std::collections::HashMap;
+
+use glium::CapabilitiesSource;
+
+use glyph_brush::ab_glyph::{GlyphId, GlyphImageFormat, Rect, ScaleFont};
+use glyph_brush::{SectionGeometry, SectionGlyph};
+
+/// Decoded bitmap of a color glyph and where it ended up in the atlas.
+struct ColorImage {
+    width: u32,
+    height: u32,
+    /// straight alpha RGBA8 pixels
+    rgba: Vec<u8>,
+    /// bottom left corner relative to the glyph origin, y pointing up, in image pixels
+    origin: (f32, f32),
+    pixels_per_em: f32,
+    atlas_position: (u32, u32),
+}
+
+/// A color glyph quad waiting for the atlas to be uploaded.
+pub(crate) struct QueuedImage {
+    key: (FontId, GlyphId, u16),
+    pixel_coords: Rect,
+    bounds: Rect,
+    extra: Extra,
+}
+
+/// Glyphs without outlines that come with embedded color bitmaps instead
+/// (CBDT/sbix fonts, e.g. Noto Color Emoji). Layered COLR glyphs are handled
+/// by `ColorLayers`.
+///
+/// glyph_brush only rasterizes outlines, so these are drawn with a separate
+/// RGBA atlas after the regular glyphs.
+pub(crate) struct ColorGlyphs {
+    program: Program,
+    texture: Option<Texture2d>,
+    images: HashMap<(FontId, GlyphId, u16), Option<ColorImage>>,
+    queued: Vec<QueuedImage>,
+    atlas_dirty: bool,
+    quads: QuadBuffer,
+    allocator: Box<dyn AtlasAllocator>,
+}
+
+impl ColorGlyphs {
+    pub(crate) fn new<C: Facade>(
+        facade: &C,
+        instancing: bool,
+        allocator: Box<dyn AtlasAllocator>,
+    ) -> Result<Self, BuildError> {
+        static VERTEX_SHADER: &str = include_str!("shader/vert.glsl");
+        static FRAGMENT_SHADER: &str = include_str!("shader/frag_color.glsl");
+        Ok(ColorGlyphs {
+            program: Program::from_source(facade, VERTEX_SHADER, FRAGMENT_SHADER, None)?,
+            texture: None,
+            images: HashMap::new(),
+            queued: Vec::new(),
+            atlas_dirty: false,
+            quads: QuadBuffer::empty(facade, instancing)?,
+            allocator,
+        })
+    }
+
+    /// Queues the color glyphs among the laid out glyphs of `section`.
+    pub(crate) fn queue<F: Font, X: GlyphData>(
+        &mut self,
+        fonts: &[F],
+        section: &Section<X>,
+        bounds: Rect,
+        glyphs: &[SectionGlyph],
+    ) {
+        for section_glyph in glyphs {
+            let font = &fonts[section_glyph.font_id.0];
+            let glyph = &section_glyph.glyph;
+            let units_per_em = font.units_per_em().unwrap_or(1.0);
+            let pixels_per_em = units_per_em * font.as_scaled(glyph.scale).scale_factor().vertical;
+            let key = (
+                section_glyph.font_id,
+                glyph.id,
+                pixels_per_em.round() as u16,
+            );
+
+            if !self.images.contains_key(&key) {
+                let image = decode_color_glyph(font, glyph.id, key.2);
+                self.atlas_dirty |= image.is_some();
+                self.images.insert(key, image);
+            }
+            let image = match &self.images[&key] {
+                Some(image) => image,
+                None => continue,
+            };
+
+            let scale = pixels_per_em / image.pixels_per_em;
+            // differs from `scale` for horizontally stretched text, e.g. with subpixel AA
+            let scale_x = scale * glyph.scale.x / glyph.scale.y;
+            let (left, bottom) = (
+                glyph.position.x + image.origin.0 * scale_x,
+                glyph.position.y - image.origin.1 * scale,
+            );
+            self.queued.push(QueuedImage {
+                key,
+                pixel_coords: Rect {
+                    min: point(left, bottom - image.height as f32 * scale),
+                    max: point(left + image.width as f32 * scale_x, bottom),
+                },
+                bounds,
+                extra: {
+                    let extra = &section.text[section_glyph.section_index].extra;
+                    Extra {
+                        color: extra.color(),
+                        z: extra.z(),
+                    }
+                },
+            });
+        }
+    }
+
+    /// Takes the queued glyphs out, to be passed to [`draw`](#method.draw).
+    pub(crate) fn take_queue(&mut self) -> Vec<QueuedImage> {
+        std::mem::take(&mut self.queued)
+    }
+
+    /// Drops all cached images and the atlas texture.
+    pub(crate) fn clear_cache(&mut self) {
+        self.images.clear();
+        self.texture = None;
+        self.atlas_dirty = false;
+    }
+
+    /// Drops the cached and queued images of a font.
+    pub(crate) fn forget_font(&mut self, font_id: FontId) {
+        self.images.retain(|key, _| key.0 != font_id);
+        self.queued.retain(|queued| queued.key.0 != font_id);
+    }
+
+    pub(crate) fn texture_bytes(&self) -> usize {
+        self.texture
+            .as_ref()
+            .map_or(0, |t| t.width() as usize * t.height() as usize * 4)
+    }
+
+    /// Draws previously queued color glyphs, uploading newly added images first.
+    pub(crate) fn draw<C: Facade, S: Surface>(
+        &mut self,
+        facade: &C,
+        surface: &mut S,
+        queued: Vec<QueuedImage>,
+        draw_uniforms: DrawUniforms,
+        params: &glium::DrawParameters,
+    ) -> Result<(), DrawError> {
+        if queued.is_empty() {
+            return Ok(());
+        }
+        if self.atlas_dirty {
+            self.texture = Some(self.pack_atlas(facade)?);
+            self.atlas_dirty = false;
+        }
+        let texture = match &self.texture {
+            Some(texture) => texture,
+            None => return Ok(()),
+        };
+
+        let (width, height) = (texture.width() as f32, texture.height() as f32);
+        let images = &self.images;
+        let verts: Vec<GlyphVertex> = queued
+            .into_iter()
+            .filter_map(|queued| {
+                let image = images.get(&queued.key)?.as_ref()?;
+                let (x, y) = image.atlas_position;
+                let tex_coords = Rect {
+                    min: point(x as f32 / width, y as f32 / height),
+                    max: point(
+                        (x + image.width) as f32 / width,
+                        (y + image.height) as f32 / height,
+                    ),
+                };
+                Some(to_vertex(glyph_brush::GlyphVertex {
+                    tex_coords,
+                    pixel_coords: queued.pixel_coords,
+                    bounds: queued.bounds,
+                    extra: &GlyphExtra::from(queued.extra),
+                }))
+            })
+            .collect();
+        self.quads.upload(facade, &verts)?;
+        draw_glyphs(
+            surface,
+            &self.program,
+            texture,
+            self.quads.all(),
+            draw_uniforms,
+            params,
+        )?;
+        Ok(())
+    }
+
+    /// Packs all cached images into a new atlas texture.
+    fn pack_atlas<C: Facade>(&mut self, facade: &C) -> Result<Texture2d, DrawError> {
+        const ATLAS_WIDTH: u32 = 1024;
+        let mut images: Vec<_> = self
+            .images
+            .iter_mut()
+            .filter_map(|(key, image)| Some((*key, image.as_mut()?)))
+            .collect();
+        // sort by key as well for the same atlas regardless of the hash map order
+        images.sort_by_key(|(key, image)| (std::cmp::Reverse(image.height), *key));
+        let mut images: Vec<&mut ColorImage> = images.into_iter().map(|(_, image)| image).collect();
+
+        let width = images
+            .iter()
+            .map(|image| image.width)
+            .fold(ATLAS_WIDTH, u32::max);
+        let area: u64 = images
+            .iter()
+            .map(|image| u64::from(image.width) * u64::from(image.height))
+            .sum();
+        let mut atlas_height = images
+            .iter()
+            .map(|image| image.height)
+            .fold((area / u64::from(width)) as u32, u32::max)
+            .max(1);
+        let max_height = facade.get_context().get_capabilities().max_texture_size as u32;
+        'pack: loop {
+            self.allocator.reset(width, atlas_height);
+            for image in images.iter_mut() {
+                match self.allocator.allocate(image.width, image.height) {
+                    Some(position) => image.atlas_position = position,
+                    None if atlas_height < max_height => {
+                        atlas_height = atlas_height.saturating_mul(2).min(max_height);
+                        continue 'pack;
+                    }
+                    None => {
+                        return Err(DrawError::TextureAllocation(
+                            glium::texture::TextureCreationError::DimensionsNotSupported,
+                        ))
+                    }
+                }
+            }
+            break;
+        }
+        let height = images
+            .iter()
+            .map(|image| image.atlas_position.1 + image.height)
+            .fold(1, u32::max);
+
+        let mut pixels = vec![0; width as usize * height as usize * 4];
+        for image in &images {
+            let (x, y) = image.atlas_position;
+            let row_bytes = image.width as usize * 4;
+            for row in 0..image.height as usize {
+                let start = ((y as usize + row) * width as usize + x as usize) * 4;
+                pixels[start..start + row_bytes]
+                    .copy_from_slice(&image.rgba[row * row_bytes..(row + 1) * row_bytes]);
+            }
+        }
+        let image = RawImage2d::from_raw_rgba(pixels, (width, height));
+        Ok(Texture2d::with_format(
+            facade,
+            image,
+            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+            glium::texture::MipmapsOption::NoMipmap,
+        )?)
+    }
+}
+
+/// Decodes the embedded bitmap of a glyph that has no outline, `None` if it has
+/// an outline, no bitmap or one in an unsupported format.
+fn decode_color_glyph<F: Font>(font: &F, id: GlyphId, pixels_per_em: u16) -> Option<ColorImage> {
+    if font.outline(id).is_some() {
+        return None;
+    }
+    let image = font.glyph_raster_image2(id, pixels_per_em)?;
+    let (width, height, rgba) = match image.format {
+        GlyphImageFormat::Png => decode_png(image.data)?,
+        GlyphImageFormat::BitmapPremulBgra32 => {
+            let rgba = image
+                .data
+                .chunks_exact(4)
+                .flat_map(|bgra| {
+                    let unpremultiply = |c: u8| match bgra[3] {
+                        0 => 0,
+                        a => (u32::from(c) * 255 / u32::from(a)).min(255) as u8,
+                    };
+                    [
+                        unpremultiply(bgra[2]),
+                        unpremultiply(bgra[1]),
+                        unpremultiply(bgra[0]),
+                        bgra[3],
+                    ]
+                })
+                .collect();
+            (u32::from(image.width), u32::from(image.height), rgba)
+        }
+        _ => return None,
+    };
+    if rgba.len() != width as usize * height as usize * 4 {
+        return None;
+    }
+    Some(ColorImage {
+        width,
+        height,
+        rgba,
+        origin: (image.origin.x, image.origin.y),
+        pixels_per_em: f32::from(image.pixels_per_em.max(1)),
+        atlas_position: (0, 0),
+    })
+}
+
+/// Decodes a png into straight alpha RGBA8 pixels.
+fn decode_png(data: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    buf.truncate(info.buffer_size());
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|c| [c[0], c[1], c[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|c| [c[0], c[0], c[0], c[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&c| [c, c, c, 255]).collect(),
+        png::ColorType::Indexed => return None,
+    };
+    Some((info.width, info.height, rgba))
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Lays out a section that's being queued to find its color glyphs.
+    ///
+    /// Returns `true` if the section contains COLR glyphs and was queued with
+    /// those expanded into their layers.
+    pub(crate) fn queue_color_glyphs<G: GlyphPositioner>(
+        &mut self,
+        section: &Section<X>,
+        custom_layout: &G,
+    ) -> bool {
+        let glyphs: Vec<_> = self
+            .glyph_brush
+            .glyphs_custom_layout(with_glyph_extra(section), custom_layout)
+            .cloned()
+            .collect();
+        let bounds = custom_layout.bounds_rect(&SectionGeometry::from(section));
+        let fonts = self.glyph_brush.fonts();
+        self.color_glyphs.queue(fonts, section, bounds, &glyphs);
+        match self.color_layers.expand(fonts, section, &glyphs) {
+            Some((glyphs, extras)) => {
+                let extras = extras.into_iter().map(GlyphExtra::from).collect();
+                self.queue_glyphs(glyphs, extras, bounds);
+                true
+            }
+            None => false,
+        }
+    }
+}
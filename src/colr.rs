@@ -0,0 +1,143 @@
+use super::*;
+
+use std::collections::HashMap;
+
+use glyph_brush::ab_glyph::GlyphId;
+use glyph_brush::SectionGlyph;
+use ttf_parser::colr::{ClipBox, CompositeMode, Paint, Painter};
+use ttf_parser::{Face, RgbaColor, Transform};
+
+/// Stands in for the text color when painting, for layers that use it.
+const FOREGROUND: RgbaColor = RgbaColor {
+    red: 1,
+    green: 2,
+    blue: 3,
+    alpha: 0,
+};
+
+/// A layer of a COLR glyph.
+struct Layer {
+    glyph: GlyphId,
+    /// straight alpha color, `None` for the text color
+    color: Option<[f32; 4]>,
+}
+
+/// Collects the outlined glyphs of a COLR glyph along with their fill colors.
+///
+/// Only solid fills are supported, layers filled with gradients use the text color.
+/// Clips, transforms and composite modes of COLRv1 glyphs are ignored.
+#[derive(Default)]
+struct LayerPainter {
+    outline: Option<GlyphId>,
+    layers: Vec<Layer>,
+}
+
+impl<'a> Painter<'a> for LayerPainter {
+    fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+        self.outline = Some(GlyphId(glyph_id.0));
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        let glyph = match self.outline.take() {
+            Some(glyph) => glyph,
+            None => return,
+        };
+        let color = match paint {
+            Paint::Solid(c) if c != FOREGROUND => Some([
+                f32::from(c.red) / 255.0,
+                f32::from(c.green) / 255.0,
+                f32::from(c.blue) / 255.0,
+                f32::from(c.alpha) / 255.0,
+            ]),
+            _ => None,
+        };
+        self.layers.push(Layer { glyph, color });
+    }
+
+    fn push_clip(&mut self) {}
+    fn push_clip_box(&mut self, _: ClipBox) {}
+    fn pop_clip(&mut self) {}
+    fn push_layer(&mut self, _: CompositeMode) {}
+    fn pop_layer(&mut self) {}
+    fn push_transform(&mut self, _: Transform) {}
+    fn pop_transform(&mut self) {}
+}
+
+/// Layered color glyphs of fonts with COLR/CPAL tables, e.g. Twemoji COLR.
+///
+/// The layers are outline glyphs themselves, so they're expanded into one glyph per
+/// layer with the layer's palette color and go through the regular glyph cache.
+/// Reading the tables requires [`Font::font_data`], which all ab_glyph fonts provide.
+#[derive(Default)]
+pub(crate) struct ColorLayers {
+    has_colr: HashMap<FontId, bool>,
+    layers: HashMap<(FontId, GlyphId), Option<Vec<Layer>>>,
+}
+
+impl ColorLayers {
+    fn layers<F: Font>(&mut self, fonts: &[F], font_id: FontId, id: GlyphId) -> Option<&[Layer]> {
+        let font = &fonts[font_id.0];
+        let has_colr = *self.has_colr.entry(font_id).or_insert_with(|| {
+            Face::parse(font.font_data(), 0).is_ok_and(|face| face.tables().colr.is_some())
+        });
+        if !has_colr {
+            return None;
+        }
+        self.layers
+            .entry((font_id, id))
+            .or_insert_with(|| {
+                let face = Face::parse(font.font_data(), 0).ok()?;
+                let mut painter = LayerPainter::default();
+                face.paint_color_glyph(ttf_parser::GlyphId(id.0), 0, FOREGROUND, &mut painter)?;
+                Some(painter.layers)
+            })
+            .as_deref()
+    }
+
+    /// Drops the layers read from a font.
+    pub(crate) fn forget_font(&mut self, font_id: FontId) {
+        self.has_colr.remove(&font_id);
+        self.layers.retain(|key, _| key.0 != font_id);
+    }
+
+    /// Replaces the COLR glyphs among the laid out glyphs of `section` with their
+    /// layers, returning the glyphs along with the extras they index into.
+    ///
+    /// Returns `None` if there are no COLR glyphs.
+    pub(crate) fn expand<F: Font, X: GlyphData>(
+        &mut self,
+        fonts: &[F],
+        section: &Section<X>,
+        glyphs: &[SectionGlyph],
+    ) -> Option<(Vec<SectionGlyph>, Vec<X>)> {
+        let mut expanded: Option<(Vec<SectionGlyph>, Vec<X>)> = None;
+        for (i, section_glyph) in glyphs.iter().enumerate() {
+            let layers = match self.layers(fonts, section_glyph.font_id, section_glyph.glyph.id) {
+                Some(layers) => layers,
+                None => {
+                    if let Some((expanded, _)) = expanded.as_mut() {
+                        expanded.push(section_glyph.clone());
+                    }
+                    continue;
+                }
+            };
+            let (expanded, extras) = expanded.get_or_insert_with(|| {
+                let extras = section.text.iter().map(|t| t.extra.clone()).collect();
+                (glyphs[..i].to_vec(), extras)
+            });
+            let extra = &section.text[section_glyph.section_index].extra;
+            let text_color = extra.color();
+            for layer in layers {
+                let color = layer
+                    .color
+                    .map_or(text_color, |c| [c[0], c[1], c[2], c[3] * text_color[3]]);
+                extras.push(extra.with_color(color));
+                let mut layer_glyph = section_glyph.clone();
+                layer_glyph.section_index = extras.len() - 1;
+                layer_glyph.glyph.id = layer.glyph;
+                expanded.push(layer_glyph);
+            }
+        }
+        expanded
+    }
+}
@@ -0,0 +1,204 @@
+use super::*;
+
+use std::collections::VecDeque;
+
+use glyph_brush::ab_glyph::{PxScale, ScaleFont};
+use glyph_brush::Text;
+
+/// Severity of a [`ConsoleView`](struct.ConsoleView.html) line, shown as a colored prefix.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    fn prefix(self) -> &'static str {
+        match self {
+            Severity::Debug => "[debug] ",
+            Severity::Info => "[info] ",
+            Severity::Warn => "[warn] ",
+            Severity::Error => "[error] ",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+struct ConsoleLine {
+    severity: Option<Severity>,
+    text: String,
+}
+
+/// A scrollable log of text lines, e.g. for a debug console or tool output.
+///
+/// Lines are kept in a ring buffer of fixed capacity, dropping the oldest ones,
+/// and wrap at the width of the area they're drawn into. The newest line is at
+/// the bottom. Each line is queued as its own section, so only new lines need a
+/// full layout while older ones are moved up using the cached layout.
+pub struct ConsoleView {
+    lines: VecDeque<ConsoleLine>,
+    capacity: usize,
+    scroll: usize,
+    font_id: FontId,
+    scale: PxScale,
+    color: [f32; 4],
+    severity_colors: [[f32; 4]; 4],
+}
+
+impl ConsoleView {
+    /// Creates an empty console keeping up to `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        ConsoleView {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+            scroll: 0,
+            font_id: FontId::default(),
+            scale: PxScale::from(16.0),
+            color: [1.0, 1.0, 1.0, 1.0],
+            severity_colors: [
+                [0.6, 0.6, 0.6, 1.0],
+                [0.4, 0.7, 1.0, 1.0],
+                [1.0, 0.8, 0.2, 1.0],
+                [1.0, 0.3, 0.3, 1.0],
+            ],
+        }
+    }
+
+    pub fn font_id(mut self, font_id: FontId) -> Self {
+        self.font_id = font_id;
+        self
+    }
+
+    /// Defaults to 16 pixels.
+    pub fn scale<S: Into<PxScale>>(mut self, scale: S) -> Self {
+        self.scale = scale.into();
+        self
+    }
+
+    /// Sets the color of the line text.
+    ///
+    /// Defaults to white.
+    pub fn color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the color of the prefix of lines with the given severity.
+    pub fn severity_color(mut self, severity: Severity, color: [f32; 4]) -> Self {
+        self.severity_colors[severity.index()] = color;
+        self
+    }
+
+    /// Appends a line with a severity prefix.
+    pub fn push<S: Into<String>>(&mut self, severity: Severity, text: S) {
+        self.push_line(Some(severity), text.into());
+    }
+
+    /// Appends a line without a prefix.
+    pub fn push_plain<S: Into<String>>(&mut self, text: S) {
+        self.push_line(None, text.into());
+    }
+
+    fn push_line(&mut self, severity: Option<Severity>, text: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(ConsoleLine { severity, text });
+        if self.scroll > 0 {
+            // keep the scrolled to lines in view
+            self.scroll = (self.scroll + 1).min(self.lines.len() - 1);
+        }
+    }
+
+    /// Removes all lines.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.scroll = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Scrolls towards older lines.
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scrolls towards newer lines.
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Scrolls to the newest line.
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// Returns by how many lines the view is scrolled up from the newest line.
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll
+    }
+
+    fn section<'a>(&'a self, line: &'a ConsoleLine, (x, y): (f32, f32), width: f32) -> Section<'a> {
+        let mut text = Vec::with_capacity(2);
+        if let Some(severity) = line.severity {
+            text.push(
+                Text::new(severity.prefix())
+                    .with_font_id(self.font_id)
+                    .with_scale(self.scale)
+                    .with_color(self.severity_colors[severity.index()]),
+            );
+        }
+        text.push(
+            Text::new(&line.text)
+                .with_font_id(self.font_id)
+                .with_scale(self.scale)
+                .with_color(self.color),
+        );
+        Section::default()
+            .with_screen_position((x, y))
+            .with_bounds((width, f32::INFINITY))
+            .with_text(text)
+    }
+
+    /// Queues the visible lines to be drawn into the area with the top left corner
+    /// `position` and the given `size`, in pixels.
+    ///
+    /// Lines that don't fit completely into the area are left out.
+    pub fn queue<F: Font + Sync, H: BuildHasher>(
+        &self,
+        brush: &mut GlyphBrush<F, H>,
+        (x, y): (f32, f32),
+        (width, height): (f32, f32),
+    ) {
+        let empty_line_height = {
+            let font = brush.fonts()[self.font_id.0].as_scaled(self.scale);
+            font.height() + font.line_gap()
+        };
+        let mut bottom = y + height;
+        for line in self.lines.iter().rev().skip(self.scroll) {
+            // Measured at a fixed position so the layout is cached across frames.
+            let line_height = brush
+                .glyph_bounds(self.section(line, (0.0, 0.0), width))
+                .map_or(empty_line_height, |bounds| bounds.height());
+            if bottom - line_height < y {
+                break;
+            }
+            bottom -= line_height;
+            brush.queue(self.section(line, (x, bottom), width));
+        }
+    }
+}
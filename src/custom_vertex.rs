@@ -0,0 +1,140 @@
+use super::*;
+
+use glium::index::{IndexBuffer, PrimitiveType};
+use glium::vertex::{BufferCreationError, VertexBuffer, VertexBufferAny};
+use glyph_brush::ab_glyph::Rect;
+
+/// A glyph quad passed to the vertex conversion of
+/// [`GlyphBrushBuilder::custom_vertex`](struct.GlyphBrushBuilder.html#method.custom_vertex)
+/// or returned by
+/// [`GlyphBrush::process_queued_to_vertices`](struct.GlyphBrush.html#method.process_queued_to_vertices).
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphQuad<X = Extra> {
+    /// Pixel rectangle of the rasterized glyph, clipped to its section's bounds.
+    pub pixel_rect: Rect,
+    /// Texture coordinates of the glyph in the cache texture, from 0 to 1.
+    pub uv_rect: Rect,
+    /// Transform of the glyph, see [`GlyphTransform`](struct.GlyphTransform.html).
+    pub transform: GlyphTransform,
+    /// Data of the text the glyph belongs to.
+    pub extra: X,
+}
+
+impl<X> GlyphQuad<X> {
+    pub(crate) fn new(v: &GlyphVertex, extra: X) -> Self {
+        GlyphQuad {
+            pixel_rect: Rect {
+                min: point(v.left_top[0], v.right_bottom[1]),
+                max: point(v.right_bottom[0], v.left_top[1]),
+            },
+            uv_rect: Rect {
+                min: point(v.tex_left_top[0], v.tex_right_bottom[1]),
+                max: point(v.tex_right_bottom[0], v.tex_left_top[1]),
+            },
+            transform: GlyphTransform([v.transform_x, v.transform_y]),
+            extra,
+        }
+    }
+}
+
+/// Converts glyph quads into a vertex buffer of the user's vertex type.
+type VertexConversion<X> =
+    dyn Fn(&dyn Facade, &[GlyphQuad<X>]) -> Result<VertexBufferAny, BufferCreationError>;
+
+/// Glyph vertices of a user defined type, drawn with the user's program.
+pub(crate) struct CustomVertices<X> {
+    program: Rc<Program>,
+    convert: Box<VertexConversion<X>>,
+    vertices: Option<VertexBufferAny>,
+    indices: Option<IndexBuffer<u32>>,
+    glyphs: usize,
+}
+
+impl<X: GlyphData> CustomVertices<X> {
+    pub(crate) fn new<V, T>(program: Rc<Program>, to_vertex: T) -> Self
+    where
+        V: glium::Vertex + Send + 'static,
+        T: Fn(&GlyphQuad<X>) -> [V; 4] + 'static,
+    {
+        let convert = move |facade: &dyn Facade, quads: &[GlyphQuad<X>]| {
+            let mut vertices = Vec::with_capacity(quads.len() * 4);
+            for quad in quads {
+                for vertex in &to_vertex(quad) {
+                    vertices.push(*vertex);
+                }
+            }
+            Ok(VertexBuffer::new(facade, &vertices)?.into())
+        };
+        CustomVertices {
+            program,
+            convert: Box::new(convert),
+            vertices: None,
+            indices: None,
+            glyphs: 0,
+        }
+    }
+
+    pub(crate) fn glyphs(&self) -> usize {
+        self.glyphs
+    }
+
+    /// Converts freshly generated vertices and replaces the buffers with them.
+    pub(crate) fn upload<C: Facade>(
+        &mut self,
+        facade: &C,
+        verts: Vec<(Batch, GlyphVertex, Option<X>)>,
+    ) -> Result<(), DrawError> {
+        let quads: Vec<GlyphQuad<X>> = verts
+            .into_iter()
+            .filter_map(|(_, v, extra)| Some(GlyphQuad::new(&v, extra?)))
+            .collect();
+        // the corners of each quad as two triangles
+        let indices: Vec<u32> = (0..quads.len() as u32)
+            .flat_map(|i| {
+                let base = i * 4;
+                vec![base, base + 1, base + 2, base + 2, base + 1, base + 3]
+            })
+            .collect();
+        self.glyphs = 0;
+        if quads.is_empty() {
+            return Ok(());
+        }
+        self.vertices = Some((self.convert)(facade.get_context(), &quads)?);
+        self.indices = Some(IndexBuffer::new(
+            facade,
+            PrimitiveType::TrianglesList,
+            &indices,
+        )?);
+        self.glyphs = quads.len();
+        Ok(())
+    }
+
+    pub(crate) fn draw<S: Surface>(
+        &self,
+        surface: &mut S,
+        texture: &Texture2d,
+        draw_uniforms: DrawUniforms,
+        params: &glium::DrawParameters,
+    ) -> Result<(), glium::DrawError> {
+        let (vertices, indices) = match (&self.vertices, &self.indices) {
+            (Some(vertices), Some(indices)) if self.glyphs > 0 => (vertices, indices),
+            _ => return Ok(()),
+        };
+        let sampler = glium::uniforms::Sampler::new(texture)
+            .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+            .minify_filter(draw_uniforms.texture_filter.0)
+            .magnify_filter(draw_uniforms.texture_filter.1);
+        let uniforms = WithUserUniforms {
+            uniforms: uniform! {
+                font_tex: sampler,
+                transform: draw_uniforms.transform,
+                gamma: draw_uniforms.gamma,
+                time: draw_uniforms.time,
+                opacity: draw_uniforms.opacity,
+                tint: draw_uniforms.tint,
+            },
+            user: draw_uniforms.user,
+        };
+        surface.draw(vertices, indices, &self.program, &uniforms, params)
+    }
+}
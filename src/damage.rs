@@ -0,0 +1,204 @@
+use super::*;
+
+use glyph_brush::ab_glyph::Rect;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+/// Glyphs of the previous draw, to find the regions that changed since.
+#[derive(Default)]
+pub(crate) struct Damage {
+    /// whether draws are compared, see `set_damage_tracking`
+    enabled: bool,
+    /// hash and bounding box of every glyph drawn, sorted by hash
+    glyphs: Vec<(u64, Rect)>,
+    rects: Vec<Rect>,
+    /// bounding boxes of glyphs recolored in place since the last draw
+    recolored: Vec<Rect>,
+}
+
+/// Bounding box of a glyph quad in pixels, after its transform.
+fn glyph_rect(v: &GlyphVertex, stretch: f32) -> Rect {
+    let [x, y] = [v.transform_x, v.transform_y];
+    let corners = [
+        (v.left_top[0], v.left_top[1]),
+        (v.right_bottom[0], v.left_top[1]),
+        (v.left_top[0], v.right_bottom[1]),
+        (v.right_bottom[0], v.right_bottom[1]),
+    ];
+    let mut rect = Rect {
+        min: point(f32::INFINITY, f32::INFINITY),
+        max: point(f32::NEG_INFINITY, f32::NEG_INFINITY),
+    };
+    for (px, py) in corners.iter() {
+        let tx = (x[0] * px + x[1] * py + x[2]) / stretch;
+        let ty = y[0] * px + y[1] * py + y[2];
+        rect.min = point(rect.min.x.min(tx), rect.min.y.min(ty));
+        rect.max = point(rect.max.x.max(tx), rect.max.y.max(ty));
+    }
+    rect
+}
+
+fn glyph_hash(v: &GlyphVertex) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let values = v
+        .left_top
+        .iter()
+        .chain(&v.right_bottom)
+        .chain(&v.tex_left_top)
+        .chain(&v.tex_right_bottom)
+        .chain(&v.color)
+        .chain(&v.transform_x)
+        .chain(&v.transform_y)
+        .chain(&v.fill_x)
+        .chain(&v.fill_y)
+        .chain(&v.procedural)
+        .chain(&v.procedural_color)
+        .chain(Some(&v.hollow));
+    for value in values {
+        hasher.write_u32(value.to_bits());
+    }
+    hasher.finish()
+}
+
+fn overlap(a: &Rect, b: &Rect) -> bool {
+    // touching rectangles are merged as well
+    a.min.x <= b.max.x + 1.0
+        && b.min.x <= a.max.x + 1.0
+        && a.min.y <= b.max.y + 1.0
+        && b.min.y <= a.max.y + 1.0
+}
+
+impl Damage {
+    /// Compares the glyphs of a draw with regenerated vertices to the previous ones.
+    pub(crate) fn update<'a>(
+        &mut self,
+        verts: impl Iterator<Item = &'a GlyphVertex>,
+        stretch: f32,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let mut glyphs: Vec<(u64, Rect)> = verts
+            .map(|v| (glyph_hash(v), glyph_rect(v, stretch)))
+            .collect();
+        glyphs.sort_unstable_by_key(|&(hash, _)| hash);
+
+        // glyphs in only one of the sorted lists changed
+        let mut changed = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.glyphs.len() || j < glyphs.len() {
+            match (self.glyphs.get(i), glyphs.get(j)) {
+                (Some(a), Some(b)) if a.0 == b.0 => {
+                    i += 1;
+                    j += 1;
+                }
+                (Some(a), Some(b)) if a.0 < b.0 => {
+                    changed.push(a.1);
+                    i += 1;
+                }
+                (Some(a), None) => {
+                    changed.push(a.1);
+                    i += 1;
+                }
+                (_, Some(b)) => {
+                    changed.push(b.1);
+                    j += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+        self.glyphs = glyphs;
+        self.merge(changed);
+    }
+
+    /// Adds the glyphs of another batch of a draw that didn't fit into the cache texture
+    /// at once. They count as changed, their texture positions differ from any previous
+    /// draw anyway.
+    pub(crate) fn add_batch<'a>(
+        &mut self,
+        verts: impl Iterator<Item = &'a GlyphVertex>,
+        stretch: f32,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let glyphs: Vec<(u64, Rect)> = verts
+            .map(|v| (glyph_hash(v), glyph_rect(v, stretch)))
+            .collect();
+        let mut changed = std::mem::take(&mut self.rects);
+        changed.extend(glyphs.iter().map(|&(_, rect)| rect));
+        self.glyphs.extend(glyphs);
+        self.glyphs.sort_unstable_by_key(|&(hash, _)| hash);
+        self.merge(changed);
+    }
+
+    /// Records glyphs whose colors were changed without regenerating the vertices.
+    pub(crate) fn recolored<'a>(
+        &mut self,
+        verts: impl Iterator<Item = &'a GlyphVertex>,
+        stretch: f32,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        self.recolored.extend(verts.map(|v| glyph_rect(v, stretch)));
+    }
+
+    /// Replaces the changed regions with the merged `changed` glyph rectangles and the
+    /// recolored ones.
+    fn merge(&mut self, mut changed: Vec<Rect>) {
+        changed.append(&mut self.recolored);
+        self.rects.clear();
+        for mut rect in changed {
+            // merge with every overlapping rectangle until none is left
+            while let Some(k) = self.rects.iter().position(|r| overlap(r, &rect)) {
+                let other = self.rects.swap_remove(k);
+                rect = Rect {
+                    min: point(rect.min.x.min(other.min.x), rect.min.y.min(other.min.y)),
+                    max: point(rect.max.x.max(other.max.x), rect.max.y.max(other.max.y)),
+                };
+            }
+            self.rects.push(rect);
+        }
+    }
+
+    /// Records a draw that redrew the previous vertices.
+    pub(crate) fn unchanged(&mut self) {
+        if self.enabled {
+            self.merge(Vec::new());
+        }
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Returns the regions that changed in the last draw compared to the draw before,
+    /// where glyphs were added, removed, moved or restyled, e.g. to only present those
+    /// parts of the frame again.
+    ///
+    /// Rectangles are in the pixel coordinates of the queued sections, before the draw
+    /// transform, and cover the changed glyphs both before and after the change.
+    /// Overlapping and touching rectangles are merged. Color glyphs aren't tracked.
+    ///
+    /// Empty unless [damage tracking](#method.set_damage_tracking) is enabled.
+    pub fn damage(&self) -> &[Rect] {
+        &self.damage.rects
+    }
+
+    /// Enables comparing the glyphs of every draw with regenerated vertices to the
+    /// previous ones, to report the [`damage`](#method.damage). This hashes and sorts all
+    /// drawn glyphs, so it's off unless needed. The first draw after enabling it reports
+    /// all glyphs as changed.
+    ///
+    /// Defaults to `false`.
+    pub fn set_damage_tracking(&mut self, enabled: bool) {
+        if !enabled {
+            self.damage = Damage::default();
+        }
+        self.damage.enabled = enabled;
+    }
+
+    /// Returns whether damage tracking is enabled.
+    pub fn damage_tracking(&self) -> bool {
+        self.damage.enabled
+    }
+}
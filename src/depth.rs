@@ -0,0 +1,56 @@
+use super::*;
+
+/// Common depth buffer setups for text drawn together with 3D geometry, see
+/// [`GlyphBrushBuilder::depth_preset`](struct.GlyphBrushBuilder.html#method.depth_preset).
+///
+/// Glyphs are depth tested at the **z** of their text, or the depth set with
+/// [`SectionStyle::depth`](struct.SectionStyle.html#method.depth). With the transform of
+/// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), **z** is the normalized
+/// depth from -1 (near) to 1 (far) and glyphs outside of that range are clipped. In world
+/// space, e.g. with
+/// [`draw_queued_with_view_projection`](struct.GlyphBrush.html#method.draw_queued_with_view_projection),
+/// it's a world coordinate like the pixel coordinates.
+///
+/// To interleave text with a scene, draw the opaque geometry first, then the text with
+/// a depth testing preset, then any translucent geometry. The framebuffer needs a depth
+/// buffer for the test to have an effect.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DepthPreset {
+    /// Text is drawn over everything, without testing or writing depth, e.g. for a HUD.
+    #[default]
+    Overlay,
+    /// Text is hidden behind geometry that's nearer, but doesn't hide anything itself,
+    /// e.g. for labels in a scene.
+    Occluded,
+    /// Text is hidden behind nearer geometry and writes its depth, so that it hides
+    /// geometry drawn after it, e.g. for signs that are part of a scene. Transparent parts
+    /// of glyph quads don't write depth.
+    Occluding,
+}
+
+impl DepthPreset {
+    /// Returns the depth parameters of the preset.
+    pub fn depth(self) -> glium::Depth {
+        let (test, write) = match self {
+            DepthPreset::Overlay => (glium::DepthTest::Overwrite, false),
+            DepthPreset::Occluded => (glium::DepthTest::IfLessOrEqual, false),
+            DepthPreset::Occluding => (glium::DepthTest::IfLessOrEqual, true),
+        };
+        glium::Depth {
+            test,
+            write,
+            ..Default::default()
+        }
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Queues a section like [`queue`](#method.queue), with its glyphs at depth `z`,
+    /// see [`SectionStyle::depth`](struct.SectionStyle.html#method.depth).
+    pub fn queue_at_depth<'a, S>(&mut self, section: S, z: f32)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        self.queue_styled(section, &SectionStyle::default().depth(z))
+    }
+}
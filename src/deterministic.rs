@@ -0,0 +1,33 @@
+use super::*;
+
+use glyph_brush::ab_glyph::Rect;
+use glyph_brush::{SectionGeometry, SectionGlyph, ToSectionText};
+
+/// Steps per pixel glyph positions are rounded to in deterministic mode.
+const POSITION_STEPS: f32 = 64.0;
+
+/// Wraps a layout, rounding its glyph positions to multiples of 1/64 pixel so that
+/// tiny floating point differences between platforms don't show up in the result.
+#[derive(Hash)]
+pub(crate) struct RoundedLayout<'a, G>(pub(crate) &'a G);
+
+impl<'a, G: GlyphPositioner> GlyphPositioner for RoundedLayout<'a, G> {
+    fn calculate_glyphs<F: Font, S: ToSectionText>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph> {
+        let mut glyphs = self.0.calculate_glyphs(fonts, geometry, sections);
+        for glyph in &mut glyphs {
+            let position = &mut glyph.glyph.position;
+            position.x = (position.x * POSITION_STEPS).round() / POSITION_STEPS;
+            position.y = (position.y * POSITION_STEPS).round() / POSITION_STEPS;
+        }
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.0.bounds_rect(geometry)
+    }
+}
@@ -0,0 +1,189 @@
+use glium::framebuffer::ValidationError;
+use glium::index::BufferCreationError as IndexBufferCreationError;
+use glium::program::ProgramCreationError;
+use glium::texture::TextureCreationError;
+use glium::vertex::BufferCreationError;
+use glyph_brush::ab_glyph::InvalidFont;
+use glyph_brush::FontId;
+use std::error::Error;
+use std::fmt;
+
+/// How a brush reacts to GPU resources failing to allocate while drawing,
+/// typically because of memory pressure.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum GpuErrorPolicy {
+    /// Discard the queued sections and return the error from the draw call.
+    #[default]
+    ReturnError,
+    /// Try allocating smaller resources first: grow the cache texture in only one
+    /// dimension, or draw as many glyphs as fit into a smaller vertex buffer.
+    /// Returns the error if that fails as well.
+    RetrySmaller,
+    /// Discard the queued sections and draw no text this frame, without an error.
+    SkipFrame,
+}
+
+/// Error returned when drawing queued sections.
+#[derive(Debug)]
+pub enum DrawError {
+    /// The glyph cache texture couldn't be grown.
+    TextureAllocation(TextureCreationError),
+    /// The vertex buffer for the glyph quads couldn't be allocated.
+    VertexBufferAllocation(BufferCreationError),
+    /// The index buffer for the glyph quads couldn't be allocated, in contexts
+    /// without instanced arrays.
+    IndexBufferAllocation(IndexBufferCreationError),
+    /// The draw call itself failed.
+    Draw(glium::DrawError),
+    /// An offscreen framebuffer, e.g. for order-independent transparency,
+    /// couldn't be created.
+    Framebuffer(ValidationError),
+    /// A single glyph doesn't fit into the largest cache texture the context supports.
+    /// The queued sections were discarded.
+    ///
+    /// Frames whose glyphs only don't fit all at once are drawn in batches instead, see
+    /// [`Metrics::draw_batches`](struct.Metrics.html#structfield.draw_batches). The first
+    /// such frame is skipped, see
+    /// [`Metrics::frame_skipped`](struct.Metrics.html#structfield.frame_skipped).
+    CacheFull,
+    /// A shader program created on first use, e.g. for a debug view, couldn't be compiled.
+    Program(ProgramCreationError),
+}
+
+impl fmt::Display for DrawError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrawError::TextureAllocation(e) => {
+                write!(f, "failed to grow glyph cache texture: {}", e)
+            }
+            DrawError::VertexBufferAllocation(e) => {
+                write!(f, "failed to create vertex buffer: {}", e)
+            }
+            DrawError::IndexBufferAllocation(e) => {
+                write!(f, "failed to create index buffer: {}", e)
+            }
+            DrawError::Draw(e) => write!(f, "failed to draw glyphs: {}", e),
+            DrawError::Framebuffer(e) => write!(f, "failed to create framebuffer: {}", e),
+            DrawError::CacheFull => {
+                write!(
+                    f,
+                    "glyphs don't fit into the largest supported cache texture"
+                )
+            }
+            DrawError::Program(e) => write!(f, "failed to create shader program: {}", e),
+        }
+    }
+}
+
+impl Error for DrawError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DrawError::TextureAllocation(e) => Some(e),
+            DrawError::VertexBufferAllocation(e) => Some(e),
+            DrawError::IndexBufferAllocation(e) => Some(e),
+            DrawError::Draw(e) => Some(e),
+            DrawError::Framebuffer(e) => Some(e),
+            DrawError::CacheFull => None,
+            DrawError::Program(e) => Some(e),
+        }
+    }
+}
+
+impl From<TextureCreationError> for DrawError {
+    fn from(e: TextureCreationError) -> Self {
+        DrawError::TextureAllocation(e)
+    }
+}
+
+impl From<BufferCreationError> for DrawError {
+    fn from(e: BufferCreationError) -> Self {
+        DrawError::VertexBufferAllocation(e)
+    }
+}
+
+impl From<IndexBufferCreationError> for DrawError {
+    fn from(e: IndexBufferCreationError) -> Self {
+        DrawError::IndexBufferAllocation(e)
+    }
+}
+
+impl From<ProgramCreationError> for DrawError {
+    fn from(e: ProgramCreationError) -> Self {
+        DrawError::Program(e)
+    }
+}
+
+impl From<glium::DrawError> for DrawError {
+    fn from(e: glium::DrawError) -> Self {
+        DrawError::Draw(e)
+    }
+}
+
+impl From<ValidationError> for DrawError {
+    fn from(e: ValidationError) -> Self {
+        DrawError::Framebuffer(e)
+    }
+}
+
+/// Error returned when font data added at runtime can't be parsed.
+#[derive(Debug)]
+pub struct FontError {
+    error: InvalidFont,
+    /// Id of the [fallback font](struct.GlyphBrush.html#method.set_fallback_font) added
+    /// in place of the failed one, if one is set. Sections using it render with the
+    /// fallback font.
+    pub fallback: Option<FontId>,
+}
+
+impl FontError {
+    pub(crate) fn new(error: InvalidFont, fallback: Option<FontId>) -> Self {
+        FontError { error, fallback }
+    }
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.fallback {
+            Some(id) => write!(f, "{}, using fallback font {}", self.error, id.0),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl Error for FontError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Error returned when loading an installed font by family name.
+#[cfg(feature = "system-fonts")]
+#[derive(Debug)]
+pub enum SystemFontError {
+    /// No installed font has the family name.
+    NotFound(String),
+    /// The font file of the family couldn't be parsed.
+    InvalidFont(InvalidFont),
+}
+
+#[cfg(feature = "system-fonts")]
+impl fmt::Display for SystemFontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SystemFontError::NotFound(family) => {
+                write!(f, "no installed font of family {:?}", family)
+            }
+            SystemFontError::InvalidFont(e) => write!(f, "failed to parse system font: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "system-fonts")]
+impl Error for SystemFontError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SystemFontError::NotFound(_) => None,
+            SystemFontError::InvalidFont(e) => Some(e),
+        }
+    }
+}
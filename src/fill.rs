@@ -0,0 +1,161 @@
+use super::*;
+
+use glyph_brush::ab_glyph::Rect;
+use std::collections::HashMap;
+use std::hash::Hasher;
+use std::ops::Range;
+
+/// Identifies a fill texture added with
+/// [`GlyphBrush::add_fill_texture`](struct.GlyphBrush.html#method.add_fill_texture).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FillId(usize);
+
+/// An image filling the glyphs of a section, which are then only used as a mask,
+/// e.g. for photo-filled headlines.
+///
+/// The fill is multiplied with the text color.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextFill {
+    pub texture: FillId,
+    /// Affine transform from pixel coordinates to texture coordinates, given as rows.
+    /// Texture coordinates outside of `0..1` repeat the texture.
+    pub uv_transform: [[f32; 3]; 2],
+}
+
+impl TextFill {
+    /// Stretches the texture over the given pixel rectangle, typically the bounds
+    /// of the section.
+    pub fn stretched(texture: FillId, rect: Rect) -> Self {
+        let (width, height) = (rect.width(), rect.height());
+        TextFill {
+            texture,
+            uv_transform: [
+                [1.0 / width, 0.0, -rect.min.x / width],
+                [0.0, 1.0 / height, -rect.min.y / height],
+            ],
+        }
+    }
+
+    /// Tiles the texture at its own size, starting at `origin`.
+    pub fn tiled(texture: FillId, (width, height): (u32, u32), origin: (f32, f32)) -> Self {
+        TextFill::stretched(
+            texture,
+            Rect {
+                min: point(origin.0, origin.1),
+                max: point(origin.0 + width as f32, origin.1 + height as f32),
+            },
+        )
+    }
+}
+
+impl Hash for TextFill {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.texture.hash(state);
+        for row in &self.uv_transform {
+            for v in row {
+                v.to_bits().hash(state);
+            }
+        }
+    }
+}
+
+/// Fill textures owned by a brush.
+#[derive(Default)]
+pub(crate) struct FillTextures {
+    textures: HashMap<FillId, Texture2d>,
+    next_id: usize,
+}
+
+impl FillTextures {
+    pub(crate) fn get(&self, id: FillId) -> Option<&Texture2d> {
+        self.textures.get(&id)
+    }
+
+    pub(crate) fn texture_bytes(&self) -> usize {
+        self.textures
+            .values()
+            .map(|t| t.width() as usize * t.height() as usize * 4)
+            .sum()
+    }
+}
+
+/// State the glyphs drawn with one draw call share.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Batch {
+    pub(crate) layer: usize,
+    pub(crate) params: Option<DrawParamsId>,
+    pub(crate) fill: Option<FillId>,
+    /// sampled with nearest filtering
+    pub(crate) nearest: bool,
+    /// pinned glyphs, which are dropped before drawing
+    pub(crate) pinned: bool,
+    /// id of the retained section of the glyph, not part of the draw state
+    pub(crate) retained: Option<u32>,
+}
+
+impl Batch {
+    /// The draw state without the retained section, which doesn't split batches.
+    fn state(self) -> Batch {
+        Batch {
+            retained: None,
+            ..self
+        }
+    }
+}
+
+/// Sorts vertices by their layer, draw parameters, fill texture and filtering, keeping the queue order within
+/// each batch unless `depth_sorted`, and returns the ranges drawn with the same state.
+pub(crate) fn batch_by_fill(
+    verts: &mut [(Batch, GlyphVertex)],
+    depth_sorted: bool,
+) -> Vec<(Batch, Range<usize>)> {
+    if depth_sorted {
+        // back to front
+        verts.sort_by(|(fill_a, a), (fill_b, b)| {
+            fill_a
+                .state()
+                .cmp(&fill_b.state())
+                .then(b.left_top[2].total_cmp(&a.left_top[2]))
+        });
+    } else {
+        verts.sort_by_key(|&(fill, _)| fill.state());
+    }
+    let mut batches: Vec<(Batch, Range<usize>)> = Vec::new();
+    for (i, &(fill, _)) in verts.iter().enumerate() {
+        let fill = fill.state();
+        match batches.last_mut() {
+            Some((last, range)) if *last == fill => range.end = i + 1,
+            _ => batches.push((fill, i..i + 1)),
+        }
+    }
+    batches
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Adds a texture to fill sections with, see [`queue_filled`](#method.queue_filled).
+    pub fn add_fill_texture(&mut self, texture: Texture2d) -> FillId {
+        let id = FillId(self.fill_textures.next_id);
+        self.fill_textures.next_id += 1;
+        self.fill_textures.textures.insert(id, texture);
+        id
+    }
+
+    /// Removes a fill texture, returning it. Sections queued with it are drawn
+    /// unfilled.
+    pub fn remove_fill_texture(&mut self, id: FillId) -> Option<Texture2d> {
+        self.fill_textures.textures.remove(&id)
+    }
+
+    /// Queues a section like [`queue`](#method.queue), with its glyphs masking
+    /// the given fill.
+    ///
+    /// Sections are batched by fill texture when drawn, so sections with different
+    /// fills aren't necessarily drawn in the order they were queued.
+    /// Color glyphs aren't filled.
+    pub fn queue_filled<'a, S>(&mut self, section: S, fill: TextFill)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        self.queue_styled(section, &SectionStyle::default().fill(fill))
+    }
+}
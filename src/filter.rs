@@ -0,0 +1,159 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use glyph_brush::{OwnedSection, OwnedText, Section};
+
+/// What to do with characters rejected by a [`GlyphFilter`](struct.GlyphFilter.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DisallowedGlyph {
+    /// Drop the character from the text.
+    Skip,
+    /// Draw the given character in place of the disallowed one.
+    Replace(char),
+    /// Panic in debug builds, skip in release builds.
+    DebugPanic,
+}
+
+/// Restricts the set of characters a brush lays out and caches.
+///
+/// Useful for brushes that only ever draw a known alphabet (e.g. damage numbers),
+/// so that accidental strings can't bloat the glyph cache texture.
+///
+/// Whitespace is never filtered, as it doesn't end up in the cache texture anyway
+/// and is required for line breaking.
+#[derive(Clone, Debug)]
+pub struct GlyphFilter {
+    chars: HashSet<char>,
+    include: bool,
+    disallowed: DisallowedGlyph,
+}
+
+impl GlyphFilter {
+    /// Only allows the given characters.
+    pub fn include<I: IntoIterator<Item = char>>(chars: I) -> Self {
+        GlyphFilter {
+            chars: chars.into_iter().collect(),
+            include: true,
+            disallowed: DisallowedGlyph::Skip,
+        }
+    }
+
+    /// Allows all characters except the given ones.
+    pub fn exclude<I: IntoIterator<Item = char>>(chars: I) -> Self {
+        GlyphFilter {
+            chars: chars.into_iter().collect(),
+            include: false,
+            disallowed: DisallowedGlyph::Skip,
+        }
+    }
+
+    /// Sets the behaviour for disallowed characters.
+    ///
+    /// Defaults to `DisallowedGlyph::Skip`
+    pub fn on_disallowed(mut self, disallowed: DisallowedGlyph) -> Self {
+        self.disallowed = disallowed;
+        self
+    }
+
+    /// Returns whether the filter lets `c` through.
+    pub fn allows(&self, c: char) -> bool {
+        c.is_whitespace() || self.chars.contains(&c) == self.include
+    }
+
+    /// Returns a filtered copy of `section`, or `None` if all of its characters
+    /// are allowed and it can be used as is.
+    pub(crate) fn apply<X: Clone>(&self, section: &Section<X>) -> Option<OwnedSection<X>> {
+        if section
+            .text
+            .iter()
+            .all(|t| t.text.chars().all(|c| self.allows(c)))
+        {
+            return None;
+        }
+
+        let text = section
+            .text
+            .iter()
+            .map(|t| OwnedText {
+                text: self.filter_str(t.text).into_owned(),
+                scale: t.scale,
+                font_id: t.font_id,
+                extra: t.extra.clone(),
+            })
+            .collect();
+
+        Some(OwnedSection {
+            screen_position: section.screen_position,
+            bounds: section.bounds,
+            layout: section.layout,
+            text,
+        })
+    }
+
+    fn filter_str<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if text.chars().all(|c| self.allows(c)) {
+            return Cow::Borrowed(text);
+        }
+        let mut filtered = String::with_capacity(text.len());
+        for c in text.chars() {
+            if self.allows(c) {
+                filtered.push(c);
+                continue;
+            }
+            match self.disallowed {
+                DisallowedGlyph::Skip => {}
+                DisallowedGlyph::Replace(r) => filtered.push(r),
+                DisallowedGlyph::DebugPanic => {
+                    if cfg!(debug_assertions) {
+                        panic!("character {:?} is not allowed by the glyph filter", c);
+                    }
+                }
+            }
+        }
+        Cow::Owned(filtered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::Text;
+
+    #[test]
+    fn include() {
+        let filter = GlyphFilter::include("0123456789".chars());
+        assert!(filter.allows('7'));
+        assert!(!filter.allows('a'));
+        assert!(filter.allows(' '));
+        assert_eq!(filter.filter_str("12 ab3"), "12 3");
+        assert!(matches!(filter.filter_str("1 2"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn exclude_and_replace() {
+        let filter =
+            GlyphFilter::exclude("<>".chars()).on_disallowed(DisallowedGlyph::Replace('?'));
+        assert_eq!(filter.filter_str("<a> b"), "?a? b");
+    }
+
+    #[test]
+    fn apply() {
+        let filter = GlyphFilter::include("ab".chars());
+        let section = Section::default()
+            .add_text(Text::new("ab"))
+            .add_text(Text::new("abc"));
+        let filtered = filter.apply(&section).unwrap();
+        assert_eq!(filtered.text[0].text, "ab");
+        assert_eq!(filtered.text[1].text, "ab");
+        let section = Section::default().add_text(Text::new("a b"));
+        assert!(filter.apply(&section).is_none());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not allowed by the glyph filter")]
+    fn debug_panic() {
+        let filter = GlyphFilter::include("a".chars()).on_disallowed(DisallowedGlyph::DebugPanic);
+        filter.filter_str("ab");
+    }
+}
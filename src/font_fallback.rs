@@ -0,0 +1,161 @@
+use super::*;
+
+use glyph_brush::OwnedText;
+
+/// What to do with characters that no font of their text has a glyph for.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MissingGlyph {
+    /// Draw the missing glyph of the text's font, usually a box.
+    #[default]
+    Keep,
+    /// Drop the character from the text.
+    Skip,
+    /// Draw the given character instead, e.g. U+FFFD `'\u{FFFD}'`.
+    Replace(char),
+}
+
+pub(crate) type MissingGlyphHook = dyn FnMut(char) -> MissingGlyph;
+
+impl<F: Font, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Sets fonts to draw characters with that a text's font has no glyph for, e.g. a CJK
+    /// and then an emoji font after a Latin one. Each such character is taken from the
+    /// first font in `fallbacks` that has a glyph for it, instead of drawing the missing
+    /// glyph box of the text's font.
+    ///
+    /// Characters keep the scale and data of their text, whitespace and control
+    /// characters stay in the font of the characters before them. Applies to sections
+    /// queued and measured afterwards.
+    ///
+    /// Defaults to no fallbacks.
+    pub fn set_font_fallbacks(&mut self, fallbacks: Vec<FontId>) {
+        self.font_fallbacks = fallbacks;
+    }
+
+    /// Returns the fallback fonts set with
+    /// [`set_font_fallbacks`](#method.set_font_fallbacks).
+    pub fn font_fallbacks(&self) -> &[FontId] {
+        &self.font_fallbacks
+    }
+
+    /// Sets what to do with characters that neither their text's font nor any of the
+    /// [fallback fonts](#method.set_font_fallbacks) have a glyph for, unless a
+    /// [hook](#method.on_missing_glyph) decides. Whitespace and control characters are
+    /// never missing.
+    ///
+    /// Defaults to the policy set with
+    /// [`GlyphBrushBuilder::missing_glyph`](struct.GlyphBrushBuilder.html#method.missing_glyph).
+    pub fn set_missing_glyph(&mut self, policy: MissingGlyph) {
+        self.missing_glyph = policy;
+    }
+
+    /// Calls `hook` for each character without a glyph in any of its fonts whenever a
+    /// section containing it is queued or measured, e.g. to log it, and draws the
+    /// character as the returned policy says instead of the
+    /// [default policy](#method.set_missing_glyph). Replaces a previous hook.
+    ///
+    /// ```no_run
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::{GlyphBrush, MissingGlyph};
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # fn f(brush: &mut GlyphBrush<FontArc>) {
+    /// brush.on_missing_glyph(|c| {
+    ///     eprintln!("no glyph for {:?}", c);
+    ///     MissingGlyph::Replace('\u{FFFD}')
+    /// });
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn on_missing_glyph<C>(&mut self, hook: C)
+    where
+        C: FnMut(char) -> MissingGlyph + 'static,
+    {
+        self.missing_glyph_hook = Some(Box::new(hook));
+    }
+
+    /// Returns whether the font has a glyph for `c`.
+    fn has_glyph(&self, font_id: FontId, c: char) -> bool {
+        self.glyph_brush
+            .fonts()
+            .get(font_id.0)
+            .is_some_and(|font| font.glyph_id(c).0 != 0)
+    }
+
+    /// Returns the font to draw `c` of a text in `font_id` with, `None` if no font has
+    /// a glyph for it.
+    fn font_for(&self, font_id: FontId, c: char) -> Option<FontId> {
+        if self.has_glyph(font_id, c) {
+            return Some(font_id);
+        }
+        self.font_fallbacks
+            .iter()
+            .copied()
+            .find(|&id| self.has_glyph(id, c))
+    }
+
+    /// Returns a copy of `section` with the characters missing from their font moved
+    /// into texts of the fallback fonts and those missing from all of them handled as
+    /// configured, `None` if it can be used as is.
+    pub(crate) fn apply_font_fallbacks(&mut self, section: &Section<X>) -> Option<OwnedSection<X>> {
+        if self.font_fallbacks.is_empty()
+            && self.missing_glyph == MissingGlyph::Keep
+            && self.missing_glyph_hook.is_none()
+        {
+            return None;
+        }
+        let mut changed = false;
+        let mut text = Vec::with_capacity(section.text.len());
+        for t in &section.text {
+            let mut runs: Vec<(String, FontId)> = Vec::new();
+            for c in t.text.chars() {
+                let last = runs.last().map(|&(_, id)| id);
+                let found = match c.is_whitespace() || c.is_control() {
+                    true => Some((c, last.unwrap_or(t.font_id))),
+                    false => self.font_for(t.font_id, c).map(|id| (c, id)),
+                };
+                let (c, font_id) = match found {
+                    Some(found) => found,
+                    None => {
+                        let policy = match &mut self.missing_glyph_hook {
+                            Some(hook) => hook(c),
+                            None => self.missing_glyph,
+                        };
+                        match policy {
+                            MissingGlyph::Keep => (c, t.font_id),
+                            MissingGlyph::Skip => {
+                                changed = true;
+                                continue;
+                            }
+                            MissingGlyph::Replace(r) => {
+                                changed = true;
+                                (r, self.font_for(t.font_id, r).unwrap_or(t.font_id))
+                            }
+                        }
+                    }
+                };
+                changed |= font_id != t.font_id;
+                match runs.last_mut() {
+                    Some((run, id)) if *id == font_id => run.push(c),
+                    _ => runs.push((c.to_string(), font_id)),
+                }
+            }
+            if runs.is_empty() {
+                runs.push((String::new(), t.font_id));
+            }
+            text.extend(runs.into_iter().map(|(run, font_id)| OwnedText {
+                text: run,
+                scale: t.scale,
+                font_id,
+                extra: t.extra.clone(),
+            }));
+        }
+        if !changed {
+            return None;
+        }
+        Some(OwnedSection {
+            screen_position: section.screen_position,
+            bounds: section.bounds,
+            layout: section.layout,
+            text,
+        })
+    }
+}
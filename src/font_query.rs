@@ -0,0 +1,200 @@
+use super::*;
+
+/// Describes a font to find among the fonts of a brush with
+/// [`GlyphBrush::query_font`](struct.GlyphBrush.html#method.query_font).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FontQuery<'a> {
+    /// Family name, compared ignoring ASCII case, e.g. `"DejaVu Sans"`.
+    pub family: &'a str,
+    /// Weight from 1 to 1000, e.g. 400 for regular and 700 for bold.
+    pub weight: u16,
+    pub italic: bool,
+}
+
+impl<'a> FontQuery<'a> {
+    /// Queries the regular, upright face of a family.
+    pub fn new(family: &'a str) -> Self {
+        FontQuery {
+            family,
+            weight: 400,
+            italic: false,
+        }
+    }
+
+    /// Sets the weight, e.g. 700 for bold.
+    pub fn with_weight(mut self, weight: u16) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_italic(mut self, italic: bool) -> Self {
+        self.italic = italic;
+        self
+    }
+}
+
+/// A font of a brush with its family and style.
+struct Face {
+    font_id: FontId,
+    family: String,
+    weight: u16,
+    italic: bool,
+}
+
+/// Families and styles of the fonts of a brush, as far as they are known.
+#[derive(Default)]
+pub(crate) struct FontFaces {
+    faces: Vec<Face>,
+}
+
+impl FontFaces {
+    pub(crate) fn set(&mut self, font_id: FontId, family: &str, weight: u16, italic: bool) {
+        self.faces.retain(|f| f.font_id != font_id);
+        self.faces.push(Face {
+            font_id,
+            family: family.to_owned(),
+            weight,
+            italic,
+        });
+    }
+
+    /// Returns the font of the queried family closest to the queried style.
+    fn query(&self, query: &FontQuery) -> Option<FontId> {
+        self.family(query.family)
+            .min_by_key(|f| {
+                (
+                    f.italic != query.italic,
+                    weight_rank(query.weight, f.weight),
+                )
+            })
+            .map(|f| f.font_id)
+    }
+
+    /// Forgets the family and style of a font, e.g. after it was replaced.
+    pub(crate) fn forget(&mut self, font_id: FontId) {
+        self.faces.retain(|f| f.font_id != font_id);
+    }
+
+    fn family<'a>(&'a self, family: &'a str) -> impl Iterator<Item = &'a Face> + 'a {
+        self.faces
+            .iter()
+            .filter(move |f| f.family.eq_ignore_ascii_case(family))
+    }
+}
+
+/// Orders the weights of a family by how well they match the queried one, like CSS font
+/// matching: by group of preferred weights, then by distance.
+fn weight_rank(query: u16, weight: u16) -> (u8, u16) {
+    let group = match query {
+        400..=500 if weight >= query && weight <= 500 => 0,
+        400..=500 if weight < query => 1,
+        400..=500 => 2,
+        0..=399 if weight <= query => 0,
+        501..=u16::MAX if weight >= query => 0,
+        _ => 1,
+    };
+    (group, weight.abs_diff(query))
+}
+
+impl<F: Font, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Sets the family and style of a font, so that it is found by
+    /// [`query_font`](#method.query_font), e.g. after adding the regular, bold and italic
+    /// faces of a family. Replaces what was set for the font before.
+    ///
+    /// Fonts added with `add_system_font` are set up already.
+    pub fn set_font_style(&mut self, font_id: FontId, family: &str, weight: u16, italic: bool) {
+        self.font_faces.set(font_id, family, weight, italic);
+    }
+
+    /// Returns the font of the queried family closest to the queried style, or `None` if
+    /// no font was set to be of the family with [`set_font_style`](#method.set_font_style).
+    ///
+    /// The queried slant is preferred over any weight. Weights are matched like CSS does:
+    /// a query from 400 to 500 takes the closest weight up to 500 at or above it first,
+    /// then lighter ones, then ones above 500. Lighter queries prefer lighter weights and
+    /// heavier queries heavier ones, e.g. a query for 700 takes 900 over 600.
+    ///
+    /// ```no_run
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::{FontQuery, GlyphBrush};
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # use glium_glyph::glyph_brush::{FontId, Section, Text};
+    /// # fn f(brush: &mut GlyphBrush<FontArc>, bold: FontArc) {
+    /// let bold = brush.add_font(bold);
+    /// brush.set_font_style(FontId(0), "Inter", 400, false);
+    /// brush.set_font_style(bold, "Inter", 700, false);
+    ///
+    /// let font = brush.query_font(&FontQuery::new("Inter").with_weight(700));
+    /// let text = Text::new("Warning").with_font_id(font.unwrap_or_default());
+    /// brush.queue(Section::default().add_text(text));
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn query_font(&self, query: &FontQuery) -> Option<FontId> {
+        self.font_faces.query(query)
+    }
+
+    /// Returns the fonts set to be of a family with
+    /// [`set_font_style`](#method.set_font_style), in the order they were set.
+    pub fn family_fonts(&self, family: &str) -> Vec<FontId> {
+        self.font_faces.family(family).map(|f| f.font_id).collect()
+    }
+
+    /// Returns the family a font was set to be of with
+    /// [`set_font_style`](#method.set_font_style).
+    pub fn font_family(&self, font_id: FontId) -> Option<&str> {
+        self.font_faces
+            .faces
+            .iter()
+            .find(|f| f.font_id == font_id)
+            .map(|f| &f.family[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(weights: &[u16], weight: u16) -> u16 {
+        let mut faces = FontFaces::default();
+        for (i, &w) in weights.iter().enumerate() {
+            faces.set(FontId(i), "Inter", w, false);
+        }
+        let font_id = faces.query(&FontQuery::new("inter").with_weight(weight));
+        weights[font_id.unwrap().0]
+    }
+
+    #[test]
+    fn css_weights() {
+        assert_eq!(query(&[300, 500], 400), 500);
+        assert_eq!(query(&[300, 600], 400), 300);
+        assert_eq!(query(&[400, 500, 600], 450), 500);
+        assert_eq!(query(&[200, 600], 500), 200);
+        assert_eq!(query(&[200, 400], 300), 200);
+        assert_eq!(query(&[400, 500], 300), 400);
+        assert_eq!(query(&[600, 900], 700), 900);
+        assert_eq!(query(&[100, 600], 700), 600);
+        assert_eq!(query(&[100, 400, 700], 400), 400);
+    }
+
+    #[test]
+    fn slant_before_weight() {
+        let mut faces = FontFaces::default();
+        faces.set(FontId(0), "Inter", 400, false);
+        faces.set(FontId(1), "Inter", 900, true);
+        let italic = FontQuery::new("Inter").with_italic(true);
+        assert_eq!(faces.query(&italic), Some(FontId(1)));
+        assert_eq!(faces.query(&FontQuery::new("Other")), None);
+    }
+
+    #[test]
+    fn forget() {
+        let mut faces = FontFaces::default();
+        faces.set(FontId(0), "Inter", 400, false);
+        faces.set(FontId(1), "Inter", 700, false);
+        faces.forget(FontId(0));
+        assert_eq!(faces.query(&FontQuery::new("Inter")), Some(FontId(1)));
+        faces.forget(FontId(1));
+        assert_eq!(faces.query(&FontQuery::new("Inter")), None);
+    }
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+/// Per glyph data carried by the texts of sections through layout to vertex generation,
+/// `Extra` by default. Implement it to attach custom data to glyphs, e.g. an object id,
+/// and build the brush with
+/// [`GlyphBrushBuilder::glyph_data`](struct.GlyphBrushBuilder.html#method.glyph_data).
+///
+/// Sections hash their data to decide whether they need to be laid out again, so
+/// the hash must change whenever the data affects the drawn glyphs.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::GlyphData;
+/// # use glium_glyph::glyph_brush::{Section, Text};
+/// # fn main() {
+/// #[derive(Clone, Default, Hash, PartialEq)]
+/// struct Label {
+///     object_id: u32,
+///     color: [u8; 4],
+/// }
+///
+/// impl GlyphData for Label {
+///     fn color(&self) -> [f32; 4] {
+///         self.color.map(|c| c as f32 / 255.0)
+///     }
+///
+///     fn z(&self) -> f32 {
+///         0.0
+///     }
+///
+///     fn with_color(&self, color: [f32; 4]) -> Self {
+///         let color = color.map(|c| (c * 255.0).round() as u8);
+///         Label { color, ..*self }
+///     }
+/// }
+///
+/// let label = Label { object_id: 7, color: [255; 4] };
+/// let text: Text<Label> = Text::new("crate");
+/// let section = Section::new().add_text(text.with_extra(label));
+/// # let _ = section;
+/// // let mut glyph_brush = GlyphBrushBuilder::using_font(font)
+/// //     .glyph_data::<Label>()
+/// //     .build(&display);
+/// // glyph_brush.queue(section);
+/// # }
+/// ```
+pub trait GlyphData: Clone + Default + Hash + PartialEq + 'static {
+    /// Color the glyph is drawn in.
+    fn color(&self) -> [f32; 4];
+
+    /// Depth of the glyph, see `Extra::z`.
+    fn z(&self) -> f32;
+
+    /// Returns a copy drawn in `color`, e.g. for the shadow and outline passes
+    /// of a [`SectionStyle`](struct.SectionStyle.html).
+    fn with_color(&self, color: [f32; 4]) -> Self;
+}
+
+impl GlyphData for Extra {
+    fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    fn z(&self) -> f32 {
+        self.z
+    }
+
+    fn with_color(&self, color: [f32; 4]) -> Self {
+        Extra { color, z: self.z }
+    }
+}
@@ -0,0 +1,200 @@
+use super::*;
+
+use glyph_brush::ab_glyph::PxScale;
+use glyph_brush::{OwnedSection, OwnedText, Text};
+
+/// Largest glyph scale in pixels laid out in hardened mode.
+const HARDENED_MAX_SCALE: f32 = 1024.0;
+/// Most combining marks kept after a base character in hardened mode.
+const HARDENED_MAX_MARKS: usize = 8;
+
+/// Combining marks from the combining diacritical mark blocks, which stack up
+/// on their base character.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE20}'..='\u{FE2F}')
+}
+
+/// Bidirectional formatting characters, which have no effect on the layout.
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Drops bidi controls and combining marks beyond the limit.
+fn harden_str(text: &str) -> Cow<'_, str> {
+    let mut marks = 0;
+    let keep = |c: char, marks: &mut usize| {
+        if is_bidi_control(c) {
+            return false;
+        }
+        if !is_combining_mark(c) {
+            *marks = 0;
+            return true;
+        }
+        *marks += 1;
+        *marks <= HARDENED_MAX_MARKS
+    };
+    if text.chars().all(|c| keep(c, &mut marks)) {
+        return Cow::Borrowed(text);
+    }
+    marks = 0;
+    Cow::Owned(text.chars().filter(|&c| keep(c, &mut marks)).collect())
+}
+
+/// Returns a copy of `section` that's safe to lay out, or `None` if it can be used as is.
+///
+/// Texts with an unknown font or a scale that isn't positive and finite are dropped,
+/// larger scales are clamped to `HARDENED_MAX_SCALE`. Sections at a non-finite position
+/// lose all their texts, NaN bounds become unbounded.
+fn harden_section<X: Clone>(section: &Section<X>, font_count: usize) -> Option<OwnedSection<X>> {
+    let position_valid =
+        section.screen_position.0.is_finite() && section.screen_position.1.is_finite();
+    let text_valid = |t: &Text<X>| {
+        t.font_id.0 < font_count
+            && t.scale.x.is_finite()
+            && t.scale.y.is_finite()
+            && t.scale.x > 0.0
+            && t.scale.y > 0.0
+    };
+    let unchanged = position_valid
+        && !section.bounds.0.is_nan()
+        && !section.bounds.1.is_nan()
+        && section.text.iter().all(|t| {
+            text_valid(t)
+                && t.scale.x <= HARDENED_MAX_SCALE
+                && t.scale.y <= HARDENED_MAX_SCALE
+                && matches!(harden_str(t.text), Cow::Borrowed(_))
+        });
+    if unchanged {
+        return None;
+    }
+
+    let unbounded = |bound: f32| if bound.is_nan() { f32::INFINITY } else { bound };
+    let text = section
+        .text
+        .iter()
+        .filter(|t| position_valid && text_valid(t))
+        .map(|t| OwnedText {
+            text: harden_str(t.text).into_owned(),
+            scale: PxScale {
+                x: t.scale.x.min(HARDENED_MAX_SCALE),
+                y: t.scale.y.min(HARDENED_MAX_SCALE),
+            },
+            font_id: t.font_id,
+            extra: t.extra.clone(),
+        })
+        .collect();
+    Some(OwnedSection {
+        screen_position: section.screen_position,
+        bounds: (unbounded(section.bounds.0), unbounded(section.bounds.1)),
+        layout: section.layout,
+        text,
+    })
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Enables hardened mode, which makes queueing and drawing sections with arbitrary,
+    /// e.g. fuzzed, contents panic free.
+    ///
+    /// In hardened mode, before any other processing:
+    ///
+    /// * texts with an unknown `FontId` or a scale that isn't positive and finite are
+    ///   dropped, and scales above 1024 pixels are clamped to 1024,
+    /// * sections with a non-finite screen position are dropped, NaN bounds become
+    ///   unbounded,
+    /// * bidirectional formatting characters like RTL overrides are removed, they have
+    ///   no effect as text is always laid out left to right,
+    /// * more than 8 combining marks in a row after a base character are removed,
+    /// * [outlines](struct.SectionStyle.html#method.outline) are at most 64 pixels wide,
+    ///   [shadows](struct.SectionStyle.html#method.blurred_shadow) are blurred over at
+    ///   most 64 pixels and non-finite offsets of either are ignored.
+    ///
+    /// The worst case cost of a section is then linear in its length, combine this
+    /// with a [glyph cap](#method.set_glyph_cap) to bound the cost of a frame. Glyphs
+    /// that don't fit into the largest cache texture the context supports make the
+    /// draw return an error. Doesn't apply to particles.
+    ///
+    /// Defaults to `false`.
+    pub fn set_hardened(&mut self, hardened: bool) {
+        self.hardened = hardened;
+    }
+
+    /// Returns whether hardened mode is enabled.
+    pub fn hardened(&self) -> bool {
+        self.hardened
+    }
+}
+
+impl<F: Font, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Returns a sanitized copy of `section` in hardened mode, `None` if it can be used as is.
+    pub(crate) fn harden(&self, section: &Section<X>) -> Option<OwnedSection<X>> {
+        if !self.hardened {
+            return None;
+        }
+        harden_section(section, self.glyph_brush.fonts().len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combining_marks() {
+        assert!(matches!(harden_str("héllo"), Cow::Borrowed(_)));
+        let marks = "\u{0301}".repeat(100);
+        let hardened = harden_str(&format!("a{}b{}", marks, marks)).into_owned();
+        let expected = format!(
+            "a{}b{}",
+            "\u{0301}".repeat(HARDENED_MAX_MARKS),
+            "\u{0301}".repeat(HARDENED_MAX_MARKS)
+        );
+        assert_eq!(hardened, expected);
+    }
+
+    #[test]
+    fn bidi_controls() {
+        assert_eq!(harden_str("a\u{202E}b\u{2066}c"), "abc");
+    }
+
+    #[test]
+    fn scales() {
+        let section = Section::default()
+            .add_text(Text::new("nan").with_scale(f32::NAN))
+            .add_text(Text::new("inf").with_scale(f32::INFINITY))
+            .add_text(Text::new("negative").with_scale(-12.0))
+            .add_text(Text::new("zero").with_scale(0.0))
+            .add_text(Text::new("huge").with_scale(1e9))
+            .add_text(Text::new("fine").with_scale(24.0));
+        let hardened = harden_section(&section, 1).unwrap();
+        let texts: Vec<_> = hardened
+            .text
+            .iter()
+            .map(|t| (t.text.as_str(), t.scale.y))
+            .collect();
+        assert_eq!(texts, [("huge", HARDENED_MAX_SCALE), ("fine", 24.0)]);
+    }
+
+    #[test]
+    fn fonts_positions_and_bounds() {
+        let section = Section::default()
+            .add_text(Text::new("a").with_font_id(FontId(1)))
+            .add_text(Text::new("b"))
+            .with_bounds((f32::NAN, 100.0));
+        let hardened = harden_section(&section, 1).unwrap();
+        assert_eq!(hardened.text.len(), 1);
+        assert_eq!(hardened.bounds, (f32::INFINITY, 100.0));
+
+        let section = Section::default()
+            .add_text(Text::new("a"))
+            .with_screen_position((f32::NAN, 0.0));
+        assert!(harden_section(&section, 1).unwrap().text.is_empty());
+
+        let section = Section::default().add_text(Text::new("fine"));
+        assert!(harden_section(&section, 1).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+use super::*;
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Shader source files watched for changes by the brush.
+pub(crate) struct ShaderWatch {
+    vertex: PathBuf,
+    fragment: PathBuf,
+    modified: Option<(SystemTime, SystemTime)>,
+}
+
+impl ShaderWatch {
+    pub(crate) fn new(vertex: PathBuf, fragment: PathBuf) -> Self {
+        ShaderWatch {
+            vertex,
+            fragment,
+            modified: None,
+        }
+    }
+
+    fn modified(&self) -> io::Result<(SystemTime, SystemTime)> {
+        Ok((
+            fs::metadata(&self.vertex)?.modified()?,
+            fs::metadata(&self.fragment)?.modified()?,
+        ))
+    }
+
+    /// Reads both shader sources, remembering their modification times.
+    pub(crate) fn read(&mut self) -> io::Result<(String, String)> {
+        let modified = self.modified()?;
+        let sources = (
+            fs::read_to_string(&self.vertex)?,
+            fs::read_to_string(&self.fragment)?,
+        );
+        self.modified = Some(modified);
+        Ok(sources)
+    }
+
+    /// Returns whether either file changed since the last `read`.
+    fn changed(&self) -> io::Result<bool> {
+        Ok(self.modified != Some(self.modified()?))
+    }
+}
+
+/// Error returned when reloading shaders from disk.
+#[derive(Debug)]
+pub enum ShaderReloadError {
+    /// The brush wasn't built with
+    /// [`GlyphBrushBuilder::shader_paths`](struct.GlyphBrushBuilder.html#method.shader_paths).
+    NoShaderPaths,
+    /// A shader file couldn't be read.
+    Io(io::Error),
+    /// The shaders failed to compile or link.
+    Compilation(ProgramCreationError),
+    /// The shaders use vertex attributes or uniforms the brush doesn't provide.
+    Incompatible,
+}
+
+impl fmt::Display for ShaderReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderReloadError::NoShaderPaths => write!(f, "no shader paths configured"),
+            ShaderReloadError::Io(e) => write!(f, "failed to read shader: {}", e),
+            ShaderReloadError::Compilation(e) => write!(f, "failed to compile shaders: {}", e),
+            ShaderReloadError::Incompatible => {
+                write!(f, "shaders are incompatible with the glyph vertex layout")
+            }
+        }
+    }
+}
+
+impl Error for ShaderReloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ShaderReloadError::Io(e) => Some(e),
+            ShaderReloadError::Compilation(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ShaderReloadError {
+    fn from(e: io::Error) -> Self {
+        ShaderReloadError::Io(e)
+    }
+}
+
+impl From<ProgramCreationError> for ShaderReloadError {
+    fn from(e: ProgramCreationError) -> Self {
+        ShaderReloadError::Compilation(e)
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Reads the shaders from the paths given to
+    /// [`GlyphBrushBuilder::shader_paths`](struct.GlyphBrushBuilder.html#method.shader_paths)
+    /// and recompiles them.
+    ///
+    /// On error the previous shaders stay in use.
+    pub fn reload_shaders<C: Facade>(&mut self, facade: &C) -> Result<(), ShaderReloadError> {
+        let (vert, frag) = match self.shader_watch.as_mut() {
+            Some(watch) => watch.read()?,
+            None => return Err(ShaderReloadError::NoShaderPaths),
+        };
+        let program = Program::from_source(facade, &vert, &include_fill(&frag), None)?;
+        if !is_compatible_program(&program) {
+            return Err(ShaderReloadError::Incompatible);
+        }
+        self.program = Rc::new(program);
+        self.custom_shaders = true;
+        Ok(())
+    }
+
+    /// Reloads the shaders if either file was modified since they were last read.
+    ///
+    /// Meant to be called once per frame, returns whether the shaders were reloaded.
+    pub fn poll_shaders<C: Facade>(&mut self, facade: &C) -> Result<bool, ShaderReloadError> {
+        let changed = match self.shader_watch.as_ref() {
+            Some(watch) => watch.changed()?,
+            None => return Err(ShaderReloadError::NoShaderPaths),
+        };
+        if changed {
+            self.reload_shaders(facade)?;
+        }
+        Ok(changed)
+    }
+}
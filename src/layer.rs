@@ -0,0 +1,223 @@
+use super::*;
+
+use glium::DrawParameters;
+
+/// A named group of queued sections drawn together.
+struct Layer {
+    name: String,
+    order: i32,
+    params: Option<DrawParameters<'static>>,
+    /// whether the layer was drawn since the queue was last processed
+    drawn: bool,
+}
+
+/// Layers of a brush, the first one is the default layer named `""`.
+pub(crate) struct Layers {
+    layers: Vec<Layer>,
+    /// whether sections were queued since the queue was last processed
+    queued: bool,
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Layers {
+            layers: vec![Layer {
+                name: String::new(),
+                order: 0,
+                params: None,
+                drawn: false,
+            }],
+            queued: false,
+        }
+    }
+}
+
+impl Layers {
+    /// Returns the index of the layer, adding it if it doesn't exist yet.
+    pub(crate) fn index(&mut self, name: &str) -> usize {
+        if let Some(i) = self.layers.iter().position(|l| l.name == name) {
+            return i;
+        }
+        self.layers.push(Layer {
+            name: name.to_owned(),
+            order: 0,
+            params: None,
+            drawn: false,
+        });
+        self.layers.len() - 1
+    }
+
+    pub(crate) fn params(&self, index: usize) -> Option<&DrawParameters<'static>> {
+        self.layers.get(index).and_then(|l| l.params.as_ref())
+    }
+
+    pub(crate) fn queued(&mut self) {
+        self.queued = true;
+    }
+
+    pub(crate) fn processed(&mut self) {
+        self.queued = false;
+        for layer in &mut self.layers {
+            layer.drawn = false;
+        }
+    }
+
+    /// Whether drawing the selected layers needs the queue processed first, because
+    /// sections were queued or a layer was drawn already since it was last processed.
+    pub(crate) fn needs_processing(&self, select: &dyn Fn(&str) -> bool) -> bool {
+        self.queued || self.layers.iter().any(|l| l.drawn && select(&l.name))
+    }
+
+    /// Marks the selected layers as drawn and returns their indices in draw order.
+    pub(crate) fn draw(&mut self, select: &dyn Fn(&str) -> bool) -> Vec<usize> {
+        let mut selected: Vec<usize> = (0..self.layers.len())
+            .filter(|&i| select(&self.layers[i].name))
+            .collect();
+        selected.sort_by_key(|&i| self.layers[i].order);
+        for &i in &selected {
+            self.layers[i].drawn = true;
+        }
+        selected
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Sets where a layer is drawn among the others by
+    /// [`draw_queued`](#method.draw_queued), lower orders first. Layers of the same order
+    /// are drawn in the order they were first used, starting with the default layer `""`.
+    ///
+    /// Defaults to 0.
+    pub fn set_layer_order(&mut self, layer: &str, order: i32) {
+        let i = self.layers.index(layer);
+        self.layers.layers[i].order = order;
+    }
+
+    /// Sets the draw parameters of a layer's sections, overriding the brush's. Sections
+    /// queued with [their own parameters](#method.queue_with_params) keep them.
+    ///
+    /// Defaults to the brush's draw parameters.
+    pub fn set_layer_params(&mut self, layer: &str, params: Option<DrawParameters<'static>>) {
+        let i = self.layers.index(layer);
+        self.layers.layers[i].params = params;
+    }
+
+    /// Queues a section like [`queue`](#method.queue) on a named layer, e.g. `"hud"`,
+    /// to be drawn with [`draw_layer`](#method.draw_layer). Sections queued otherwise are
+    /// on the default layer `""`.
+    pub fn queue_on_layer<'a, S>(&mut self, layer: &str, section: S)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        let layer = self.layers.index(layer);
+        self.queue_styled(section, &SectionStyle::default().layer(layer))
+    }
+
+    /// Draws the sections queued on a layer, see [`queue_on_layer`](#method.queue_on_layer).
+    ///
+    /// The queue is processed for the first layer drawn in a frame, the other layers are
+    /// drawn from the same vertices, so queue the sections of all layers before drawing
+    /// any of them. [`draw_queued`](#method.draw_queued) draws all layers at once.
+    ///
+    /// Color glyphs, [custom vertices](struct.GlyphBrushBuilder.html#method.custom_vertex)
+    /// and [order-independent transparency](struct.GlyphBrushBuilder.html#method.order_independent_transparency)
+    /// don't support layers, all their glyphs are drawn with the default layer.
+    pub fn draw_layer<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        layer: &str,
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError> {
+        let transform = screen_transform(surface.get_dimensions());
+        self.draw_layer_with_transform(layer, transform, facade, surface)
+    }
+
+    /// Draws the sections queued on a layer like [`draw_layer`](#method.draw_layer),
+    /// applying a position transform like
+    /// [`draw_queued_with_transform`](#method.draw_queued_with_transform).
+    pub fn draw_layer_with_transform<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        layer: &str,
+        transform: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError> {
+        self.draw_selected(&|name| name == layer, transform, facade, surface)
+    }
+
+    /// Draws the sections on the layers whose names match `predicate`, e.g. minimap
+    /// labels to an offscreen target and the rest of the text to the frame, without a
+    /// second brush. Layers are drawn like with [`draw_layer`](#method.draw_layer).
+    ///
+    /// ```no_run
+    /// # extern crate glium;
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::GlyphBrush;
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # use glium_glyph::glyph_brush::Section;
+    /// # fn f(
+    /// #     display: &glium::Display,
+    /// #     brush: &mut GlyphBrush<FontArc>,
+    /// #     minimap: &mut glium::framebuffer::SimpleFrameBuffer,
+    /// #     frame: &mut glium::Frame,
+    /// #     town: Section,
+    /// #     score: Section,
+    /// # ) {
+    /// brush.queue_on_layer("minimap", town);
+    /// brush.queue(score);
+    /// brush
+    ///     .draw_queued_filtered(|layer| layer == "minimap", display, minimap)
+    ///     .unwrap();
+    /// brush
+    ///     .draw_queued_filtered(|layer| layer != "minimap", display, frame)
+    ///     .unwrap();
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn draw_queued_filtered<P, C, S>(
+        &mut self,
+        predicate: P,
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError>
+    where
+        P: Fn(&str) -> bool,
+        C: Facade + Deref<Target = Context>,
+        S: Surface,
+    {
+        let transform = screen_transform(surface.get_dimensions());
+        self.draw_selected(&predicate, transform, facade, surface)
+    }
+
+    /// Draws the sections on the layers whose names match `predicate` like
+    /// [`draw_queued_filtered`](#method.draw_queued_filtered), applying a position
+    /// transform like [`draw_queued_with_transform`](#method.draw_queued_with_transform).
+    pub fn draw_queued_filtered_with_transform<P, C, S>(
+        &mut self,
+        predicate: P,
+        transform: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError>
+    where
+        P: Fn(&str) -> bool,
+        C: Facade + Deref<Target = Context>,
+        S: Surface,
+    {
+        self.draw_selected(&predicate, transform, facade, surface)
+    }
+
+    /// Draws the selected layers, processing the queue first if needed.
+    fn draw_selected<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        select: &dyn Fn(&str) -> bool,
+        transform: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError> {
+        if self.layers.needs_processing(select) && !self.process_for_draw(facade)? {
+            return Ok(self.metrics);
+        }
+        self.draw_batches(select, transform, NO_TINT, facade, surface)?;
+        Ok(self.metrics)
+    }
+}
@@ -1,26 +1,165 @@
+#[cfg(feature = "system-fonts")]
+extern crate fontdb;
 #[macro_use]
 extern crate glium;
 #[macro_use]
 pub extern crate glyph_brush;
+#[cfg(feature = "color-glyphs")]
+extern crate png;
+#[cfg(feature = "color-glyphs")]
+extern crate ttf_parser;
 
+mod advance;
+mod anchor;
+#[cfg(feature = "color-glyphs")]
+mod atlas;
+mod atlas_debug;
+mod axis;
+mod bake;
+mod baseline;
+mod billboard;
 mod builder;
+#[cfg(feature = "color-glyphs")]
+mod color;
+#[cfg(feature = "color-glyphs")]
+mod colr;
+mod console;
+mod custom_vertex;
+mod damage;
+mod depth;
+mod deterministic;
+mod error;
+mod fill;
+mod filter;
+mod font_fallback;
+mod font_query;
+mod glyph_data;
+mod harden;
+#[cfg(feature = "shader-hot-reload")]
+mod hot_reload;
+mod layer;
+mod limit;
+mod line_align;
+#[cfg(feature = "markup")]
+mod markup;
+mod measure;
+mod memory;
+mod metrics;
+mod msdf;
+mod oit;
+mod overflow;
+mod params;
+mod particles;
+mod pin;
+mod precache;
+mod preformatted;
+mod quads;
+mod retained;
+mod runs;
+mod sdf;
+mod shared;
+mod snap;
+mod snapshot;
+mod style;
+mod subpixel;
+#[cfg(feature = "system-fonts")]
+mod system_font;
+mod table;
+mod tint;
+mod transform;
+mod user_uniforms;
+mod variable;
+mod vertices;
+mod whitespace;
+mod world;
 
-pub use builder::GlyphBrushBuilder;
+pub use advance::{AdvanceRounding, AdvanceRoundingLayout};
+pub use anchor::BottomAnchoredLayout;
+#[cfg(feature = "color-glyphs")]
+pub use atlas::{AtlasAllocator, GuillotineAllocator, ShelfAllocator, SkylineAllocator};
+use atlas_debug::CacheRegions;
+pub use axis::{AxisLabelPlacement, AxisLabels};
+pub use bake::{AtlasBaker, AtlasParseError, BakedAtlas, BakedBrush};
+pub use baseline::{BaselineAnchor, BaselineLayout};
+pub use billboard::Billboard;
+pub use builder::{BuildError, GlyphBrushBuilder};
+#[cfg(feature = "color-glyphs")]
+use color::{ColorGlyphs, QueuedImage};
+#[cfg(feature = "color-glyphs")]
+use colr::ColorLayers;
+pub use console::{ConsoleView, Severity};
+use custom_vertex::CustomVertices;
+pub use custom_vertex::GlyphQuad;
+use damage::Damage;
+pub use depth::DepthPreset;
+use deterministic::RoundedLayout;
+#[cfg(feature = "system-fonts")]
+pub use error::SystemFontError;
+pub use error::{DrawError, FontError, GpuErrorPolicy};
+use fill::{batch_by_fill, Batch, FillTextures};
+pub use fill::{FillId, TextFill};
+pub use filter::{DisallowedGlyph, GlyphFilter};
+pub use font_fallback::MissingGlyph;
+use font_fallback::MissingGlyphHook;
+use font_query::FontFaces;
+pub use font_query::FontQuery;
+pub use glyph_data::GlyphData;
+#[cfg(feature = "shader-hot-reload")]
+pub use hot_reload::ShaderReloadError;
+#[cfg(feature = "shader-hot-reload")]
+use hot_reload::ShaderWatch;
+use layer::Layers;
+pub use line_align::LineAlignLayout;
+#[cfg(feature = "markup")]
+pub use markup::{Markup, MarkupError};
+pub use measure::ContentSize;
+pub use memory::MemoryUsage;
+pub use metrics::Metrics;
+pub use msdf::{MsdfAtlas, MsdfBrush};
+use oit::Oit;
+use overflow::Overflow;
+pub use params::DrawParamsId;
+use params::DrawParamsSets;
+pub use particles::GlyphParticle;
+pub use preformatted::PreformattedLayout;
+pub use quads::BufferStorage;
+use quads::{QuadBuffer, Quads, MAX_GLYPHS_PER_DRAW};
+use retained::Retained;
+pub use runs::TextRun;
+pub use sdf::RasterMode;
+pub use shared::GlyphBrushShared;
+use snap::snap_to_pixels;
+pub use snapshot::{LayoutSnapshot, SnapshotGlyph};
+pub use style::{ProceduralFill, SectionStyle};
+use subpixel::Subpixel;
+pub use subpixel::SubpixelOrder;
+pub use table::{Column, ColumnWidth, Table};
+use tint::NO_TINT;
+pub use transform::GlyphTransform;
+use transform::{with_glyph_extra, GlyphExtra};
+use user_uniforms::WithUserUniforms;
+use variable::VariableFonts;
+pub use whitespace::WhitespaceLayout;
+pub use world::TextPlane;
 
 use std::borrow::Cow;
 use std::hash::{BuildHasher, Hash};
-use std::ops::Deref;
+use std::ops::{Deref, Range};
+use std::rc::Rc;
 
 use glium::backend::{Context, Facade};
 use glium::index::PrimitiveType;
+use glium::program::ProgramCreationError;
 use glium::texture::texture2d::Texture2d;
 use glium::texture::{ClientFormat, RawImage2d};
-use glium::{Program, Surface};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, UniformType, UniformValue};
+use glium::vertex::BufferCreationError;
+use glium::{CapabilitiesSource, Program, Surface, Vertex};
 
-use glyph_brush::ab_glyph::{point, Font};
+use glyph_brush::ab_glyph::{point, Font, FontVec, InvalidFont};
 use glyph_brush::{
-    BrushAction, BrushError, DefaultSectionHasher, FontId, GlyphCruncher, GlyphPositioner, Section,
-    SectionGlyphIter,
+    BrushAction, BrushError, DefaultSectionHasher, FontId, GlyphCruncher, GlyphPositioner,
+    OwnedSection, Section, SectionGlyph, SectionGlyphIter,
 };
 use glyph_brush::{Extra, Rectangle};
 
@@ -34,6 +173,17 @@ struct GlyphVertex {
     tex_right_bottom: [f32; 2],
     /// text color
     color: [f32; 4],
+    /// rows of the glyph's affine transform
+    transform_x: [f32; 3],
+    transform_y: [f32; 3],
+    /// rows of the transform from pixel to fill texture coordinates
+    fill_x: [f32; 3],
+    fill_y: [f32; 3],
+    /// procedural fill kind and parameters, 0 for none
+    procedural: [f32; 4],
+    procedural_color: [f32; 4],
+    /// stroke width in pixels of hollow glyphs, 0 for filled ones
+    hollow: f32,
 }
 
 implement_vertex!(
@@ -42,15 +192,24 @@ implement_vertex!(
     right_bottom,
     tex_left_top,
     tex_right_bottom,
-    color
+    color,
+    transform_x,
+    transform_y,
+    fill_x,
+    fill_y,
+    procedural,
+    procedural_color,
+    hollow
 );
 
+/// A corner of the unit quad each glyph is drawn over, from `[0.0, 0.0]` at the
+/// left top to `[1.0, 1.0]`.
 #[derive(Copy, Clone, Debug)]
-struct InstanceVertex {
-    v: f32,
+struct QuadCorner {
+    corner: [f32; 2],
 }
 
-implement_vertex!(InstanceVertex, v);
+implement_vertex!(QuadCorner, corner);
 
 fn rect_to_rect(rect: Rectangle<u32>) -> glium::Rect {
     glium::Rect {
@@ -71,16 +230,198 @@ fn update_texture(tex: &Texture2d, rect: Rectangle<u32>, tex_data: &[u8]) {
     tex.write(rect_to_rect(rect), image);
 }
 
+/// Projection mapping pixel coordinates onto a framebuffer with the given dimensions.
+fn screen_transform((width, height): (u32, u32)) -> [[f32; 4]; 4] {
+    [
+        [2.0 / (width as f32), 0.0, 0.0, 0.0],
+        [0.0, 2.0 / (height as f32), 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [-1.0, -1.0, 0.0, 1.0],
+    ]
+}
+
+/// Writes glyph data into a region of the cache texture, replacing `update_texture`.
+type TextureUploader = dyn FnMut(&Texture2d, glium::Rect, &[u8]);
+
+/// Uploads a freshly rasterized glyph, converting it as required by the raster mode.
+fn upload_glyph(
+    tex: &Texture2d,
+    raster_mode: RasterMode,
+    uploader: &mut Option<Box<TextureUploader>>,
+    rect: Rectangle<u32>,
+    tex_data: &[u8],
+) {
+    let sdf;
+    let tex_data = match raster_mode {
+        RasterMode::AlphaMask => tex_data,
+        RasterMode::Sdf { spread } => {
+            sdf = sdf::coverage_to_sdf(rect.width(), rect.height(), tex_data, spread);
+            &sdf
+        }
+    };
+    match uploader {
+        Some(uploader) => uploader(tex, rect_to_rect(rect), tex_data),
+        None => update_texture(tex, rect, tex_data),
+    }
+}
+
+/// Moves a rectangle of the draw cache into `region` of the cache texture.
+fn in_region(rect: Rectangle<u32>, region: Option<glium::Rect>) -> Rectangle<u32> {
+    match region {
+        Some(region) => Rectangle {
+            min: [rect.min[0] + region.left, rect.min[1] + region.bottom],
+            max: [rect.max[0] + region.left, rect.max[1] + region.bottom],
+        },
+        None => rect,
+    }
+}
+
+/// Maps the texture coordinates of vertices, relative to the draw cache, into `region`
+/// of a cache texture with the given dimensions.
+fn map_tex_coords<'a, I: Iterator<Item = &'a mut GlyphVertex>>(
+    verts: I,
+    region: Option<glium::Rect>,
+    (width, height): (u32, u32),
+) {
+    let region = match region {
+        Some(region) => region,
+        None => return,
+    };
+    let map = |[u, v]: [f32; 2]| {
+        [
+            (region.left as f32 + u * region.width as f32) / width as f32,
+            (region.bottom as f32 + v * region.height as f32) / height as f32,
+        ]
+    };
+    for vertex in verts {
+        vertex.tex_left_top = map(vertex.tex_left_top);
+        vertex.tex_right_bottom = map(vertex.tex_right_bottom);
+    }
+}
+
+/// Replaces the `#include "fill.glsl"` line of a fragment shader with the fills shared
+/// by the glyph shaders.
+fn include_fill(fragment_shader: &str) -> String {
+    static FILL: &str = include_str!("shader/fill.glsl");
+    fragment_shader.replace("#include \"fill.glsl\"", FILL)
+}
+
+fn create_program<C: Facade>(
+    facade: &C,
+    raster_mode: RasterMode,
+) -> Result<Program, ProgramCreationError> {
+    static VERTEX_SHADER: &str = include_str!("shader/vert.glsl");
+    static FRAGMENT_SHADER: &str = include_str!("shader/frag.glsl");
+    static SDF_FRAGMENT_SHADER: &str = include_str!("shader/frag_sdf.glsl");
+    let fragment_shader = match raster_mode {
+        RasterMode::AlphaMask => FRAGMENT_SHADER,
+        RasterMode::Sdf { .. } => SDF_FRAGMENT_SHADER,
+    };
+    Program::from_source(facade, VERTEX_SHADER, &include_fill(fragment_shader), None)
+}
+
+/// Checks that a program only consumes the vertex attributes and uniforms
+/// supplied by `draw_glyphs`.
+fn is_compatible_program(program: &Program) -> bool {
+    let bindings = GlyphVertex::build_bindings();
+    let corner_bindings = QuadCorner::build_bindings();
+    let attributes_known = program.attributes().all(|(name, attr)| {
+        bindings
+            .iter()
+            .chain(corner_bindings.iter())
+            .any(|b| b.0 == name.as_str() && b.3 == attr.ty && attr.size == 1)
+    });
+    let has_uniform = |name, ty| program.get_uniform(name).map(|u| u.ty) == Some(ty);
+    attributes_known
+        && has_uniform("font_tex", UniformType::Sampler2d)
+        && has_uniform("transform", UniformType::FloatMat4)
+}
+
+/// Uniform values shared by the glyph shaders.
+#[derive(Copy, Clone)]
+struct DrawUniforms<'a> {
+    transform: [[f32; 4]; 4],
+    gamma: f32,
+    texture_filter: (MinifySamplerFilter, MagnifySamplerFilter),
+    /// seconds animating procedural fills
+    time: f32,
+    /// multiplied into the alpha of every glyph
+    opacity: f32,
+    /// multiplied into the color of every glyph
+    tint: [f32; 4],
+    /// texture masked by the glyphs, if they're filled
+    fill_tex: Option<&'a Texture2d>,
+    /// uniforms set with `set_uniform`
+    user: &'a [(String, UniformValue<'static>)],
+}
+
+/// Sampler for the fill texture, falling back to `texture` so that the sampler
+/// is always bound, it's unused then.
+fn fill_sampler<'a>(
+    draw_uniforms: &DrawUniforms<'a>,
+    texture: &'a Texture2d,
+) -> glium::uniforms::Sampler<'a, Texture2d> {
+    glium::uniforms::Sampler::new(draw_uniforms.fill_tex.unwrap_or(texture))
+        .wrap_function(glium::uniforms::SamplerWrapFunction::Repeat)
+        .minify_filter(MinifySamplerFilter::Linear)
+        .magnify_filter(MagnifySamplerFilter::Linear)
+}
+
+fn draw_glyphs<S: Surface>(
+    surface: &mut S,
+    program: &Program,
+    texture: &Texture2d,
+    quads: Quads,
+    draw_uniforms: DrawUniforms,
+    params: &glium::DrawParameters,
+) -> Result<(), glium::DrawError> {
+    let sampler = glium::uniforms::Sampler::new(texture)
+        .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+        .minify_filter(draw_uniforms.texture_filter.0)
+        .magnify_filter(draw_uniforms.texture_filter.1);
+
+    let uniforms = WithUserUniforms {
+        uniforms: uniform! {
+            font_tex: sampler,
+            fill_tex: fill_sampler(&draw_uniforms, texture),
+            fill_enabled: draw_uniforms.fill_tex.is_some(),
+            time: draw_uniforms.time,
+            opacity: draw_uniforms.opacity,
+            tint: draw_uniforms.tint,
+            transform: draw_uniforms.transform,
+            gamma: draw_uniforms.gamma,
+        },
+        user: draw_uniforms.user,
+    };
+
+    // drawing a frame
+    quads.draw(surface, program, &uniforms, params)
+}
+
 #[inline]
-fn to_vertex(
+fn to_vertex<X: GlyphData>(
     glyph_brush::GlyphVertex {
         mut tex_coords,
         pixel_coords,
         bounds,
-        extra,
-    }: glyph_brush::GlyphVertex,
+        extra:
+            GlyphExtra {
+                extra,
+                transform,
+                fill,
+                procedural,
+                hollow,
+                depth,
+                params: _,
+                layer: _,
+                nearest: _,
+                pinned: _,
+                retained: _,
+            },
+    }: glyph_brush::GlyphVertex<GlyphExtra<X>>,
 ) -> GlyphVertex {
     let gl_bounds = bounds;
+    let procedural_params = procedural.map_or(([0.0; 4], [0.0; 4]), |p| p.to_params());
 
     let mut gl_rect = glyph_brush::ab_glyph::Rect {
         min: point(pixel_coords.min.x, pixel_coords.min.y),
@@ -110,14 +451,46 @@ fn to_vertex(
     }
 
     GlyphVertex {
-        left_top: [gl_rect.min.x, gl_rect.max.y, extra.z],
+        left_top: [
+            gl_rect.min.x,
+            gl_rect.max.y,
+            depth.unwrap_or_else(|| extra.z()),
+        ],
         right_bottom: [gl_rect.max.x, gl_rect.min.y],
         tex_left_top: [tex_coords.min.x, tex_coords.max.y],
         tex_right_bottom: [tex_coords.max.x, tex_coords.min.y],
-        color: extra.color,
+        color: extra.color(),
+        transform_x: transform.0[0],
+        transform_y: transform.0[1],
+        fill_x: fill.map_or([0.0; 3], |f| f.uv_transform[0]),
+        fill_y: fill.map_or([0.0; 3], |f| f.uv_transform[1]),
+        procedural: procedural_params.0,
+        procedural_color: procedural_params.1,
+        hollow: *hollow,
     }
 }
 
+/// Like `to_vertex`, keeping the fill texture and filtering to batch the vertex by,
+/// and the glyph data if it's converted to a custom vertex later.
+fn to_filled_vertex<X: GlyphData>(
+    vertex: glyph_brush::GlyphVertex<GlyphExtra<X>>,
+    keep_extra: bool,
+) -> (Batch, GlyphVertex, Option<X>) {
+    let batch = Batch {
+        layer: vertex.extra.layer,
+        params: vertex.extra.params,
+        fill: vertex.extra.fill.map(|f| f.texture),
+        nearest: vertex.extra.nearest,
+        pinned: vertex.extra.pinned,
+        retained: vertex.extra.retained,
+    };
+    let extra = match keep_extra {
+        true => Some(vertex.extra.extra.clone()),
+        false => None,
+    };
+    (batch, to_vertex(vertex), extra)
+}
+
 /*
 /// Object allowing glyph drawing, containing cache state. Manages glyph positioning cacheing,
 /// glyph draw caching & efficient GPU texture cache updating and re-sizing on demand.
@@ -173,23 +546,90 @@ fn to_vertex(
 /// the previous draw call.
 */
 
-pub struct GlyphBrush<'a, F: Font, H: BuildHasher = DefaultSectionHasher> {
-    glyph_brush: glyph_brush::GlyphBrush<GlyphVertex, Extra, F, H>,
-    params: glium::DrawParameters<'a>,
-    program: Program,
-    texture: Texture2d,
-    index_buffer: glium::index::NoIndices,
-    vertex_buffer: glium::VertexBuffer<GlyphVertex>,
-    instances: glium::VertexBuffer<InstanceVertex>,
+pub struct GlyphBrush<F: Font, H: BuildHasher = DefaultSectionHasher, X: GlyphData = Extra> {
+    glyph_brush: glyph_brush::GlyphBrush<(Batch, GlyphVertex, Option<X>), GlyphExtra<X>, F, H>,
+    params: glium::DrawParameters<'static>,
+    glyph_filter: Option<GlyphFilter>,
+    program: Rc<Program>,
+    custom_shaders: bool,
+    #[cfg(feature = "shader-hot-reload")]
+    shader_watch: Option<ShaderWatch>,
+    texture: Rc<Texture2d>,
+    /// region of a texture passed to the builder the glyphs are cached in,
+    /// `None` if the brush owns its texture
+    cache_region: Option<glium::Rect>,
+    texture_uploader: Option<Box<TextureUploader>>,
+    quads: QuadBuffer,
+    initial_cache_size: (u32, u32),
+    /// largest cache texture size the context supports
+    max_texture_size: u32,
+    /// program of the cache texture debug view, created on first use
+    atlas_debug: Option<Program>,
+    cache_regions: CacheRegions,
+    memory_budget: Option<usize>,
+    trim_pending: bool,
+    gpu_error_policy: GpuErrorPolicy,
+    raster_mode: RasterMode,
+    subpixel: Option<Subpixel>,
+    oit: Option<Oit>,
+    gamma: f32,
+    texture_filter: (MinifySamplerFilter, MagnifySamplerFilter),
+    fill_textures: FillTextures,
+    draw_params: DrawParamsSets,
+    layers: Layers,
+    damage: Damage,
+    time: f32,
+    opacity: f32,
+    /// extra uniforms passed to the glyph shaders
+    user_uniforms: Vec<(String, UniformValue<'static>)>,
+    /// ranges of glyphs in `quads` drawn with the same state, see `Batch`
+    fill_batches: Vec<(Batch, Range<usize>)>,
+    /// fonts sampled with nearest filtering
+    pixel_fonts: Vec<FontId>,
+    /// font added in place of fonts that fail to parse
+    fallback_font: Option<FontId>,
+    /// fonts of characters missing from their text's font, in order
+    font_fallbacks: Vec<FontId>,
+    missing_glyph: MissingGlyph,
+    missing_glyph_hook: Option<Box<MissingGlyphHook>>,
+    variable_fonts: VariableFonts,
+    /// families and styles of the fonts, for `query_font`
+    font_faces: FontFaces,
+    /// glyphs queued for caching in every frame
+    pinned_glyphs: Vec<SectionGlyph>,
+    /// sections queued for every draw by name
+    retained: Retained<X>,
+    /// vertices of a user defined type replacing the built-in ones
+    custom_vertices: Option<CustomVertices<X>>,
+    /// laid out glyphs of the queue, to draw it in batches if it doesn't fit
+    overflow: Overflow<X>,
+    /// whether the latest vertices were returned by `process_queued_to_vertices`
+    vertices_taken: bool,
+    glyph_cap: Option<usize>,
+    depth_sorted: bool,
+    deterministic: bool,
+    hardened: bool,
+    pixel_snapping: bool,
+    queued_glyphs: usize,
+    queued_sections: usize,
+    truncated_glyphs: usize,
+    metrics: Metrics,
+    #[cfg(feature = "color-glyphs")]
+    color_glyphs: ColorGlyphs,
+    /// color glyphs of the processed queue, drawn with the default layer
+    #[cfg(feature = "color-glyphs")]
+    color_queue: Vec<QueuedImage>,
+    #[cfg(feature = "color-glyphs")]
+    color_layers: ColorLayers,
 }
 
-impl<'p, F: Font> GlyphBrush<'p, F> {
+impl<F: Font> GlyphBrush<F> {
     pub fn new<C: Facade, V: Into<Vec<F>>>(facade: &C, fonts: V) -> Self {
         GlyphBrushBuilder::using_fonts(fonts).build(facade)
     }
 }
 
-impl<'p, F: Font + Sync, H: BuildHasher> GlyphBrush<'p, F, H> {
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
     /// Queues a section/layout to be drawn by the next call of
     /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued). Can be called multiple times
     /// to queue multiple sections for drawing.
@@ -202,9 +642,83 @@ impl<'p, F: Font + Sync, H: BuildHasher> GlyphBrush<'p, F, H> {
     pub fn queue_custom_layout<'a, S, G>(&mut self, section: S, custom_layout: &G)
     where
         G: GlyphPositioner,
-        S: Into<Cow<'a, Section<'a>>>,
+        S: Into<Cow<'a, Section<'a, X>>>,
     {
-        self.glyph_brush.queue_custom_layout(section, custom_layout)
+        self.queue_section(section.into(), custom_layout, &SectionStyle::default())
+    }
+
+    fn queue_section<G: GlyphPositioner>(
+        &mut self,
+        section: Cow<Section<X>>,
+        custom_layout: &G,
+        style: &SectionStyle,
+    ) {
+        if self.deterministic {
+            self.queue_section_with_layout(section, &RoundedLayout(custom_layout), style)
+        } else {
+            self.queue_section_with_layout(section, custom_layout, style)
+        }
+    }
+
+    fn queue_section_with_layout<G: GlyphPositioner>(
+        &mut self,
+        section: Cow<Section<X>>,
+        custom_layout: &G,
+        style: &SectionStyle,
+    ) {
+        self.queued_sections += 1;
+        self.layers.queued();
+        let prepared = self.prepare_section(&section);
+        let section = match &prepared {
+            Some(prepared) => Cow::Owned(prepared.to_borrowed()),
+            None => Cow::Borrowed(section.as_ref()),
+        };
+        let capped = self.cap_glyphs(&section);
+        let section = match &capped {
+            Some(capped) => Cow::Owned(capped.to_borrowed()),
+            None => section,
+        };
+        let stretched = self
+            .subpixel
+            .as_ref()
+            .map(|_| Subpixel::stretch_section(&section));
+        let section = match &stretched {
+            Some(stretched) => Cow::Owned(stretched.to_borrowed()),
+            None => section,
+        };
+        self.queue_background_passes(&section, custom_layout, style);
+        #[cfg(feature = "color-glyphs")]
+        {
+            if self.queue_color_glyphs(&section, custom_layout) {
+                return;
+            }
+        }
+        let mut section = with_glyph_extra(&section);
+        let mut style = *style;
+        if self.subpixel.is_some() {
+            // map the stretched pixel coordinates
+            if let Some(fill) = &mut style.fill {
+                fill.uv_transform[0][0] /= 3.0;
+                fill.uv_transform[1][0] /= 3.0;
+            }
+            style.procedural = style.procedural.map(|p| p.stretched(3.0));
+            style.transform = style.transform.stretched(3.0);
+        }
+        for text in &mut section.text {
+            text.extra.fill = style.fill;
+            text.extra.procedural = style.procedural;
+            text.extra.hollow = style.hollow;
+            text.extra.transform = style.transform;
+            text.extra.depth = style.depth;
+            text.extra.params = style.params;
+            text.extra.layer = style.layer;
+            text.extra.retained = style.retained;
+            text.extra.nearest = self.pixel_fonts.contains(&text.font_id);
+            if let Some(color) = style.fill_color {
+                text.extra.extra = text.extra.extra.with_color(color);
+            }
+        }
+        self.queue_laid_out(section, custom_layout)
     }
 
     /// Queues a section/layout to be drawn by the next call of
@@ -215,9 +729,11 @@ impl<'p, F: Font + Sync, H: BuildHasher> GlyphBrush<'p, F, H> {
     #[inline]
     pub fn queue<'a, S>(&mut self, section: S)
     where
-        S: Into<Cow<'a, Section<'a>>>,
+        S: Into<Cow<'a, Section<'a, X>>>,
     {
-        self.glyph_brush.queue(section)
+        let section = section.into();
+        let layout = section.layout;
+        self.queue_custom_layout(section, &layout)
     }
 
     /*
@@ -231,20 +747,112 @@ impl<'p, F: Font + Sync, H: BuildHasher> GlyphBrush<'p, F, H> {
     /// be provided. [See example.](struct.GlyphBrush.html#raw-usage-1)
     	*/
 
+    /// Draws all queued sections onto a render target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the draw fails, see [`try_draw_queued`](#method.try_draw_queued) to
+    /// handle the error instead.
     #[inline]
     pub fn draw_queued<C: Facade + Deref<Target = Context>, S: Surface>(
         &mut self,
         facade: &C,
         surface: &mut S,
     ) {
-        let dims = facade.get_framebuffer_dimensions();
-        let transform = [
-            [2.0 / (dims.0 as f32), 0.0, 0.0, 0.0],
-            [0.0, 2.0 / (dims.1 as f32), 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [-1.0, -1.0, 0.0, 1.0],
-        ];
-        self.draw_queued_with_transform(transform, facade, surface)
+        if let Err(e) = self.try_draw_queued(facade, surface) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Draws all queued sections onto a render target and returns the metrics of the
+    /// draw, e.g. to skip recompositing a cached UI layer if its vertices weren't
+    /// [regenerated](struct.Metrics.html#structfield.regenerated_vertices).
+    #[inline]
+    pub fn try_draw_queued<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError> {
+        let transform = screen_transform(surface.get_dimensions());
+        self.try_draw_queued_with_transform(transform, facade, surface)
+    }
+
+    /// Draws all queued sections like [`try_draw_queued`](#method.try_draw_queued),
+    /// clipped to `scissor`, e.g. a panel or scrollable region.
+    ///
+    /// The scissor only applies to this call, the brush's draw parameters are unchanged.
+    pub fn draw_queued_clipped<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        facade: &C,
+        surface: &mut S,
+        scissor: glium::Rect,
+    ) -> Result<Metrics, DrawError> {
+        let previous = self.params.scissor.replace(scissor);
+        let result = self.try_draw_queued(facade, surface);
+        self.params.scissor = previous;
+        result
+    }
+
+    /// Queues `sections` and draws them, along with any sections queued before, into a new
+    /// RGBA texture of `(width, height)` pixels, e.g. to cache static labels or use text
+    /// in other materials.
+    ///
+    /// The texture starts out transparent and holds the text as it would appear on a
+    /// framebuffer of the same size, blended with the brush's draw parameters.
+    pub fn render_to_texture<'a, C, I, S>(
+        &mut self,
+        facade: &C,
+        sections: I,
+        (width, height): (u32, u32),
+    ) -> Result<Texture2d, DrawError>
+    where
+        C: Facade + Deref<Target = Context>,
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        for section in sections {
+            self.queue(section);
+        }
+        let texture = Texture2d::empty_with_format(
+            facade,
+            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+            glium::texture::MipmapsOption::NoMipmap,
+            width,
+            height,
+        )?;
+        {
+            let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(facade, &texture)?;
+            framebuffer.clear_color(0.0, 0.0, 0.0, 0.0);
+            let transform = screen_transform((width, height));
+            self.try_draw_queued_with_transform(transform, facade, &mut framebuffer)?;
+        }
+        Ok(texture)
+    }
+
+    /// Renders like [`render_to_texture`](#method.render_to_texture) and reads the result
+    /// back as RGBA8 pixels, rows from top to bottom, e.g. to generate text images
+    /// with a headless context.
+    pub fn render_to_pixels<'a, C, I, S>(
+        &mut self,
+        facade: &C,
+        sections: I,
+        (width, height): (u32, u32),
+    ) -> Result<Vec<u8>, DrawError>
+    where
+        C: Facade + Deref<Target = Context>,
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        let texture = self.render_to_texture(facade, sections, (width, height))?;
+        let image: RawImage2d<u8> = texture.read();
+        // OpenGL returns the bottom row first
+        let row_len = width as usize * 4;
+        Ok(image
+            .data
+            .chunks(row_len)
+            .rev()
+            .flat_map(|row| row.iter().copied())
+            .collect())
     }
 
     /*
@@ -296,67 +904,493 @@ impl<'p, F: Font + Sync, H: BuildHasher> GlyphBrush<'p, F, H> {
     /// ```
     	*/
 
+    /// Draws all queued sections onto a render target, applying a position transform.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the draw fails, see
+    /// [`try_draw_queued_with_transform`](#method.try_draw_queued_with_transform) to
+    /// handle the error instead.
     pub fn draw_queued_with_transform<C: Facade + Deref<Target = Context>, S: Surface>(
         &mut self,
         transform: [[f32; 4]; 4],
         facade: &C,
         surface: &mut S,
     ) {
-        let mut brush_action;
-        loop {
-            // We need this scope because of lifetimes.
-            // Ultimately, we'd like to put the &self.texture
-            // into the closure, but that'd inevitably
-            // borrow the entirety of self inside the closure.
-            // This is a problem with the language and is
-            // discussed here:
-            // http://smallcultfollowing.com/babysteps/blog/2018/11/01/after-nll-interprocedural-conflicts/
-            {
-                let tex = &self.texture;
-                brush_action = self.glyph_brush.process_queued(
-                    |rect, tex_data| {
-                        update_texture(tex, rect, tex_data);
-                    },
-                    to_vertex,
-                );
-            }
-            match brush_action {
-                Ok(_) => break,
+        if let Err(e) = self.try_draw_queued_with_transform(transform, facade, surface) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Draws all queued sections onto a render target, applying a position transform,
+    /// and returns the metrics of the draw like [`try_draw_queued`](#method.try_draw_queued).
+    pub fn try_draw_queued_with_transform<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        transform: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError> {
+        if self.process_for_draw(facade)? {
+            self.draw_batches(&|_| true, transform, NO_TINT, facade, surface)?;
+        }
+        Ok(self.metrics)
+    }
+
+    /// Processes the queue and uploads the vertices for drawing, returns `false` if the
+    /// frame is skipped.
+    fn process_for_draw<C: Facade>(&mut self, facade: &C) -> Result<bool, DrawError> {
+        self.queue_retained();
+        if self.trim_pending {
+            self.trim_memory(facade)?;
+        }
+        if self.vertices_taken {
+            self.invalidate_vertices();
+            self.vertices_taken = false;
+        }
+        self.queued_glyphs = 0;
+        self.truncated_glyphs = 0;
+        self.metrics = Metrics {
+            sections_processed: self.queued_sections,
+            ..Metrics::default()
+        };
+        self.queued_sections = 0;
+        self.layers.processed();
+        #[cfg(feature = "color-glyphs")]
+        {
+            self.color_queue = self.color_glyphs.take_queue();
+        }
+        self.queue_pinned();
+        let queued = self.overflow.processing();
+
+        let brush_action = loop {
+            match self.process_queued_uploading() {
+                Ok(action) => break action,
                 Err(BrushError::TextureTooSmall { suggested }) => {
-                    let (nwidth, nheight) = suggested;
-                    self.texture = Texture2d::empty(facade, nwidth, nheight).unwrap();
-                    self.glyph_brush.resize_texture(nwidth, nheight);
+                    let size = self.budgeted_texture_size(suggested);
+                    match self.grow_texture(facade, size) {
+                        Ok(()) => self.metrics.texture_resized = true,
+                        Err(DrawError::CacheFull) if self.overflow.is_recording() => {
+                            let processed = self.process_in_batches(facade, queued)?;
+                            self.cache_metrics();
+                            return Ok(processed);
+                        }
+                        Err(DrawError::CacheFull) => {
+                            // the glyphs of this frame weren't kept to draw it in batches
+                            self.discard_queued();
+                            self.overflow.start_recording();
+                            self.metrics.frame_skipped = true;
+                            self.cache_metrics();
+                            return Ok(false);
+                        }
+                        Err(e) => {
+                            self.discard_queued();
+                            return match self.gpu_error_policy {
+                                GpuErrorPolicy::SkipFrame => Ok(false),
+                                _ => Err(e),
+                            };
+                        }
+                    }
                 }
             }
+        };
+
+        if let BrushAction::ReDraw = brush_action {
+            self.damage.unchanged();
+        }
+        if let BrushAction::Draw(verts) = brush_action {
+            let verts = self.prepare_vertices(verts);
+            let stretch = self.stretch();
+            self.damage.update(verts.iter().map(|(_, v, _)| v), stretch);
+            if !self.upload_vertices(facade, verts)? {
+                return Ok(false);
+            }
+        }
+
+        self.metrics.glyphs_drawn = match &self.custom_vertices {
+            Some(custom) => custom.glyphs(),
+            None => self.quads.glyphs(),
+        };
+        self.cache_metrics();
+        Ok(true)
+    }
+
+    fn cache_metrics(&mut self) {
+        let (width, height) = self.cache_size();
+        self.metrics.cache_area = width as u64 * height as u64;
+        self.metrics.cache_used_area = self.cache_regions.area();
+    }
+
+    /// Processes the queue of glyph_brush, uploading newly rasterized glyphs into the
+    /// cache texture.
+    fn process_queued_uploading(
+        &mut self,
+    ) -> Result<BrushAction<(Batch, GlyphVertex, Option<X>)>, BrushError> {
+        // We need this scope because of lifetimes.
+        // Ultimately, we'd like to put the &self.texture
+        // into the closure, but that'd inevitably
+        // borrow the entirety of self inside the closure.
+        // This is a problem with the language and is
+        // discussed here:
+        // http://smallcultfollowing.com/babysteps/blog/2018/11/01/after-nll-interprocedural-conflicts/
+        let tex = &self.texture;
+        let raster_mode = self.raster_mode;
+        let uploader = &mut self.texture_uploader;
+        let metrics = &mut self.metrics;
+        let regions = &mut self.cache_regions;
+        let region = self.cache_region;
+        let keep_extra = self.custom_vertices.is_some();
+        self.glyph_brush.process_queued(
+            |rect, tex_data| {
+                let rect = in_region(rect, region);
+                upload_glyph(tex, raster_mode, uploader, rect, tex_data);
+                metrics.texture_uploads += 1;
+                metrics.uploaded_bytes += tex_data.len();
+                regions.track(rect);
+            },
+            move |v| to_filled_vertex(v, keep_extra),
+        )
+    }
+
+    /// Horizontal stretch of the vertices, 3 in subpixel mode.
+    fn stretch(&self) -> f32 {
+        match self.subpixel {
+            Some(_) => 3.0,
+            None => 1.0,
         }
+    }
 
-        let sampler = glium::uniforms::Sampler::new(&self.texture)
-            .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
-            .minify_filter(glium::uniforms::MinifySamplerFilter::Linear)
-            .magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear);
+    /// Drops the vertices of pinned glyphs and snaps the others if enabled.
+    fn prepare_vertices(
+        &mut self,
+        mut verts: Vec<(Batch, GlyphVertex, Option<X>)>,
+    ) -> Vec<(Batch, GlyphVertex, Option<X>)> {
+        self.metrics.regenerated_vertices = true;
+        verts.retain(|(batch, _, _)| !batch.pinned);
+        if self.pixel_snapping {
+            snap_to_pixels(verts.iter_mut().map(|(_, v, _)| v));
+        }
+        verts
+    }
 
-        match brush_action.unwrap() {
-            BrushAction::Draw(verts) => {
-                self.vertex_buffer = glium::VertexBuffer::new(facade, &verts).unwrap();
+    /// Uploads regenerated vertices for drawing, returns `false` if the frame is skipped.
+    fn upload_vertices<C: Facade>(
+        &mut self,
+        facade: &C,
+        mut verts: Vec<(Batch, GlyphVertex, Option<X>)>,
+    ) -> Result<bool, DrawError> {
+        let tex_size = self.texture.dimensions();
+        map_tex_coords(
+            verts.iter_mut().map(|(_, v, _)| v),
+            self.cache_region,
+            tex_size,
+        );
+        if let Some(custom) = &mut self.custom_vertices {
+            self.retained.not_uploaded();
+            if let Err(e) = custom.upload(facade, verts) {
+                self.invalidate_vertices();
+                return match self.gpu_error_policy {
+                    GpuErrorPolicy::SkipFrame => Ok(false),
+                    _ => Err(e),
+                };
             }
-            BrushAction::ReDraw => {}
-        };
+            return Ok(true);
+        }
+        let mut verts: Vec<(Batch, GlyphVertex)> =
+            verts.into_iter().map(|(batch, v, _)| (batch, v)).collect();
+        self.fill_batches = batch_by_fill(&mut verts, self.depth_sorted);
+        self.retained.uploading(&mut verts);
+        if self.overflow.is_batched() {
+            // the glyphs of the other batches replace these in the quad buffer
+            self.retained.not_uploaded();
+        }
+        let verts: Vec<GlyphVertex> = verts.into_iter().map(|(_, v)| v).collect();
+        if let Err(e) = self.quads.upload(facade, &verts) {
+            // The vertices are lost, make sure the next draw regenerates them
+            // instead of redrawing the previous vertex buffer.
+            self.invalidate_vertices();
+            match self.gpu_error_policy {
+                GpuErrorPolicy::ReturnError => return Err(e),
+                GpuErrorPolicy::SkipFrame => return Ok(false),
+                GpuErrorPolicy::RetrySmaller => {
+                    if !self.quads.upload_partial(facade, &verts) {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
 
-        let uniforms = uniform! {
-            font_tex: sampler,
-            transform: transform,
+    /// Draws the selected layers of the processed vertices.
+    fn draw_processed<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        select: &dyn Fn(&str) -> bool,
+        transform: [[f32; 4]; 4],
+        tint: [f32; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<(), DrawError> {
+        let layers = self.layers.draw(select);
+        // glyphs that aren't batched by layer are drawn with the default layer
+        let default_layer = layers.contains(&0);
+        let draw_uniforms = DrawUniforms {
+            transform: match self.subpixel {
+                Some(_) => Subpixel::squeeze_transform(transform),
+                None => transform,
+            },
+            gamma: self.gamma,
+            texture_filter: self.texture_filter,
+            time: self.time,
+            opacity: self.opacity,
+            tint,
+            fill_tex: None,
+            user: &self.user_uniforms,
+        };
+        if let Some(oit) = self.oit.as_mut().filter(|_| default_layer) {
+            let quads = self.quads.all();
+            if !quads.is_empty() {
+                oit.draw(
+                    facade,
+                    surface,
+                    &self.texture,
+                    quads,
+                    draw_uniforms,
+                    &self.params,
+                )?;
+            }
+        }
+        let batches = match self.oit {
+            Some(_) => &[][..],
+            None => &self.fill_batches[..],
         };
+        let layer_batches = layers.iter().flat_map(|&layer| {
+            batches
+                .iter()
+                .filter(move |(batch, _)| batch.layer == layer)
+        });
+        for (batch, range) in layer_batches {
+            // a smaller vertex buffer may hold only part of the glyphs
+            let quads = self.quads.quads(range.clone());
+            if quads.is_empty() {
+                continue;
+            }
+            let draw_uniforms = DrawUniforms {
+                fill_tex: batch.fill.and_then(|id| self.fill_textures.get(id)),
+                texture_filter: match batch.nearest {
+                    true => (MinifySamplerFilter::Nearest, MagnifySamplerFilter::Nearest),
+                    false => draw_uniforms.texture_filter,
+                },
+                ..draw_uniforms
+            };
+            let params = self
+                .draw_params
+                .get(batch.params)
+                .or_else(|| self.layers.params(batch.layer))
+                .unwrap_or(&self.params);
+            match &self.subpixel {
+                Some(subpixel) => {
+                    subpixel.draw(surface, &self.texture, quads, draw_uniforms, params)?
+                }
+                None => draw_glyphs(
+                    surface,
+                    &self.program,
+                    &self.texture,
+                    quads,
+                    draw_uniforms,
+                    params,
+                )?,
+            }
+        }
+        if let Some(custom) = self.custom_vertices.as_ref().filter(|_| default_layer) {
+            custom.draw(surface, &self.texture, draw_uniforms, &self.params)?;
+        }
+        #[cfg(feature = "color-glyphs")]
+        {
+            if default_layer {
+                let color_glyphs = std::mem::take(&mut self.color_queue);
+                self.color_glyphs.draw(
+                    facade,
+                    surface,
+                    color_glyphs,
+                    draw_uniforms,
+                    &self.params,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the cache texture with one of the given size, or a smaller one
+    /// that's still larger than the current texture if the policy allows it.
+    ///
+    /// The size is clamped to the largest texture the context supports, so that large
+    /// glyph sets, e.g. CJK text, can use all of it.
+    ///
+    /// A texture passed to the builder can't grow, the cache is full then.
+    fn grow_texture<C: Facade>(&mut self, facade: &C, size: (u32, u32)) -> Result<(), DrawError> {
+        if self.cache_region.is_some() {
+            return Err(DrawError::CacheFull);
+        }
+        let (width, height) = self.texture.dimensions();
+        let size = (
+            size.0.min(self.max_texture_size),
+            size.1.min(self.max_texture_size),
+        );
+        if size.0 <= width && size.1 <= height {
+            return Err(DrawError::CacheFull);
+        }
+        let mut candidates = vec![size];
+        if self.gpu_error_policy == GpuErrorPolicy::RetrySmaller {
+            candidates.push((size.0, height));
+            candidates.push((width, size.1));
+            candidates.retain(|&(w, h)| w > width || h > height);
+        }
+
+        let mut error = None;
+        for (w, h) in candidates {
+            match Texture2d::empty(facade, w, h) {
+                Ok(texture) => {
+                    self.texture = Rc::new(texture);
+                    self.glyph_brush.resize_texture(w, h);
+                    self.cache_regions.clear();
+                    return Ok(());
+                }
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+        Err(error.unwrap().into())
+    }
+
+    /// Throws away the queued sections without drawing them or touching the GPU, e.g.
+    /// sections queued while measuring a layout or for an aborted frame.
+    ///
+    /// Retained sections stay, see [`insert_section`](#method.insert_section).
+    pub fn clear_queue(&mut self) {
+        self.discard_queued();
+        self.overflow.forget_queued();
+        self.queued_glyphs = 0;
+        self.truncated_glyphs = 0;
+        self.queued_sections = 0;
+        #[cfg(feature = "color-glyphs")]
+        self.color_glyphs.take_queue();
+        // the next layer drawn shows the now empty queue
+        self.layers.queued();
+    }
+
+    /// Throws away the queued sections without touching the GPU.
+    fn discard_queued(&mut self) {
+        // glyph_brush has no way of clearing its queue other than processing it,
+        // so process it against a large enough logical texture without uploading
+        // anything. If that put glyphs into the cache, reset the logical texture
+        // to match the real one.
+        // The marker never equals any pre-positioned glyphs, so the next draw gets
+        // fresh vertices instead of redrawing ours for the discarded queue.
+        let nan = point(f32::NAN, f32::NAN);
+        let marker = glyph_brush::ab_glyph::Rect { min: nan, max: nan };
+        self.glyph_brush
+            .queue_pre_positioned(Vec::new(), Vec::new(), marker);
+        let mut cached = false;
+        loop {
+            let action = self
+                .glyph_brush
+                .process_queued(|_, _| cached = true, |v| to_filled_vertex(v, false));
+            match action {
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    self.glyph_brush.resize_texture(suggested.0, suggested.1);
+                    cached = true;
+                }
+                Ok(_) => break,
+            }
+        }
+        if cached {
+            let (width, height) = self.cache_size();
+            self.glyph_brush.resize_texture(width, height);
+        }
+    }
+
+    /// Makes the next draw regenerate all vertices, even if nothing changed.
+    fn invalidate_vertices(&mut self) {
+        let (width, height) = self.cache_size();
+        self.glyph_brush.resize_texture(width, height);
+        self.cache_regions.clear();
+    }
+
+    /// Sets how GPU allocation failures during drawing are handled.
+    pub fn set_gpu_error_policy(&mut self, policy: GpuErrorPolicy) {
+        self.gpu_error_policy = policy;
+    }
 
-        // drawing a frame
-        surface
-            .draw(
-                (&self.instances, self.vertex_buffer.per_instance().unwrap()),
-                &self.index_buffer,
-                &self.program,
-                &uniforms,
-                &self.params,
-            )
-            .unwrap();
+    /// Sorts glyph quads back to front by their `z` within each draw call, so that
+    /// overlapping translucent text at different depths blends in depth order instead
+    /// of queue order. Higher `z` values are drawn first, i.e. treated as farther away.
+    ///
+    /// Glyphs with equal `z` keep their queue order.
+    pub fn set_depth_sorted(&mut self, sorted: bool) {
+        if sorted != self.depth_sorted {
+            self.depth_sorted = sorted;
+            self.invalidate_vertices();
+        }
+    }
+
+    /// Samples the glyphs of `font_id` with nearest filtering if `pixel_font` is set,
+    /// e.g. for pixel art fonts drawn next to smooth ones, instead of the texture filter
+    /// of the brush. Glyphs with different filtering are drawn with separate draw calls
+    /// from the same cache texture. Has no effect with subpixel antialiasing, which always
+    /// samples the nearest texels, or with order-independent transparency.
+    ///
+    /// Applies to sections queued afterwards.
+    pub fn set_pixel_font(&mut self, font_id: FontId, pixel_font: bool) {
+        self.pixel_fonts.retain(|&id| id != font_id);
+        if pixel_font {
+            self.pixel_fonts.push(font_id);
+        }
+    }
+
+    /// Returns whether the shaders passed to
+    /// [`GlyphBrushBuilder::with_shaders`](struct.GlyphBrushBuilder.html#method.with_shaders)
+    /// or the program passed to
+    /// [`GlyphBrushBuilder::with_program`](struct.GlyphBrushBuilder.html#method.with_program)
+    /// are in use, `false` if the brush fell back to the default shaders.
+    pub fn has_custom_shaders(&self) -> bool {
+        self.custom_shaders
+    }
+
+    /// Returns the glyph cache texture, e.g. to sample glyphs in custom shaders with
+    /// texture coordinates from a [`snapshot`](#method.snapshot).
+    ///
+    /// Coverage is stored in the red channel, as a signed distance field with
+    /// `RasterMode::Sdf`. The texture is replaced when the cache grows or is trimmed,
+    /// unless it was passed to the builder, and glyphs move within it when evicted and
+    /// rasterized again.
+    pub fn texture(&self) -> &Texture2d {
+        &self.texture
+    }
+
+    /// Returns the dimensions `(width, height)` of the glyph cache texture.
+    pub fn texture_dimensions(&self) -> (u32, u32) {
+        self.texture.dimensions()
+    }
+
+    /// Returns the region of the cache texture passed to
+    /// [`GlyphBrushBuilder::cache_texture`](struct.GlyphBrushBuilder.html#method.cache_texture)
+    /// the glyphs are cached in, `None` if the brush owns its cache texture.
+    pub fn cache_region(&self) -> Option<glium::Rect> {
+        self.cache_region
+    }
+
+    /// Size of the area glyphs are cached in, the cache region or the whole texture.
+    fn cache_size(&self) -> (u32, u32) {
+        match self.cache_region {
+            Some(region) => (region.width, region.height),
+            None => self.texture.dimensions(),
+        }
+    }
+
+    /// Returns whether glyphs are drawn as instanced triangle strips, `false` if they are
+    /// drawn as an indexed triangle list, see
+    /// [`GlyphBrushBuilder::instancing`](struct.GlyphBrushBuilder.html#method.instancing).
+    pub fn is_instanced(&self) -> bool {
+        self.quads.is_instanced()
     }
 
     /// Adds an additional font to the one(s) initially added on build.
@@ -365,9 +1399,124 @@ impl<'p, F: Font + Sync, H: BuildHasher> GlyphBrush<'p, F, H> {
     pub fn add_font<I: Into<F>>(&mut self, font_data: I) -> FontId {
         self.glyph_brush.add_font(font_data)
     }
+
+    /// Replaces the font at `font_id`, e.g. to reload a font file edited while the app
+    /// runs. Sections using the font are laid out and drawn with the new one on the next
+    /// draw, without recreating the brush.
+    ///
+    /// Everything cached for the old font is dropped, including its
+    /// [pinned glyphs](#method.pin_glyphs), [instances](#method.font_instance) of it
+    /// as a variable font and the family and style set with
+    /// [`set_font_style`](#method.set_font_style). The draw cache of glyph_brush can't
+    /// forget the glyphs of single fonts, so all glyphs are rasterized again.
+    ///
+    /// Settings referring to `font_id` refer to the new font, e.g. if it is the
+    /// [fallback font](#method.set_fallback_font), one of the
+    /// [font fallbacks](#method.set_font_fallbacks) or a
+    /// [pixel font](#method.set_pixel_font).
+    ///
+    /// Returns `false` and keeps the old font if there is no font at `font_id` or if
+    /// sections or glyphs are queued, as the queue of glyph_brush is lost when it is
+    /// rebuilt with the new font. Replace fonts between draws.
+    pub fn replace_font<I: Into<F>>(&mut self, font_id: FontId, font_data: I) -> bool
+    where
+        F: Clone,
+        H: Clone,
+    {
+        if font_id.0 >= self.glyph_brush.fonts().len() || self.overflow.has_queued() {
+            return false;
+        }
+        let mut builder = self.glyph_brush.to_builder();
+        builder.font_data[font_id.0] = font_data.into();
+        self.glyph_brush = builder.build();
+        self.cache_regions.clear();
+        self.pinned_glyphs.retain(|g| g.font_id != font_id);
+        self.variable_fonts.forget(font_id);
+        self.font_faces.forget(font_id);
+        #[cfg(feature = "color-glyphs")]
+        {
+            self.color_glyphs.forget_font(font_id);
+            self.color_layers.forget_font(font_id);
+        }
+        true
+    }
+
+    /// Parses `font_data` and adds the font like [`add_font`](#method.add_font), e.g. for
+    /// fonts loaded from user supplied files.
+    ///
+    /// Returns an error if the data isn't a valid font. If a
+    /// [fallback font](#method.set_fallback_font) is set, a copy of it is added in place
+    /// of the failed font and its id returned in the error, so that the app can keep
+    /// rendering with it.
+    pub fn try_add_font_bytes(&mut self, font_data: Vec<u8>) -> Result<FontId, FontError>
+    where
+        F: From<FontVec> + Clone,
+    {
+        self.add_parsed_font(FontVec::try_from_vec(font_data))
+    }
+
+    /// Parses the face at `index` of a font collection, e.g. a `.ttc` file, and adds it
+    /// like [`try_add_font_bytes`](#method.try_add_font_bytes). Index 0 is the first
+    /// face, plain font files only have that one.
+    pub fn try_add_font_collection_bytes(
+        &mut self,
+        font_data: Vec<u8>,
+        index: u32,
+    ) -> Result<FontId, FontError>
+    where
+        F: From<FontVec> + Clone,
+    {
+        self.add_parsed_font(FontVec::try_from_vec_and_index(font_data, index))
+    }
+
+    /// Adds a parsed font, or a copy of the fallback font if parsing failed.
+    fn add_parsed_font(&mut self, font: Result<FontVec, InvalidFont>) -> Result<FontId, FontError>
+    where
+        F: From<FontVec> + Clone,
+    {
+        match font {
+            Ok(font) => Ok(self.glyph_brush.add_font(font)),
+            Err(e) => {
+                let fallback = self
+                    .fallback_font
+                    .and_then(|id| self.glyph_brush.fonts().get(id.0).cloned())
+                    .map(|font| self.glyph_brush.add_font(font));
+                Err(FontError::new(e, fallback))
+            }
+        }
+    }
+
+    /// Sets the font added in place of fonts that fail to parse in
+    /// [`try_add_font_bytes`](#method.try_add_font_bytes).
+    ///
+    /// Defaults to `None`, failed fonts aren't replaced.
+    pub fn set_fallback_font(&mut self, font_id: Option<FontId>) {
+        self.fallback_font = font_id;
+    }
+
+    /// Returns the fallback font set with
+    /// [`set_fallback_font`](#method.set_fallback_font).
+    pub fn fallback_font(&self) -> Option<FontId> {
+        self.fallback_font
+    }
+}
+
+impl<F: Font, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Applies hardened mode, the glyph filter and the font fallbacks to a section about
+    /// to be laid out, returns `None` if it can be used as is.
+    fn prepare_section(&mut self, section: &Section<X>) -> Option<OwnedSection<X>> {
+        let hardened = self.harden(section);
+        let hardened_section = hardened.as_ref().map(|h| h.to_borrowed());
+        let section = hardened_section.as_ref().unwrap_or(section);
+        let filtered = self.glyph_filter.as_ref().and_then(|f| f.apply(section));
+        let filtered_section = filtered.as_ref().map(|f| f.to_borrowed());
+        let section = filtered_section.as_ref().unwrap_or(section);
+        // each step works on the result of the previous ones
+        self.apply_font_fallbacks(section).or(filtered).or(hardened)
+    }
 }
 
-impl<'l, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<'l, F, H> {
+impl<F: Font, H: BuildHasher, X: GlyphData> GlyphCruncher<F, X> for GlyphBrush<F, H, X> {
     fn glyph_bounds_custom_layout<'a, S, L>(
         &mut self,
         section: S,
@@ -375,10 +1524,21 @@ impl<'l, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<'l, F, H> {
     ) -> Option<glyph_brush::ab_glyph::Rect>
     where
         L: GlyphPositioner + Hash,
-        S: Into<Cow<'a, Section<'a>>>,
+        S: Into<Cow<'a, Section<'a, X>>>,
     {
-        self.glyph_brush
-            .glyph_bounds_custom_layout(section, custom_layout)
+        let section = section.into();
+        let prepared = self.prepare_section(&section);
+        let section = match &prepared {
+            Some(prepared) => with_glyph_extra(&prepared.to_borrowed()),
+            None => with_glyph_extra(&section),
+        };
+        if self.deterministic {
+            self.glyph_brush
+                .glyph_bounds_custom_layout(section, &RoundedLayout(custom_layout))
+        } else {
+            self.glyph_brush
+                .glyph_bounds_custom_layout(section, custom_layout)
+        }
     }
 
     fn glyphs_custom_layout<'a, 'b, S, L>(
@@ -388,10 +1548,21 @@ impl<'l, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<'l, F, H> {
     ) -> SectionGlyphIter<'b>
     where
         L: GlyphPositioner + Hash,
-        S: Into<Cow<'a, Section<'a>>>,
+        S: Into<Cow<'a, Section<'a, X>>>,
     {
-        self.glyph_brush
-            .glyphs_custom_layout(section, custom_layout)
+        let section = section.into();
+        let prepared = self.prepare_section(&section);
+        let section = match &prepared {
+            Some(prepared) => with_glyph_extra(&prepared.to_borrowed()),
+            None => with_glyph_extra(&section),
+        };
+        if self.deterministic {
+            self.glyph_brush
+                .glyphs_custom_layout(section, &RoundedLayout(custom_layout))
+        } else {
+            self.glyph_brush
+                .glyphs_custom_layout(section, custom_layout)
+        }
     }
 
     /// Returns the available fonts.
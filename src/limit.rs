@@ -0,0 +1,126 @@
+use super::*;
+
+use glyph_brush::{OwnedSection, OwnedText};
+
+/// Number of glyphs the characters of `text` lay out to, ignoring whitespace
+/// which produces no quads.
+fn glyph_count(text: &str) -> usize {
+    text.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+/// Cuts `text` after its first `max` glyphs.
+fn truncate_str(text: &str, max: usize) -> &str {
+    let end = text
+        .char_indices()
+        .filter(|(_, c)| !c.is_whitespace())
+        .nth(max)
+        .map_or(text.len(), |(i, _)| i);
+    &text[..end]
+}
+
+/// Returns a copy of `section` cut after its first `max` glyphs.
+fn truncate_section<X: Clone>(section: &Section<X>, mut max: usize) -> OwnedSection<X> {
+    let text = section
+        .text
+        .iter()
+        .map(|t| {
+            let text = truncate_str(t.text, max);
+            max -= glyph_count(text);
+            OwnedText {
+                text: text.to_owned(),
+                scale: t.scale,
+                font_id: t.font_id,
+                extra: t.extra.clone(),
+            }
+        })
+        .collect();
+    OwnedSection {
+        screen_position: section.screen_position,
+        bounds: section.bounds,
+        layout: section.layout,
+        text,
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Sets the maximum number of glyphs queued per frame, `None` to remove the limit.
+    ///
+    /// Guards against huge inputs, e.g. user provided text, blowing up layout and
+    /// vertex generation. Sections queued once the cap is reached are truncated,
+    /// see [`truncated_glyphs`](#method.truncated_glyphs).
+    pub fn set_glyph_cap(&mut self, cap: Option<usize>) {
+        self.glyph_cap = cap;
+    }
+
+    /// Returns the cap set with [`set_glyph_cap`](#method.set_glyph_cap).
+    pub fn glyph_cap(&self) -> Option<usize> {
+        self.glyph_cap
+    }
+
+    /// Returns the number of glyphs dropped from the sections queued since the last
+    /// draw because of the glyph cap.
+    pub fn truncated_glyphs(&self) -> usize {
+        self.truncated_glyphs
+    }
+
+    /// Counts glyphs being queued against the cap, returning how many of them fit.
+    pub(crate) fn cap_count(&mut self, count: usize) -> usize {
+        let remaining = match self.glyph_cap {
+            Some(cap) => cap.saturating_sub(self.queued_glyphs),
+            None => return count,
+        };
+        let fitting = count.min(remaining);
+        self.queued_glyphs += fitting;
+        self.truncated_glyphs += count - fitting;
+        fitting
+    }
+
+    /// Counts the glyphs of a section being queued against the cap, returning
+    /// a truncated copy if it doesn't fit.
+    pub(crate) fn cap_glyphs(&mut self, section: &Section<X>) -> Option<OwnedSection<X>> {
+        self.glyph_cap?;
+        let count: usize = section.text.iter().map(|t| glyph_count(t.text)).sum();
+        let remaining = self.cap_count(count);
+        if remaining == count {
+            return None;
+        }
+
+        Some(truncate_section(section, remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::Text;
+
+    #[test]
+    fn truncate_str_counts_glyphs() {
+        assert_eq!(truncate_str("hello world", 7), "hello wo");
+        assert_eq!(truncate_str("hello world", 5), "hello ");
+        assert_eq!(truncate_str("hello", 0), "");
+        assert_eq!(truncate_str("hello", 10), "hello");
+        assert_eq!(truncate_str("äöü", 2), "äö");
+        assert_eq!(glyph_count(" a\tb\n"), 2);
+    }
+
+    #[test]
+    fn truncate_section_mid_text() {
+        let section = Section::default()
+            .add_text(Text::new("one "))
+            .add_text(Text::new("two three"))
+            .add_text(Text::new("four"));
+        let texts: Vec<_> = truncate_section(&section, 6)
+            .text
+            .into_iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(texts, ["one ", "two ", ""]);
+        let texts: Vec<_> = truncate_section(&section, 3)
+            .text
+            .into_iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(texts, ["one ", "", ""]);
+    }
+}
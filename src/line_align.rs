@@ -0,0 +1,152 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, HorizontalAlign, Layout, SectionGeometry, SectionGlyph, ToSectionText,
+    VerticalAlign,
+};
+
+/// Layout aligning every line of the source text, i.e. every run of text between
+/// explicit line breaks, on its own, e.g. a centered title above a left aligned body
+/// in one section.
+///
+/// The section's `screen_position` is the left top corner of the text box, which is as
+/// wide as the section's width bound, or as the widest line if the text is unbounded.
+/// Lines wrap at the width bound, wrapped lines keep the alignment of their source line.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::LineAlignLayout;
+/// # use glium_glyph::glyph_brush::{HorizontalAlign, Section, Text};
+/// # fn main() {
+/// let text = Section::default()
+///     .add_text(Text::new("Title\nThe body text below the title"))
+///     .with_bounds((300.0, f32::INFINITY));
+/// let layout = LineAlignLayout::new(HorizontalAlign::Left).line_align(0, HorizontalAlign::Center);
+/// # let _ = (text, layout);
+/// // glyph_brush.queue_custom_layout(text, &layout);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LineAlignLayout {
+    line_breaker: BuiltInLineBreaker,
+    h_align: HorizontalAlign,
+    /// `(line, alignment)` overrides
+    line_aligns: Vec<(usize, HorizontalAlign)>,
+}
+
+impl LineAlignLayout {
+    /// Creates a layout aligning lines without an override with `h_align`.
+    pub fn new(h_align: HorizontalAlign) -> Self {
+        LineAlignLayout {
+            line_breaker: BuiltInLineBreaker::default(),
+            h_align,
+            line_aligns: Vec::new(),
+        }
+    }
+
+    /// Aligns the `line`th line of the source text with `h_align`, counting explicit
+    /// line breaks from 0.
+    pub fn line_align(mut self, line: usize, h_align: HorizontalAlign) -> Self {
+        self.line_aligns.retain(|&(l, _)| l != line);
+        self.line_aligns.push((line, h_align));
+        self
+    }
+
+    pub fn line_breaker(mut self, line_breaker: BuiltInLineBreaker) -> Self {
+        self.line_breaker = line_breaker;
+        self
+    }
+
+    fn align_of(&self, line: usize) -> HorizontalAlign {
+        self.line_aligns
+            .iter()
+            .find(|&&(l, _)| l == line)
+            .map_or(self.h_align, |&(_, h_align)| h_align)
+    }
+
+    fn layout(&self) -> Layout<BuiltInLineBreaker> {
+        Layout::Wrap {
+            line_breaker: self.line_breaker,
+            h_align: HorizontalAlign::Left,
+            v_align: VerticalAlign::Top,
+        }
+    }
+}
+
+impl GlyphPositioner for LineAlignLayout {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs = self.layout().calculate_glyphs(fonts, geometry, sections);
+        if glyphs.is_empty() {
+            return glyphs;
+        }
+
+        // source line of every glyph, from the line breaks before it
+        let texts: Vec<&str> = sections.iter().map(|s| s.to_section_text().text).collect();
+        let breaks_before: Vec<usize> = texts
+            .iter()
+            .scan(0, |breaks, text| {
+                let before = *breaks;
+                *breaks += text.matches('\n').count();
+                Some(before)
+            })
+            .collect();
+        let source_line = |g: &SectionGlyph| {
+            let text = texts[g.section_index];
+            breaks_before[g.section_index] + text[..g.byte_index].matches('\n').count()
+        };
+
+        // visual lines share their baseline, `(baseline, source line, right edge)`
+        let left = geometry.screen_position.0;
+        let mut lines: Vec<(f32, usize, f32)> = Vec::new();
+        for g in &glyphs {
+            let text = texts[g.section_index];
+            // whitespace, e.g. before a wrap, doesn't count towards the width
+            let right = if text[g.byte_index..].starts_with(char::is_whitespace) {
+                left
+            } else {
+                let font = fonts[g.font_id.0].as_scaled(g.glyph.scale);
+                g.glyph.position.x + font.h_advance(g.glyph.id)
+            };
+            let baseline = g.glyph.position.y;
+            match lines.iter_mut().find(|(b, ..)| *b == baseline) {
+                Some((_, _, line_right)) => *line_right = line_right.max(right),
+                None => lines.push((baseline, source_line(g), right)),
+            }
+        }
+        let width = match geometry.bounds.0.is_finite() {
+            true => geometry.bounds.0,
+            false => lines
+                .iter()
+                .map(|&(.., right)| right - left)
+                .fold(0.0, f32::max),
+        };
+
+        for g in &mut glyphs {
+            let (_, line, right) = *lines
+                .iter()
+                .find(|(b, ..)| *b == g.glyph.position.y)
+                .unwrap();
+            let free = width - (right - left);
+            g.glyph.position.x += match self.align_of(line) {
+                HorizontalAlign::Left => 0.0,
+                HorizontalAlign::Center => free / 2.0,
+                HorizontalAlign::Right => free,
+            };
+        }
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout().bounds_rect(geometry)
+    }
+}
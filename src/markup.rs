@@ -0,0 +1,332 @@
+use super::*;
+
+use std::error::Error;
+use std::fmt;
+
+use glyph_brush::ab_glyph::PxScale;
+use glyph_brush::Text;
+
+/// Error returned when parsing markup with [`Markup::parse`](struct.Markup.html#method.parse).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MarkupError {
+    /// A `<color=...>` tag with a value other than `#rrggbb` or `#rrggbbaa`,
+    /// at the given byte offset.
+    InvalidColor(usize),
+    /// A `<size=...>` tag with a value that isn't a positive, finite number,
+    /// at the given byte offset.
+    InvalidSize(usize),
+    /// A closing tag without a matching opening tag, at the given byte offset.
+    UnmatchedClosingTag(usize),
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkupError::InvalidColor(at) => write!(f, "invalid color at byte {}", at),
+            MarkupError::InvalidSize(at) => write!(f, "invalid size at byte {}", at),
+            MarkupError::UnmatchedClosingTag(at) => {
+                write!(f, "unmatched closing tag at byte {}", at)
+            }
+        }
+    }
+}
+
+impl Error for MarkupError {}
+
+enum Tag {
+    Bold,
+    Italic,
+    Color(Option<[f32; 4]>),
+    Size(Option<f32>),
+}
+
+impl Tag {
+    /// Parses the contents of a tag, `None` if it isn't a known tag.
+    fn parse(tag: &str) -> Option<(Tag, bool)> {
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) if !closing => (name, Some(value)),
+            Some(_) => return None,
+            None => (tag, None),
+        };
+        let tag = match (name, value) {
+            ("b", None) => Tag::Bold,
+            ("i", None) => Tag::Italic,
+            ("color", Some(value)) => Tag::Color(parse_color(value)),
+            ("color", None) if closing => Tag::Color(None),
+            ("size", Some(value)) => Tag::Size(
+                value
+                    .parse()
+                    .ok()
+                    .filter(|&s: &f32| s.is_finite() && s > 0.0),
+            ),
+            ("size", None) if closing => Tag::Size(None),
+            _ => return None,
+        };
+        Some((tag, closing))
+    }
+}
+
+/// Parses `#rrggbb` or `#rrggbbaa`.
+fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let hex = value.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+    let mut color = [1.0; 4];
+    for (i, c) in color.iter_mut().enumerate().take(hex.len() / 2) {
+        *c = f32::from(u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?) / 255.0;
+    }
+    Some(color)
+}
+
+/// Parses a small markup language into the `Text`s of a section, e.g. for chat
+/// logs and dialog boxes.
+///
+/// Supports `<b>` and `<i>`, switching to the fonts set with [`bold`](#method.bold),
+/// [`italic`](#method.italic) and [`bold_italic`](#method.bold_italic),
+/// `<color=#rrggbb>` or `<color=#rrggbbaa>`, and `<size=24>` in pixels.
+/// Tags nest and are closed with `</b>`, `</i>`, `</color>` and `</size>`.
+/// A `<` that doesn't start a known tag is kept as text, `<<` is a literal `<`, e.g. to
+/// write `<<b>` for the text `<b>`.
+///
+/// ```
+/// # use glium_glyph::Markup;
+/// # use glium_glyph::glyph_brush::{FontId, Section};
+/// let markup = Markup::new(FontId(0)).bold(FontId(1)).scale(20.0);
+/// let text = markup.parse("<b>Alice:</b> <color=#ff0000>hi</color> <3").unwrap();
+/// let section = Section::default().with_text(text);
+/// # assert_eq!(section.text.len(), 4);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Markup {
+    font_id: FontId,
+    bold: Option<FontId>,
+    italic: Option<FontId>,
+    bold_italic: Option<FontId>,
+    scale: PxScale,
+    color: [f32; 4],
+}
+
+impl Markup {
+    /// Creates a parser using `font_id` for regular text.
+    pub fn new(font_id: FontId) -> Self {
+        Markup {
+            font_id,
+            bold: None,
+            italic: None,
+            bold_italic: None,
+            scale: PxScale::from(16.0),
+            color: [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Sets the font of `<b>` text.
+    ///
+    /// Defaults to the regular font.
+    pub fn bold(mut self, font_id: FontId) -> Self {
+        self.bold = Some(font_id);
+        self
+    }
+
+    /// Sets the font of `<i>` text.
+    ///
+    /// Defaults to the regular font.
+    pub fn italic(mut self, font_id: FontId) -> Self {
+        self.italic = Some(font_id);
+        self
+    }
+
+    /// Sets the font of text that's both bold and italic.
+    ///
+    /// Defaults to the bold font, or the italic one if there's no bold font.
+    pub fn bold_italic(mut self, font_id: FontId) -> Self {
+        self.bold_italic = Some(font_id);
+        self
+    }
+
+    /// Sets the scale of text outside of `<size>` tags.
+    ///
+    /// Defaults to 16.
+    pub fn scale<S: Into<PxScale>>(mut self, scale: S) -> Self {
+        self.scale = scale.into();
+        self
+    }
+
+    /// Sets the color of text outside of `<color>` tags.
+    ///
+    /// Defaults to black.
+    pub fn color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Creates a text with the current style.
+    fn text<'a>(
+        &self,
+        text: &'a str,
+        (bold, italic): (usize, usize),
+        scales: &[PxScale],
+        colors: &[[f32; 4]],
+    ) -> Text<'a> {
+        Text::new(text)
+            .with_font_id(self.font(bold, italic))
+            .with_scale(*scales.last().unwrap())
+            .with_color(*colors.last().unwrap())
+    }
+
+    fn font(&self, bold: usize, italic: usize) -> FontId {
+        let font = match (bold > 0, italic > 0) {
+            (true, true) => self.bold_italic.or(self.bold).or(self.italic),
+            (true, false) => self.bold,
+            (false, true) => self.italic,
+            (false, false) => None,
+        };
+        font.unwrap_or(self.font_id)
+    }
+
+    /// Parses `markup` into texts borrowing from it.
+    pub fn parse<'a>(&self, markup: &'a str) -> Result<Vec<Text<'a>>, MarkupError> {
+        let (mut bold, mut italic) = (0, 0);
+        let mut colors = vec![self.color];
+        let mut scales = vec![self.scale];
+        let mut texts = Vec::new();
+
+        let mut text_start = 0;
+        let mut rest_start = 0;
+        while let Some(open) = markup[rest_start..].find('<').map(|i| rest_start + i) {
+            if markup[open + 1..].starts_with('<') {
+                let text = &markup[text_start..open + 1];
+                texts.push(self.text(text, (bold, italic), &scales, &colors));
+                text_start = open + 2;
+                rest_start = text_start;
+                continue;
+            }
+            let tag = markup[open + 1..]
+                .find('>')
+                .and_then(|len| Some((Tag::parse(&markup[open + 1..open + 1 + len])?, len)));
+            let ((tag, closing), len) = match tag {
+                Some(tag) => tag,
+                None => {
+                    rest_start = open + 1;
+                    continue;
+                }
+            };
+            if text_start < open {
+                let text = &markup[text_start..open];
+                texts.push(self.text(text, (bold, italic), &scales, &colors));
+            }
+            let unmatched = MarkupError::UnmatchedClosingTag(open);
+            match (tag, closing) {
+                (Tag::Bold, false) => bold += 1,
+                (Tag::Italic, false) => italic += 1,
+                (Tag::Color(color), false) => {
+                    colors.push(color.ok_or(MarkupError::InvalidColor(open))?);
+                }
+                (Tag::Size(size), false) => {
+                    scales.push(PxScale::from(size.ok_or(MarkupError::InvalidSize(open))?));
+                }
+                (Tag::Bold, true) if bold > 0 => bold -= 1,
+                (Tag::Italic, true) if italic > 0 => italic -= 1,
+                (Tag::Color(_), true) if colors.len() > 1 => {
+                    colors.pop();
+                }
+                (Tag::Size(_), true) if scales.len() > 1 => {
+                    scales.pop();
+                }
+                (_, true) => return Err(unmatched),
+            }
+            text_start = open + len + 2;
+            rest_start = text_start;
+        }
+        if text_start < markup.len() {
+            let text = &markup[text_start..];
+            texts.push(self.text(text, (bold, italic), &scales, &colors));
+        }
+        Ok(texts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(markup: &str) -> Result<Vec<Text<'_>>, MarkupError> {
+        Markup::new(FontId(0))
+            .bold(FontId(1))
+            .italic(FontId(2))
+            .bold_italic(FontId(3))
+            .parse(markup)
+    }
+
+    fn texts(markup: &str) -> Vec<(&str, usize)> {
+        parse(markup)
+            .unwrap()
+            .iter()
+            .map(|t| (t.text, t.font_id.0))
+            .collect()
+    }
+
+    #[test]
+    fn nesting() {
+        assert_eq!(
+            texts("a<b>b<i>bi</i>b</b>a"),
+            [("a", 0), ("b", 1), ("bi", 3), ("b", 1), ("a", 0)]
+        );
+        let parsed = parse("<size=20>a<color=#ff000080>b</color>c</size>d").unwrap();
+        let scales: Vec<_> = parsed.iter().map(|t| t.scale.y).collect();
+        assert_eq!(scales, [20.0, 20.0, 20.0, 16.0]);
+        assert_eq!(parsed[1].extra.color, [1.0, 0.0, 0.0, 128.0 / 255.0]);
+        assert_eq!(parsed[2].extra.color, [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn unknown_tags_are_text() {
+        assert_eq!(texts("a <3 <u>b</u>"), [("a <3 <u>b</u>", 0)]);
+        assert_eq!(texts("<b=1>a</b=1>"), [("<b=1>a</b=1>", 0)]);
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(texts("<<b>"), [("<", 0), ("b>", 0)]);
+        assert_eq!(texts("a<<<b>c"), [("a<", 0), ("c", 1)]);
+        assert_eq!(texts("<<"), [("<", 0)]);
+    }
+
+    #[test]
+    fn unmatched_closing_tags() {
+        assert_eq!(
+            parse("a</b>").unwrap_err(),
+            MarkupError::UnmatchedClosingTag(1)
+        );
+        assert_eq!(
+            parse("<b>a</b></i>").unwrap_err(),
+            MarkupError::UnmatchedClosingTag(8)
+        );
+        assert_eq!(
+            parse("</color>").unwrap_err(),
+            MarkupError::UnmatchedClosingTag(0)
+        );
+        // unclosed tags apply to the end
+        assert_eq!(texts("<b>a"), [("a", 1)]);
+    }
+
+    #[test]
+    fn bad_colors() {
+        for color in &["ff0000", "#ff00", "#ff00000", "#gg0000", "#ff00ü0", ""] {
+            let markup = format!("a<color={}>b", color);
+            assert_eq!(parse(&markup).unwrap_err(), MarkupError::InvalidColor(1));
+        }
+    }
+
+    #[test]
+    fn bad_sizes() {
+        for size in &["0", "-12", "NaN", "inf", "1e40", "big", ""] {
+            let markup = format!("a<size={}>b", size);
+            assert_eq!(parse(&markup).unwrap_err(), MarkupError::InvalidSize(1));
+        }
+    }
+}
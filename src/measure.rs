@@ -0,0 +1,44 @@
+use super::*;
+
+/// Size of a section's text, from
+/// [`GlyphBrush::content_size`](struct.GlyphBrush.html#method.content_size).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ContentSize {
+    /// Width of the text without wrapping, only breaking lines at explicit line breaks.
+    pub natural_width: f32,
+    /// Width of the text wrapped at the given maximum width.
+    pub width: f32,
+    /// Height of the text wrapped at the given maximum width.
+    pub height: f32,
+}
+
+impl<F: Font, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Measures `section` both unwrapped and wrapped at `max_width`, e.g. for a container
+    /// shrinking to its content: one as wide as `natural_width.min(max_width)` fits the
+    /// text in `height`.
+    ///
+    /// The section's bounds are ignored and its layout is used for both measurements,
+    /// which are cached like [`glyph_bounds`](#method.glyph_bounds). Empty sections
+    /// measure zero.
+    pub fn content_size<'a, S>(&mut self, section: S, max_width: f32) -> ContentSize
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        let section = section.into();
+        let mut size = |bounds: (f32, f32)| {
+            let bounded = Section {
+                bounds,
+                ..section.as_ref().clone()
+            };
+            self.glyph_bounds(bounded)
+                .map_or((0.0, 0.0), |rect| (rect.width(), rect.height()))
+        };
+        let (natural_width, _) = size((f32::INFINITY, f32::INFINITY));
+        let (width, height) = size((max_width, f32::INFINITY));
+        ContentSize {
+            natural_width,
+            width,
+            height,
+        }
+    }
+}
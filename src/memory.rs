@@ -0,0 +1,108 @@
+use super::*;
+
+use std::mem;
+
+/// Memory used by a [`GlyphBrush`](struct.GlyphBrush.html), in bytes.
+///
+/// GPU sizes are derived from the allocated resource sizes, driver side overhead
+/// isn't included.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Size of the glyph cache texture and the other textures owned by the brush,
+    /// e.g. fill textures.
+    pub texture_bytes: usize,
+    /// Size of the vertex buffers holding the glyph quads.
+    pub vertex_buffer_bytes: usize,
+    /// Size of the glyph vertices cached on the CPU side to speed up redraws.
+    pub cpu_cache_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// Total GPU memory, i.e. the texture and vertex buffers.
+    pub fn gpu_bytes(&self) -> usize {
+        self.texture_bytes + self.vertex_buffer_bytes
+    }
+
+    /// Total memory, GPU and CPU.
+    pub fn total_bytes(&self) -> usize {
+        self.gpu_bytes() + self.cpu_cache_bytes
+    }
+}
+
+/// Bytes per texel of the cache texture, assuming RGBA8 if the driver can't tell.
+fn texel_bytes(texture: &Texture2d) -> usize {
+    texture
+        .get_internal_format()
+        .map(|f| f.get_total_bits() / 8)
+        .unwrap_or(4)
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Returns the current memory usage of the brush.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let (width, height) = self.cache_size();
+        let texture_bytes = width as usize * height as usize * texel_bytes(&self.texture);
+        #[cfg(feature = "color-glyphs")]
+        let texture_bytes = texture_bytes + self.color_glyphs.texture_bytes();
+        let texture_bytes = texture_bytes + self.fill_textures.texture_bytes();
+        let texture_bytes = texture_bytes + self.oit.as_ref().map_or(0, |oit| oit.texture_bytes());
+        MemoryUsage {
+            texture_bytes,
+            vertex_buffer_bytes: self.quads.size(),
+            cpu_cache_bytes: self.quads.glyphs() * mem::size_of::<GlyphVertex>(),
+        }
+    }
+
+    /// Sets a soft limit for the GPU memory used by the brush, `None` to remove it.
+    ///
+    /// Growth of the glyph cache texture is capped to stay within the budget. A frame
+    /// that can't be drawn within the budget still grows the texture, as text couldn't
+    /// be drawn otherwise. If the current usage exceeds a newly set budget, the cache
+    /// texture is shrunk, evicting all cached glyphs, on the next draw.
+    pub fn set_memory_budget(&mut self, budget: Option<usize>) {
+        self.memory_budget = budget;
+        self.trim_pending = budget.is_some_and(|b| self.memory_usage().gpu_bytes() > b);
+    }
+
+    /// Returns the budget set with [`set_memory_budget`](#method.set_memory_budget).
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    /// Shrinks the glyph cache texture back to its initial size and frees the vertex data,
+    /// evicting all cached glyphs.
+    ///
+    /// Glyphs are rasterized again as needed by subsequent draws.
+    pub fn trim_memory<C: Facade>(&mut self, facade: &C) -> Result<(), DrawError> {
+        let (width, height) = self.initial_cache_size;
+        if self.cache_region.is_none() {
+            self.texture = Rc::new(Texture2d::empty(facade, width, height)?);
+        }
+        self.glyph_brush.resize_texture(width, height);
+        self.cache_regions.clear();
+        self.quads.clear(facade)?;
+        #[cfg(feature = "color-glyphs")]
+        self.color_glyphs.clear_cache();
+        self.trim_pending = false;
+        Ok(())
+    }
+
+    /// Picks the cache texture size to grow to from the one suggested by glyph_brush,
+    /// respecting the memory budget where possible.
+    pub(crate) fn budgeted_texture_size(&self, suggested: (u32, u32)) -> (u32, u32) {
+        let budget = match self.memory_budget {
+            Some(budget) => budget,
+            None => return suggested,
+        };
+        let usage = self.memory_usage();
+        let texel_bytes = texel_bytes(&self.texture);
+        let available = budget.saturating_sub(usage.vertex_buffer_bytes) / texel_bytes;
+        let (width, height) = self.cache_size();
+        let candidates = [suggested, (suggested.0, height), (width, suggested.1)];
+        candidates
+            .iter()
+            .cloned()
+            .find(|&(w, h)| w as usize * h as usize <= available && (w > width || h > height))
+            .unwrap_or(suggested)
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+/// What the last draw of a [`GlyphBrush`](struct.GlyphBrush.html) had to do, e.g. to
+/// correlate frame time spikes with text changes. Returned by
+/// [`try_draw_queued`](struct.GlyphBrush.html#method.try_draw_queued) and its variants.
+///
+/// Color glyphs aren't counted.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Number of sections queued since the previous draw.
+    pub sections_processed: usize,
+    /// Number of glyphs drawn.
+    pub glyphs_drawn: usize,
+    /// Whether the glyph vertices were regenerated and uploaded, `false` if the
+    /// previous ones were drawn again.
+    pub regenerated_vertices: bool,
+    /// Number of newly rasterized glyphs uploaded into the cache texture.
+    pub texture_uploads: usize,
+    /// Size of the glyph data uploaded into the cache texture in bytes.
+    pub uploaded_bytes: usize,
+    /// Whether the cache texture was resized, which makes all glyphs rasterize again.
+    pub texture_resized: bool,
+    /// Texels of the cache texture holding glyphs after the draw, including glyphs that
+    /// weren't used recently but haven't been overwritten yet.
+    pub cache_used_area: u64,
+    /// Texels of the cache texture.
+    pub cache_area: u64,
+    /// Number of batches the queue was drawn in because its glyphs didn't fit into the
+    /// largest cache texture at once, each rasterizing its glyphs again. 0 if it fit.
+    pub draw_batches: usize,
+    /// Whether nothing was drawn because the queue was the first one not to fit into the
+    /// largest cache texture at once. The brush only keeps the laid out glyphs needed to
+    /// draw in batches from then on.
+    pub frame_skipped: bool,
+}
+
+impl Metrics {
+    /// Percentage of the cache texture holding glyphs, see
+    /// [`cache_used_area`](#structfield.cache_used_area).
+    pub fn atlas_occupancy(&self) -> f32 {
+        match self.cache_area {
+            0 => 0.0,
+            area => self.cache_used_area as f32 / area as f32 * 100.0,
+        }
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Returns the metrics of the last call to
+    /// [`draw_queued`](#method.draw_queued) or one of its variants.
+    pub fn metrics(&self) -> Metrics {
+        self.metrics
+    }
+}
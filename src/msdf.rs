@@ -0,0 +1,203 @@
+use super::*;
+
+use std::collections::HashMap;
+
+use glyph_brush::ab_glyph::{GlyphId, Rect, ScaleFont};
+use glyph_brush::SectionGeometry;
+
+/// A pre-generated multi-channel signed distance field atlas, e.g. as produced by
+/// msdfgen / msdf-atlas-gen.
+///
+/// MSDFs keep glyph corners sharp at any magnification, but can't be generated from
+/// the coverage bitmaps glyph_brush rasterizes, so they are loaded instead. Glyphs
+/// are still laid out with the font they were generated from.
+#[derive(Clone, Debug)]
+pub struct MsdfAtlas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    distance_range: f32,
+    glyphs: HashMap<(FontId, GlyphId), MsdfGlyph>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct MsdfGlyph {
+    atlas_bounds: Rect,
+    plane_bounds: Rect,
+}
+
+impl MsdfAtlas {
+    /// Creates an atlas from RGBA8 pixels, rows ordered top to bottom.
+    ///
+    /// `distance_range` is the width in texels of the distance field around the outlines,
+    /// the `-pxrange` of msdfgen.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>, distance_range: f32) -> Self {
+        assert_eq!(pixels.len(), width as usize * height as usize * 4);
+        MsdfAtlas {
+            width,
+            height,
+            pixels,
+            distance_range,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// Adds the glyph `glyph_id` of the font `font_id`.
+    ///
+    /// `atlas_bounds` are in texels with the origin at the top left of the atlas.
+    /// `plane_bounds` are in ems relative to the glyph origin on the baseline, with y
+    /// pointing up, matching the msdf-atlas-gen json output with a top y origin for
+    /// the atlas.
+    pub fn add_glyph(
+        &mut self,
+        font_id: FontId,
+        glyph_id: GlyphId,
+        atlas_bounds: Rect,
+        plane_bounds: Rect,
+    ) {
+        self.glyphs.insert(
+            (font_id, glyph_id),
+            MsdfGlyph {
+                atlas_bounds,
+                plane_bounds,
+            },
+        );
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+/// Draws text using a pre-generated [`MsdfAtlas`](struct.MsdfAtlas.html).
+///
+/// Glyphs missing from the atlas are skipped.
+pub struct MsdfBrush<F> {
+    fonts: Vec<F>,
+    glyphs: HashMap<(FontId, GlyphId), MsdfGlyph>,
+    distance_range: f32,
+    queued: Vec<GlyphVertex>,
+    params: glium::DrawParameters<'static>,
+    program: Program,
+    texture: Texture2d,
+    quads: QuadBuffer,
+}
+
+impl<F: Font> MsdfBrush<F> {
+    /// Uploads the atlas texture and creates the GPU resources for drawing.
+    ///
+    /// `fonts` are indexed by the `FontId`s used in the atlas and the queued sections.
+    pub fn new<C: Facade>(facade: &C, fonts: Vec<F>, atlas: MsdfAtlas) -> Result<Self, BuildError> {
+        static VERTEX_SHADER: &str = include_str!("shader/vert.glsl");
+        static FRAGMENT_SHADER: &str = include_str!("shader/frag_msdf.glsl");
+        let image = RawImage2d::from_raw_rgba(atlas.pixels, (atlas.width, atlas.height));
+        let texture = Texture2d::with_format(
+            facade,
+            image,
+            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+            glium::texture::MipmapsOption::NoMipmap,
+        )?;
+        Ok(MsdfBrush {
+            fonts,
+            glyphs: atlas.glyphs,
+            distance_range: atlas.distance_range,
+            queued: Vec::new(),
+            params: glium::DrawParameters {
+                blend: glium::Blend::alpha_blending(),
+                ..Default::default()
+            },
+            program: Program::from_source(facade, VERTEX_SHADER, FRAGMENT_SHADER, None)?,
+            texture,
+            quads: QuadBuffer::empty(facade, true)?,
+        })
+    }
+
+    pub fn params(mut self, params: glium::DrawParameters<'static>) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Lays out a section and queues it to be drawn.
+    pub fn queue<'s, S>(&mut self, section: S)
+    where
+        S: Into<Cow<'s, Section<'s>>>,
+    {
+        let section = section.into();
+        let geometry = SectionGeometry::from(section.as_ref());
+        let bounds = section.layout.bounds_rect(&geometry);
+        let (width, height) = (self.texture.width() as f32, self.texture.height() as f32);
+        let glyphs = section
+            .layout
+            .calculate_glyphs(&self.fonts, &geometry, &section.text);
+        for section_glyph in glyphs {
+            let key = (section_glyph.font_id, section_glyph.glyph.id);
+            let msdf_glyph = match self.glyphs.get(&key) {
+                Some(msdf_glyph) => msdf_glyph,
+                None => continue,
+            };
+            let font = &self.fonts[section_glyph.font_id.0];
+            let glyph = &section_glyph.glyph;
+            let scale = font.as_scaled(glyph.scale).scale_factor();
+            let units_per_em = font.units_per_em().unwrap_or(1.0);
+            let (plane, atlas) = (msdf_glyph.plane_bounds, msdf_glyph.atlas_bounds);
+            let pixel_coords = Rect {
+                min: point(
+                    glyph.position.x + plane.min.x * units_per_em * scale.horizontal,
+                    glyph.position.y - plane.max.y * units_per_em * scale.vertical,
+                ),
+                max: point(
+                    glyph.position.x + plane.max.x * units_per_em * scale.horizontal,
+                    glyph.position.y - plane.min.y * units_per_em * scale.vertical,
+                ),
+            };
+            let tex_coords = Rect {
+                min: point(atlas.min.x / width, atlas.min.y / height),
+                max: point(atlas.max.x / width, atlas.max.y / height),
+            };
+            self.queued.push(to_vertex(glyph_brush::GlyphVertex {
+                tex_coords,
+                pixel_coords,
+                bounds,
+                extra: &GlyphExtra::from(section.text[section_glyph.section_index].extra),
+            }));
+        }
+    }
+
+    /// Draws all queued sections onto a render target.
+    pub fn draw_queued<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<(), DrawError> {
+        let transform = screen_transform(surface.get_dimensions());
+        self.draw_queued_with_transform(transform, facade, surface)
+    }
+
+    /// Draws all queued sections onto a render target, applying a position transform.
+    pub fn draw_queued_with_transform<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        transform: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<(), DrawError> {
+        let uploaded = self.quads.upload(facade, &self.queued);
+        self.queued.clear();
+        uploaded?;
+
+        let sampler = glium::uniforms::Sampler::new(&self.texture)
+            .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+            .minify_filter(glium::uniforms::MinifySamplerFilter::Linear)
+            .magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear);
+        let uniforms = uniform! {
+            font_tex: sampler,
+            transform: transform,
+            distance_range: self.distance_range,
+            opacity: 1.0f32,
+            tint: NO_TINT,
+        };
+        self.quads
+            .all()
+            .draw(surface, &self.program, &uniforms, &self.params)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use super::*;
+
+use glium::framebuffer::MultiOutputFrameBuffer;
+use glium::texture::{MipmapsOption, UncompressedFloatFormat};
+use glium::vertex::EmptyVertexAttributes;
+use glium::{Blend, BlendingFunction, LinearBlendingFactor};
+
+/// Weighted blended order-independent transparency: glyphs are accumulated into
+/// offscreen textures, then resolved onto the surface in a fullscreen pass.
+pub(crate) struct Oit {
+    program: Program,
+    resolve: Program,
+    /// premultiplied color sums and revealage, sized to the surface
+    accum: Option<Texture2d>,
+    /// weight sums
+    weight: Option<Texture2d>,
+}
+
+impl Oit {
+    pub(crate) fn new<C: Facade>(facade: &C) -> Result<Self, ProgramCreationError> {
+        static VERTEX_SHADER: &str = include_str!("shader/vert.glsl");
+        static FRAGMENT_SHADER: &str = include_str!("shader/frag_oit.glsl");
+        static RESOLVE_VERTEX_SHADER: &str = include_str!("shader/vert_resolve.glsl");
+        static RESOLVE_FRAGMENT_SHADER: &str = include_str!("shader/frag_resolve.glsl");
+        Ok(Oit {
+            program: Program::from_source(facade, VERTEX_SHADER, FRAGMENT_SHADER, None)?,
+            resolve: Program::from_source(
+                facade,
+                RESOLVE_VERTEX_SHADER,
+                RESOLVE_FRAGMENT_SHADER,
+                None,
+            )?,
+            accum: None,
+            weight: None,
+        })
+    }
+
+    pub(crate) fn texture_bytes(&self) -> usize {
+        // RGBA16F each
+        [&self.accum, &self.weight]
+            .iter()
+            .flat_map(|t| t.iter())
+            .map(|t| t.width() as usize * t.height() as usize * 8)
+            .sum()
+    }
+
+    /// (Re)creates the offscreen textures if the surface size changed.
+    fn resize<C: Facade>(
+        &mut self,
+        facade: &C,
+        (width, height): (u32, u32),
+    ) -> Result<(), DrawError> {
+        if self.accum.as_ref().map(|t| t.dimensions()) == Some((width, height)) {
+            return Ok(());
+        }
+        let texture = || {
+            Texture2d::empty_with_format(
+                facade,
+                UncompressedFloatFormat::F16F16F16F16,
+                MipmapsOption::NoMipmap,
+                width,
+                height,
+            )
+        };
+        self.accum = Some(texture()?);
+        self.weight = Some(texture()?);
+        Ok(())
+    }
+
+    pub(crate) fn draw<C: Facade, S: Surface>(
+        &mut self,
+        facade: &C,
+        surface: &mut S,
+        texture: &Texture2d,
+        quads: Quads,
+        draw_uniforms: DrawUniforms,
+        params: &glium::DrawParameters,
+    ) -> Result<(), DrawError> {
+        self.resize(facade, surface.get_dimensions())?;
+        let (accum, weight) = match (&self.accum, &self.weight) {
+            (Some(accum), Some(weight)) => (accum, weight),
+            _ => return Ok(()),
+        };
+
+        let mut framebuffer =
+            MultiOutputFrameBuffer::new(facade, [("accum", accum), ("weight", weight)])?;
+        framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+        let sampler = glium::uniforms::Sampler::new(texture)
+            .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+            .minify_filter(draw_uniforms.texture_filter.0)
+            .magnify_filter(draw_uniforms.texture_filter.1);
+        let uniforms = WithUserUniforms {
+            uniforms: uniform! {
+                font_tex: sampler,
+                transform: draw_uniforms.transform,
+                gamma: draw_uniforms.gamma,
+                opacity: draw_uniforms.opacity,
+                tint: draw_uniforms.tint,
+            },
+            user: draw_uniforms.user,
+        };
+        let accumulate = glium::DrawParameters {
+            // sum up the color channels, multiply the alpha channel by 1 - alpha
+            blend: Blend {
+                color: BlendingFunction::Addition {
+                    source: LinearBlendingFactor::One,
+                    destination: LinearBlendingFactor::One,
+                },
+                alpha: BlendingFunction::Addition {
+                    source: LinearBlendingFactor::Zero,
+                    destination: LinearBlendingFactor::OneMinusSourceAlpha,
+                },
+                constant_value: (0.0, 0.0, 0.0, 0.0),
+            },
+            // there's no depth buffer to test against
+            depth: glium::Depth::default(),
+            stencil: Default::default(),
+            ..params.clone()
+        };
+        quads.draw(&mut framebuffer, &self.program, &uniforms, &accumulate)?;
+
+        let uniforms = uniform! {
+            accum_tex: accum,
+            weight_tex: weight,
+        };
+        let resolve = glium::DrawParameters {
+            blend: Blend::alpha_blending(),
+            depth: glium::Depth::default(),
+            stencil: Default::default(),
+            ..params.clone()
+        };
+        surface.draw(
+            EmptyVertexAttributes { len: 3 },
+            glium::index::NoIndices(PrimitiveType::TrianglesList),
+            &self.resolve,
+            &uniforms,
+            &resolve,
+        )?;
+        Ok(())
+    }
+}
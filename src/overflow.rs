@@ -0,0 +1,304 @@
+use super::*;
+
+use glyph_brush::ab_glyph::Rect;
+use glyph_brush::SectionGeometry;
+
+/// Laid out glyphs of a queued section.
+#[derive(Clone)]
+pub(crate) struct QueuedGlyphs<X> {
+    glyphs: Vec<SectionGlyph>,
+    extra: Vec<GlyphExtra<X>>,
+    bounds: Rect,
+}
+
+/// Glyphs of one or more sections processed together.
+type GlyphBatch<X> = Vec<QueuedGlyphs<X>>;
+
+/// The queue of a brush kept as laid out glyphs, to draw it in batches if its glyphs
+/// don't fit into the largest cache texture at once.
+///
+/// Glyphs are only kept once a queue didn't fit, glyph_brush doesn't hand out the glyphs
+/// of its queue, so that frame is skipped.
+pub(crate) struct Overflow<X> {
+    /// whether queued glyphs are kept
+    recording: bool,
+    /// whether anything was queued since the queue was last processed
+    pending: bool,
+    /// glyphs queued since the queue was last processed
+    queued: GlyphBatch<X>,
+    /// batches of the processed queue in draw order, empty if it fit at once
+    batches: Vec<GlyphBatch<X>>,
+    /// batch whose vertices are uploaded
+    loaded: usize,
+    /// number of batches whose glyphs are in the damage of the draw
+    damaged: usize,
+}
+
+impl<X> Default for Overflow<X> {
+    fn default() -> Self {
+        Overflow {
+            recording: false,
+            pending: false,
+            queued: Vec::new(),
+            batches: Vec::new(),
+            loaded: 0,
+            damaged: 0,
+        }
+    }
+}
+
+impl<X: GlyphData> Overflow<X> {
+    /// Returns the glyphs queued since the queue was last processed, which is about to be
+    /// processed now.
+    pub(crate) fn processing(&mut self) -> GlyphBatch<X> {
+        self.batches.clear();
+        self.pending = false;
+        std::mem::take(&mut self.queued)
+    }
+
+    /// Forgets the queued glyphs after the queue was processed or discarded otherwise.
+    pub(crate) fn forget_queued(&mut self) {
+        self.queued.clear();
+        self.pending = false;
+    }
+
+    /// Whether glyphs were queued since the queue was last processed.
+    pub(crate) fn has_queued(&self) -> bool {
+        self.pending
+    }
+
+    /// Whether the glyphs of the queue are kept, so that it can be drawn in batches.
+    pub(crate) fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Starts keeping the glyphs of the queue from the next frame on, after the current
+    /// one didn't fit.
+    pub(crate) fn start_recording(&mut self) {
+        self.recording = true;
+    }
+
+    /// Whether the processed queue is drawn in batches.
+    pub(crate) fn is_batched(&self) -> bool {
+        !self.batches.is_empty()
+    }
+}
+
+/// Splits glyphs into two batches of about the same number of glyphs, splitting a
+/// section if needed. Returns `None` for a single glyph.
+fn split<X: GlyphData>(mut batch: GlyphBatch<X>) -> Option<(GlyphBatch<X>, GlyphBatch<X>)> {
+    let total: usize = batch.iter().map(|q| q.glyphs.len()).sum();
+    if total < 2 {
+        return None;
+    }
+    let mut first_len = total / 2;
+    let mut first = Vec::new();
+    let mut rest = batch.drain(..);
+    for queued in rest.by_ref() {
+        if queued.glyphs.len() <= first_len {
+            first_len -= queued.glyphs.len();
+            first.push(queued);
+            if first_len == 0 {
+                break;
+            }
+        } else {
+            let mut head = queued.clone();
+            let tail = QueuedGlyphs {
+                glyphs: head.glyphs.split_off(first_len),
+                ..queued
+            };
+            first.push(head);
+            let mut second = vec![tail];
+            second.extend(rest);
+            return Some((first, second));
+        }
+    }
+    let second = rest.collect();
+    Some((first, second))
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Queues a laid out section, keeping its glyphs in case the queue has to be drawn in
+    /// batches.
+    pub(crate) fn queue_laid_out<G: GlyphPositioner>(
+        &mut self,
+        section: Section<GlyphExtra<X>>,
+        custom_layout: &G,
+    ) {
+        self.glyph_brush
+            .queue_custom_layout(&section, custom_layout);
+        self.overflow.pending = true;
+        if !self.overflow.recording {
+            return;
+        }
+        let bounds = custom_layout.bounds_rect(&SectionGeometry::from(&section));
+        let extra = section.text.iter().map(|t| t.extra.clone()).collect();
+        // the glyphs were just laid out and cached by glyph_brush
+        let glyphs = self
+            .glyph_brush
+            .glyphs_custom_layout(&section, custom_layout)
+            .cloned()
+            .collect();
+        self.overflow.queued.push(QueuedGlyphs {
+            glyphs,
+            extra,
+            bounds,
+        });
+    }
+
+    /// Queues pre-positioned glyphs to be drawn, keeping them in case the queue has to be
+    /// drawn in batches.
+    pub(crate) fn queue_glyphs(
+        &mut self,
+        glyphs: Vec<SectionGlyph>,
+        extra: Vec<GlyphExtra<X>>,
+        bounds: Rect,
+    ) {
+        self.overflow.pending = true;
+        if self.overflow.recording {
+            self.overflow.queued.push(QueuedGlyphs {
+                glyphs: glyphs.clone(),
+                extra: extra.clone(),
+                bounds,
+            });
+        }
+        self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds);
+    }
+
+    /// Processes the queue in batches that each fit into the cache texture, after all of
+    /// it didn't, and uploads the vertices of the first batch. The other batches are
+    /// uploaded and drawn in turn by `draw_batches`.
+    pub(crate) fn process_in_batches<C: Facade>(
+        &mut self,
+        facade: &C,
+        queued: GlyphBatch<X>,
+    ) -> Result<bool, DrawError> {
+        self.discard_queued();
+        self.metrics.glyphs_drawn = queued.iter().map(|q| q.glyphs.len()).sum();
+        let (first, second) = split(queued).ok_or(DrawError::CacheFull)?;
+        self.overflow.batches = vec![first, second];
+        self.overflow.damaged = 0;
+        self.load_batch(facade, 0)
+    }
+
+    /// Uploads the vertices of a batch, splitting it further if it doesn't fit into the
+    /// cache texture either. Returns `false` if the frame is skipped.
+    fn load_batch<C: Facade>(&mut self, facade: &C, index: usize) -> Result<bool, DrawError> {
+        let action = loop {
+            for queued in &self.overflow.batches[index] {
+                self.glyph_brush.queue_pre_positioned(
+                    queued.glyphs.clone(),
+                    queued.extra.clone(),
+                    queued.bounds,
+                );
+            }
+            match self.process_queued_uploading() {
+                Ok(action) => break action,
+                Err(BrushError::TextureTooSmall { .. }) => {
+                    self.discard_queued();
+                    let batch = std::mem::take(&mut self.overflow.batches[index]);
+                    let (first, second) = split(batch).ok_or(DrawError::CacheFull)?;
+                    self.overflow.batches[index] = first;
+                    self.overflow.batches.insert(index + 1, second);
+                }
+            }
+        };
+        self.overflow.loaded = index;
+        self.metrics.draw_batches = self.overflow.batches.len();
+        let verts = match action {
+            BrushAction::Draw(verts) => verts,
+            // the batch was uploaded last
+            BrushAction::ReDraw => return Ok(true),
+        };
+        let verts = self.prepare_vertices(verts);
+        if index >= self.overflow.damaged {
+            let stretch = self.stretch();
+            let verts = verts.iter().map(|(_, v, _)| v);
+            match index {
+                0 => self.damage.update(verts, stretch),
+                _ => self.damage.add_batch(verts, stretch),
+            }
+            self.overflow.damaged = index + 1;
+        }
+        self.upload_vertices(facade, verts)
+    }
+
+    /// Draws the selected layers of the processed queue, batch by batch if it didn't fit
+    /// into the cache texture at once.
+    pub(crate) fn draw_batches<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        select: &dyn Fn(&str) -> bool,
+        transform: [[f32; 4]; 4],
+        tint: [f32; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<(), DrawError> {
+        if !self.overflow.is_batched() {
+            return self.draw_processed(select, transform, tint, facade, surface);
+        }
+        // batches may be split while loading them
+        let mut index = 0;
+        while index < self.overflow.batches.len() {
+            if self.overflow.loaded != index && !self.load_batch(facade, index)? {
+                return Ok(());
+            }
+            self.draw_processed(select, transform, tint, facade, surface)?;
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::{Glyph, GlyphId};
+
+    fn section(glyphs: usize) -> QueuedGlyphs<Extra> {
+        let glyphs = (0..glyphs)
+            .map(|i| SectionGlyph {
+                section_index: 0,
+                byte_index: i,
+                glyph: Glyph {
+                    id: GlyphId(i as u16),
+                    scale: 16.0.into(),
+                    position: point(i as f32 * 10.0, 0.0),
+                },
+                font_id: FontId(0),
+            })
+            .collect();
+        QueuedGlyphs {
+            glyphs,
+            extra: vec![GlyphExtra::from(Extra::default())],
+            bounds: Rect::default(),
+        }
+    }
+
+    fn lens(batch: &GlyphBatch<Extra>) -> Vec<usize> {
+        batch.iter().map(|q| q.glyphs.len()).collect()
+    }
+
+    #[test]
+    fn split_keeps_whole_sections() {
+        let (first, second) = split(vec![section(2), section(2), section(4)]).unwrap();
+        assert_eq!(lens(&first), [2, 2]);
+        assert_eq!(lens(&second), [4]);
+    }
+
+    #[test]
+    fn split_divides_a_section() {
+        let (first, second) = split(vec![section(1), section(6)]).unwrap();
+        assert_eq!(lens(&first), [1, 2]);
+        assert_eq!(lens(&second), [4]);
+        // both halves keep the extras the glyphs index into
+        assert_eq!(first[1].extra.len(), 1);
+        assert_eq!(second[0].extra.len(), 1);
+        assert_eq!(second[0].glyphs[0].byte_index, 2);
+    }
+
+    #[test]
+    fn split_single_glyph() {
+        assert!(split(vec![section(1)]).is_none());
+        assert!(split::<Extra>(vec![]).is_none());
+    }
+}
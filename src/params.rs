@@ -0,0 +1,62 @@
+use super::*;
+
+use glium::DrawParameters;
+use std::collections::HashMap;
+
+/// Identifies draw parameters added with
+/// [`GlyphBrush::add_draw_params`](struct.GlyphBrush.html#method.add_draw_params).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DrawParamsId(usize);
+
+/// Draw parameters of sections drawn differently from the rest, owned by a brush.
+#[derive(Default)]
+pub(crate) struct DrawParamsSets {
+    params: HashMap<DrawParamsId, DrawParameters<'static>>,
+    next_id: usize,
+}
+
+impl DrawParamsSets {
+    pub(crate) fn get(&self, id: Option<DrawParamsId>) -> Option<&DrawParameters<'static>> {
+        id.and_then(|id| self.params.get(&id))
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Adds draw parameters to draw sections with instead of the brush's, e.g. additive
+    /// blending for glowing text, see [`queue_with_params`](#method.queue_with_params).
+    pub fn add_draw_params(&mut self, params: DrawParameters<'static>) -> DrawParamsId {
+        let id = DrawParamsId(self.draw_params.next_id);
+        self.draw_params.next_id += 1;
+        self.draw_params.params.insert(id, params);
+        id
+    }
+
+    /// Replaces added draw parameters, e.g. to move the scissor of a scrolling panel.
+    /// Takes effect on the next draw without regenerating any vertices.
+    pub fn set_draw_params(&mut self, id: DrawParamsId, params: DrawParameters<'static>) {
+        if let Some(p) = self.draw_params.params.get_mut(&id) {
+            *p = params;
+        }
+    }
+
+    /// Removes added draw parameters, returning them. Sections queued with them are
+    /// drawn with the brush's draw parameters.
+    pub fn remove_draw_params(&mut self, id: DrawParamsId) -> Option<DrawParameters<'static>> {
+        self.draw_params.params.remove(&id)
+    }
+
+    /// Queues a section like [`queue`](#method.queue), drawn with the given draw
+    /// parameters instead of the brush's.
+    ///
+    /// Sections are batched by draw parameters, so sections with different ones aren't
+    /// necessarily drawn in the order they were queued. Ignored with
+    /// [order-independent transparency](struct.GlyphBrushBuilder.html#method.order_independent_transparency)
+    /// and [custom vertices](struct.GlyphBrushBuilder.html#method.custom_vertex). Color glyphs
+    /// are drawn with the brush's draw parameters.
+    pub fn queue_with_params<'a, S>(&mut self, section: S, params: DrawParamsId)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        self.queue_styled(section, &SectionStyle::default().draw_params(params))
+    }
+}
@@ -0,0 +1,99 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{Glyph, GlyphId, PxScale, Rect};
+use glyph_brush::SectionGlyph;
+
+/// A single glyph drawn on its own, e.g. as part of a particle effect.
+///
+/// Particles are rasterized into and drawn from the same glyph cache as queued
+/// sections, so animating many of them is as cheap as drawing static text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlyphParticle {
+    pub glyph_id: GlyphId,
+    pub font_id: FontId,
+    pub scale: PxScale,
+    /// Position of the glyph origin on the baseline, in pixels.
+    pub position: (f32, f32),
+    pub color: [f32; 4],
+    pub z: f32,
+    /// Transform of the glyph relative to its `position`, e.g. to spin it around
+    /// its origin.
+    pub transform: GlyphTransform,
+}
+
+impl<F: Font + Sync, H: BuildHasher> GlyphBrush<F, H> {
+    /// Lays out a section and returns its glyphs as particles at their laid out
+    /// positions, e.g. to let a headline explode into its letters.
+    pub fn section_particles<'a, S>(&mut self, section: S) -> Vec<GlyphParticle>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        self.glyphs(section.as_ref())
+            .map(|g| {
+                let extra = section.text[g.section_index].extra;
+                GlyphParticle {
+                    glyph_id: g.glyph.id,
+                    font_id: g.font_id,
+                    scale: g.glyph.scale,
+                    position: (g.glyph.position.x, g.glyph.position.y),
+                    color: extra.color,
+                    z: extra.z,
+                    transform: GlyphTransform::IDENTITY,
+                }
+            })
+            .collect()
+    }
+
+    /// Queues glyph particles to be drawn by the next call of
+    /// [`draw_queued`](#method.draw_queued).
+    ///
+    /// Particles count against the [glyph cap](#method.set_glyph_cap).
+    pub fn queue_particles(&mut self, particles: &[GlyphParticle]) {
+        let particles = &particles[..self.cap_count(particles.len())];
+        let stretch = if self.subpixel.is_some() { 3.0 } else { 1.0 };
+        let (glyphs, extra) = particles
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let glyph = SectionGlyph {
+                    section_index: i,
+                    byte_index: 0,
+                    glyph: Glyph {
+                        id: p.glyph_id,
+                        scale: PxScale {
+                            x: p.scale.x * stretch,
+                            y: p.scale.y,
+                        },
+                        position: point(p.position.0 * stretch, p.position.1),
+                    },
+                    font_id: p.font_id,
+                };
+                let transform = p.transform.around(p.position).stretched(stretch);
+                let extra = GlyphExtra {
+                    extra: Extra {
+                        color: p.color,
+                        z: p.z,
+                    },
+                    transform,
+                    fill: None,
+                    procedural: None,
+                    hollow: 0.0,
+                    depth: None,
+                    params: None,
+                    layer: 0,
+                    nearest: self.pixel_fonts.contains(&p.font_id),
+                    pinned: false,
+                    retained: None,
+                };
+                (glyph, extra)
+            })
+            .unzip();
+        let bounds = Rect {
+            min: point(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            max: point(f32::INFINITY, f32::INFINITY),
+        };
+        self.queue_glyphs(glyphs, extra, bounds);
+        self.layers.queued();
+    }
+}
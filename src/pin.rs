@@ -0,0 +1,133 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{Glyph, PxScale, Rect};
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Pins the glyphs of `chars` in `font_id` at `scale` in the cache texture, e.g. HUD
+    /// digits that must always draw without rasterizing in the same frame.
+    ///
+    /// Pinned glyphs are cached as if they were drawn in every frame, so the draw cache
+    /// never evicts them to make space for other glyphs, it grows the texture instead.
+    /// Glyphs are pinned at whole pixel positions, text at fractional positions may still
+    /// rasterize its glyphs at the other subpixel offsets, pin the section with
+    /// [`pin_section`](#method.pin_section) to pin exactly the glyphs it draws.
+    pub fn pin_glyphs(&mut self, font_id: FontId, scale: impl Into<PxScale>, chars: &str) {
+        let scale = self.stretched_scale(scale.into());
+        let font = match self.glyph_brush.fonts().get(font_id.0) {
+            Some(font) => font,
+            None => return,
+        };
+        let glyphs: Vec<_> = chars
+            .chars()
+            .map(|c| Glyph {
+                id: font.glyph_id(c),
+                scale,
+                position: point(0.0, 0.0),
+            })
+            .map(|glyph| (font_id, glyph))
+            .collect();
+        self.pin(glyphs);
+    }
+
+    /// Pins the glyphs of `section` at its position in the cache texture, see
+    /// [`pin_glyphs`](#method.pin_glyphs).
+    pub fn pin_section<'a, S>(&mut self, section: S)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        let section = section.into();
+        let stretched = self
+            .subpixel
+            .as_ref()
+            .map(|_| Subpixel::stretch_section(&section));
+        let section = match &stretched {
+            Some(stretched) => Cow::Owned(stretched.to_borrowed()),
+            None => Cow::Borrowed(section.as_ref()),
+        };
+        let glyphs: Vec<_> = self
+            .glyphs(section.as_ref())
+            .map(|g| (g.font_id, g.glyph.clone()))
+            .collect();
+        self.pin(glyphs);
+    }
+
+    /// Unpins the glyphs of `chars` in `font_id` at `scale`, at any position.
+    pub fn unpin_glyphs(&mut self, font_id: FontId, scale: impl Into<PxScale>, chars: &str) {
+        let scale = self.stretched_scale(scale.into());
+        let font = match self.glyph_brush.fonts().get(font_id.0) {
+            Some(font) => font,
+            None => return,
+        };
+        let ids: Vec<_> = chars.chars().map(|c| font.glyph_id(c)).collect();
+        self.pinned_glyphs.retain(|g| {
+            g.font_id != font_id || g.glyph.scale != scale || !ids.contains(&g.glyph.id)
+        });
+    }
+
+    /// Unpins all glyphs. They stay in the cache until evicted like any other glyph.
+    pub fn unpin_all(&mut self) {
+        self.pinned_glyphs.clear();
+    }
+
+    /// Returns the number of pinned glyphs.
+    pub fn pinned_glyphs(&self) -> usize {
+        self.pinned_glyphs.len()
+    }
+
+    fn stretched_scale(&self, scale: PxScale) -> PxScale {
+        match self.subpixel {
+            Some(_) => PxScale {
+                x: scale.x * 3.0,
+                y: scale.y,
+            },
+            None => scale,
+        }
+    }
+
+    fn pin(&mut self, glyphs: Vec<(FontId, Glyph)>) {
+        for (font_id, glyph) in glyphs {
+            let pinned = self
+                .pinned_glyphs
+                .iter()
+                .any(|g| g.font_id == font_id && g.glyph == glyph);
+            if !pinned {
+                self.pinned_glyphs.push(SectionGlyph {
+                    section_index: 0,
+                    byte_index: 0,
+                    glyph,
+                    font_id,
+                });
+            }
+        }
+    }
+
+    /// Queues the pinned glyphs to be cached by the next processing of the queue, their
+    /// vertices are dropped.
+    pub(crate) fn queue_pinned(&mut self) {
+        if self.pinned_glyphs.is_empty() {
+            return;
+        }
+        let glyphs = self
+            .pinned_glyphs
+            .iter()
+            .enumerate()
+            .map(|(i, g)| SectionGlyph {
+                section_index: i,
+                ..g.clone()
+            })
+            .collect();
+        let extra = GlyphExtra {
+            pinned: true,
+            ..GlyphExtra::from(X::default())
+        };
+        let bounds = Rect {
+            min: point(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            max: point(f32::INFINITY, f32::INFINITY),
+        };
+        self.glyph_brush.queue_pre_positioned(
+            glyphs,
+            vec![extra; self.pinned_glyphs.len()],
+            bounds,
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{Glyph, PxScale, Rect};
+use glyph_brush::SectionGlyph;
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Rasterizes the glyphs of `chars` in `font_id` at `scale` and uploads them into the
+    /// cache texture ahead of time, e.g. digits and ASCII at common sizes during a loading
+    /// screen, so that text using them later doesn't hitch on the first draw.
+    ///
+    /// Call it before queueing the text of a frame, sections queued before are dropped.
+    /// Glyphs are cached at whole pixel positions, text at fractional positions may still
+    /// rasterize its glyphs at the other subpixel offsets. Precached glyphs stay in the
+    /// cache until it runs out of space, then they are evicted like any glyph not drawn
+    /// in the current frame. Color glyphs aren't precached.
+    pub fn precache<C: Facade>(
+        &mut self,
+        facade: &C,
+        font_id: FontId,
+        scale: impl Into<PxScale>,
+        chars: &str,
+    ) -> Result<(), DrawError> {
+        let scale = scale.into();
+        let font = match self.glyph_brush.fonts().get(font_id.0) {
+            Some(font) => font,
+            None => return Ok(()),
+        };
+        // match the horizontal stretch of queued sections in subpixel mode
+        let scale = match self.subpixel {
+            Some(_) => PxScale {
+                x: scale.x * 3.0,
+                y: scale.y,
+            },
+            None => scale,
+        };
+        let mut ids: Vec<_> = chars.chars().map(|c| font.glyph_id(c)).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let (glyphs, extra) = ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let glyph = SectionGlyph {
+                    section_index: i,
+                    byte_index: 0,
+                    glyph: Glyph {
+                        id,
+                        scale,
+                        position: point(0.0, 0.0),
+                    },
+                    font_id,
+                };
+                (glyph, GlyphExtra::from(X::default()))
+            })
+            .unzip();
+        let bounds = Rect {
+            min: point(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            max: point(f32::INFINITY, f32::INFINITY),
+        };
+        // Pre-positioned glyphs differ from the ones of any frame, so the next draw
+        // regenerates its vertices instead of redrawing the previous ones.
+        self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds);
+        // keep the pinned glyphs cached
+        self.queue_pinned();
+        self.overflow.forget_queued();
+
+        loop {
+            let brush_action = {
+                let tex = &self.texture;
+                let raster_mode = self.raster_mode;
+                let uploader = &mut self.texture_uploader;
+                let regions = &mut self.cache_regions;
+                let region = self.cache_region;
+                self.glyph_brush.process_queued(
+                    |rect, tex_data| {
+                        let rect = in_region(rect, region);
+                        upload_glyph(tex, raster_mode, uploader, rect, tex_data);
+                        regions.track(rect);
+                    },
+                    |v| to_filled_vertex(v, false),
+                )
+            };
+            match brush_action {
+                Ok(_) => return Ok(()),
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    let size = self.budgeted_texture_size(suggested);
+                    if let Err(e) = self.grow_texture(facade, size) {
+                        self.discard_queued();
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, HorizontalAlign, Layout, SectionGeometry, SectionGlyph, ToSectionText,
+    VerticalAlign,
+};
+
+/// Layout for preformatted text like code, laid out left to right and top to bottom
+/// from the section's `screen_position`.
+///
+/// * whitespace is kept as is, runs of spaces aren't collapsed and whitespace at
+///   line ends isn't trimmed,
+/// * tabs advance to the next tab stop, every `tab_width` spaces of the tab's font
+///   from the start of the line,
+/// * lines only break at explicit line breaks, unless wrapping at the section's width
+///   bound is enabled.
+///
+/// Glyphs outside of the section's bounds are clipped, use unbounded sections to show
+/// long lines in full.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::PreformattedLayout;
+/// # use glium_glyph::glyph_brush::{Section, Text};
+/// # fn main() {
+/// let code = Section::default().add_text(Text::new("fn main() {\n\tprintln!(\"hi\");\n}"));
+/// let layout = PreformattedLayout::new().tab_width(8);
+/// # let _ = (code, layout);
+/// // glyph_brush.queue_custom_layout(code, &layout);
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PreformattedLayout {
+    line_breaker: BuiltInLineBreaker,
+    tab_width: u32,
+    wrap: bool,
+}
+
+impl Default for PreformattedLayout {
+    fn default() -> Self {
+        PreformattedLayout::new()
+    }
+}
+
+impl PreformattedLayout {
+    pub fn new() -> Self {
+        PreformattedLayout {
+            line_breaker: BuiltInLineBreaker::default(),
+            tab_width: 4,
+            wrap: false,
+        }
+    }
+
+    /// Sets the distance between tab stops in spaces.
+    ///
+    /// Defaults to `4`.
+    pub fn tab_width(mut self, tab_width: u32) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Sets whether lines wrap at the section's width bound. Tab stops are applied
+    /// after wrapping, so tabs can push the end of a wrapped line past the bound.
+    ///
+    /// Defaults to `false`.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Sets where lines may wrap, only used if wrapping is enabled.
+    pub fn line_breaker(mut self, line_breaker: BuiltInLineBreaker) -> Self {
+        self.line_breaker = line_breaker;
+        self
+    }
+
+    fn layout(&self) -> Layout<BuiltInLineBreaker> {
+        Layout::Wrap {
+            line_breaker: self.line_breaker,
+            h_align: HorizontalAlign::Left,
+            v_align: VerticalAlign::Top,
+        }
+    }
+}
+
+impl GlyphPositioner for PreformattedLayout {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        // without a width bound lines only end at explicit line breaks
+        let unbounded;
+        let layout_geometry = match self.wrap {
+            true => geometry,
+            false => {
+                unbounded = SectionGeometry {
+                    bounds: (f32::INFINITY, geometry.bounds.1),
+                    ..*geometry
+                };
+                &unbounded
+            }
+        };
+        let glyphs = self
+            .layout()
+            .calculate_glyphs(fonts, layout_geometry, sections);
+
+        let texts: Vec<&str> = sections.iter().map(|s| s.to_section_text().text).collect();
+        if !texts.iter().any(|text| text.contains('\t')) {
+            return glyphs;
+        }
+        let is_tab = |g: &SectionGlyph| texts[g.section_index][g.byte_index..].starts_with('\t');
+
+        // glyphs come line by line, the glyphs of a line share their baseline
+        let left = geometry.screen_position.0;
+        let mut expanded = Vec::with_capacity(glyphs.len());
+        let mut baseline = f32::NAN;
+        let mut offset = 0.0;
+        for mut g in glyphs {
+            if g.glyph.position.y != baseline {
+                baseline = g.glyph.position.y;
+                offset = 0.0;
+            }
+            g.glyph.position.x += offset;
+            if !is_tab(&g) {
+                expanded.push(g);
+                continue;
+            }
+
+            // tabs aren't drawn, only move the glyphs after them to the next tab stop
+            let font = fonts[g.font_id.0].as_scaled(g.glyph.scale);
+            let stop_width = self.tab_width as f32 * font.h_advance(font.glyph_id(' '));
+            let x = g.glyph.position.x - left;
+            let stop = match stop_width > 0.0 {
+                true => ((x / stop_width).floor() + 1.0) * stop_width,
+                false => x,
+            };
+            offset += stop - x - font.h_advance(g.glyph.id);
+        }
+        expanded
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout().bounds_rect(geometry)
+    }
+}
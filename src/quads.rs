@@ -0,0 +1,341 @@
+use super::*;
+
+use glium::index::IndexBuffer;
+use glium::uniforms::Uniforms;
+
+/// Most glyphs drawn with one draw call by default, the most that expanded quads can
+/// address with 16-bit indices.
+pub(crate) const MAX_GLYPHS_PER_DRAW: usize = (u16::MAX as usize + 1) / 4;
+
+/// Corners of the unit quad in triangle strip order.
+const UNIT_QUAD: [QuadCorner; 4] = [
+    QuadCorner { corner: [0.0, 0.0] },
+    QuadCorner { corner: [1.0, 0.0] },
+    QuadCorner { corner: [0.0, 1.0] },
+    QuadCorner { corner: [1.0, 1.0] },
+];
+
+/// How the GPU buffers holding the glyph quads are stored and updated.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BufferStorage {
+    /// Allocate a new buffer every time the text changes, e.g. for mostly static text.
+    Immutable,
+    /// Reuse a buffer, orphaning its previous contents before writing the new ones
+    /// so that the driver doesn't wait for draws still reading them.
+    #[default]
+    Dynamic,
+    /// Reuse a persistently mapped buffer, cycling through three regions of it so that
+    /// writes don't wait for draws still reading the previous ones, e.g. for debug
+    /// overlays and log consoles changing every frame. Needs OpenGL 4.4 or
+    /// `ARB_buffer_storage`, falls back to a regular buffer otherwise.
+    Persistent,
+}
+
+/// Regions of a persistent buffer written in turn.
+const PERSISTENT_REGIONS: usize = 3;
+
+/// GPU buffers holding glyph quads, reused across frames.
+///
+/// Each glyph is one `GlyphVertex`, drawn instanced over the `QuadCorner`s of a unit
+/// quad triangle strip. Without instancing, glyphs are expanded to four vertices each
+/// instead, paired with as many corners and drawn as a triangle list with six indices
+/// per glyph.
+///
+/// Unless immutable, the buffers are only reallocated when the glyphs exceed their
+/// capacity, otherwise the glyphs are written into them. Large amounts of glyphs are
+/// split into several draw calls, which share the indices of the first one.
+pub(crate) struct QuadBuffer {
+    vertex_buffer: glium::VertexBuffer<GlyphVertex>,
+    /// `None` if drawn instanced or empty
+    index_buffer: Option<IndexBuffer<u16>>,
+    /// the unit quad, or its corners repeated for the glyphs of one draw call
+    corners: glium::VertexBuffer<QuadCorner>,
+    instancing: bool,
+    storage: BufferStorage,
+    /// number of glyphs written to the buffers
+    len: usize,
+    /// index of the first written glyph
+    offset: usize,
+    /// persistent region written next
+    region: usize,
+    max_glyphs_per_draw: usize,
+}
+
+impl QuadBuffer {
+    /// Creates empty buffers, drawn instanced if `allow_instancing` is set and the context
+    /// supports instanced arrays.
+    pub(crate) fn empty<C: Facade>(
+        facade: &C,
+        allow_instancing: bool,
+    ) -> Result<Self, BufferCreationError> {
+        let vertex_buffer = glium::VertexBuffer::empty_dynamic(facade, 0)?;
+        let instancing = allow_instancing && vertex_buffer.per_instance().is_ok();
+        let corners = match instancing {
+            true => glium::VertexBuffer::immutable(facade, &UNIT_QUAD)?,
+            false => glium::VertexBuffer::empty_immutable(facade, 0)?,
+        };
+        Ok(QuadBuffer {
+            vertex_buffer,
+            index_buffer: None,
+            corners,
+            instancing,
+            storage: BufferStorage::default(),
+            len: 0,
+            offset: 0,
+            region: 0,
+            max_glyphs_per_draw: MAX_GLYPHS_PER_DRAW,
+        })
+    }
+
+    /// Limits the glyphs drawn with one draw call, at most
+    /// `MAX_GLYPHS_PER_DRAW`.
+    pub(crate) fn with_max_glyphs_per_draw(mut self, glyphs: usize) -> Self {
+        self.max_glyphs_per_draw = glyphs.clamp(1, MAX_GLYPHS_PER_DRAW);
+        self
+    }
+
+    pub(crate) fn with_storage(mut self, storage: BufferStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Frees the buffers.
+    pub(crate) fn clear<C: Facade>(&mut self, facade: &C) -> Result<(), BufferCreationError> {
+        self.index_buffer = None;
+        self.vertex_buffer = glium::VertexBuffer::empty_dynamic(facade, 0)?;
+        self.len = 0;
+        Ok(())
+    }
+
+    fn vertices_per_glyph(&self) -> usize {
+        if self.instancing {
+            1
+        } else {
+            4
+        }
+    }
+
+    fn regions(&self) -> usize {
+        match self.storage {
+            BufferStorage::Persistent => PERSISTENT_REGIONS,
+            _ => 1,
+        }
+    }
+
+    /// Number of glyphs the buffers hold without reallocating.
+    fn capacity(&self) -> usize {
+        self.vertex_buffer.len() / (self.vertices_per_glyph() * self.regions())
+    }
+
+    /// Reallocates the vertex buffer to hold `capacity` glyphs.
+    fn reallocate<C: Facade>(&mut self, facade: &C, capacity: usize) -> Result<(), DrawError> {
+        let len = capacity * self.vertices_per_glyph() * self.regions();
+        self.vertex_buffer = match self.storage {
+            BufferStorage::Persistent => glium::VertexBuffer::empty_persistent(facade, len)?,
+            _ => glium::VertexBuffer::empty_dynamic(facade, len)?,
+        };
+        self.region = 0;
+        Ok(())
+    }
+
+    /// Makes sure the index and corner buffers cover the quads of one draw call of
+    /// `glyphs` expanded glyphs.
+    fn reserve_indices<C: Facade>(&mut self, facade: &C, glyphs: usize) -> Result<(), DrawError> {
+        let glyphs = glyphs.min(self.max_glyphs_per_draw);
+        if self.instancing || self.index_buffer.as_ref().map_or(0, |b| b.len() / 6) >= glyphs {
+            return Ok(());
+        }
+        // the indices are the same every frame and for every draw call
+        let indices: Vec<u16> = (0..glyphs as u16)
+            .flat_map(|glyph| [0, 1, 2, 2, 1, 3].map(|corner| 4 * glyph + corner))
+            .collect();
+        let corners: Vec<QuadCorner> = UNIT_QUAD.iter().cycle().take(4 * glyphs).copied().collect();
+        self.index_buffer = None;
+        self.corners = glium::VertexBuffer::immutable(facade, &corners)?;
+        self.index_buffer = Some(IndexBuffer::immutable(
+            facade,
+            PrimitiveType::TrianglesList,
+            &indices,
+        )?);
+        Ok(())
+    }
+
+    /// Writes glyphs that fit into the buffers, or replaces an immutable buffer.
+    fn write<C: Facade>(&mut self, facade: &C, verts: &[GlyphVertex]) -> Result<(), DrawError> {
+        self.len = 0;
+        self.reserve_indices(facade, verts.len())?;
+        let data: Cow<[GlyphVertex]> = if self.instancing {
+            Cow::Borrowed(verts)
+        } else {
+            Cow::Owned(verts.iter().flat_map(|&v| [v; 4]).collect())
+        };
+        self.offset = 0;
+        match self.storage {
+            BufferStorage::Immutable => {
+                self.vertex_buffer = glium::VertexBuffer::immutable(facade, &data)?;
+            }
+            _ if data.is_empty() => {}
+            BufferStorage::Dynamic => {
+                // orphan the previous glyphs, which may still be read by the GPU
+                self.vertex_buffer.invalidate();
+                self.vertex_buffer.slice(..data.len()).unwrap().write(&data);
+            }
+            BufferStorage::Persistent => {
+                let region_len = self.vertex_buffer.len() / PERSISTENT_REGIONS;
+                let start = self.region * region_len;
+                self.vertex_buffer
+                    .slice(start..start + data.len())
+                    .unwrap()
+                    .write(&data);
+                self.offset = start / self.vertices_per_glyph();
+                self.region = (self.region + 1) % PERSISTENT_REGIONS;
+            }
+        }
+        self.len = verts.len();
+        Ok(())
+    }
+
+    /// Replaces the glyphs in the buffers, growing them if needed.
+    ///
+    /// On error, the buffers are left empty.
+    pub(crate) fn upload<C: Facade>(
+        &mut self,
+        facade: &C,
+        verts: &[GlyphVertex],
+    ) -> Result<(), DrawError> {
+        self.len = 0;
+        if self.storage != BufferStorage::Immutable && verts.len() > self.capacity() {
+            let capacity = verts.len().max(2 * self.capacity());
+            self.reallocate(facade, capacity)?;
+        }
+        self.write(facade, verts)
+    }
+
+    /// Uploads as many of `verts` as possible, halving the amount until it fits
+    /// or the buffers can be grown to hold it. Returns whether any were uploaded.
+    pub(crate) fn upload_partial<C: Facade>(&mut self, facade: &C, verts: &[GlyphVertex]) -> bool {
+        let mut len = verts.len() / 2;
+        while len > 0 {
+            let fits = self.storage == BufferStorage::Immutable
+                || len <= self.capacity()
+                || self.reallocate(facade, len).is_ok();
+            if fits && self.write(facade, &verts[..len]).is_ok() {
+                return true;
+            }
+            len /= 2;
+        }
+        false
+    }
+
+    /// Overwrites the written glyphs starting at index `first`, skipping those past the
+    /// end, e.g. after a partial upload.
+    pub(crate) fn write_glyphs(&mut self, first: usize, verts: &[GlyphVertex]) {
+        let verts = &verts[..verts.len().min(self.len.saturating_sub(first))];
+        if verts.is_empty() {
+            return;
+        }
+        let per_glyph = self.vertices_per_glyph();
+        let data: Cow<[GlyphVertex]> = if self.instancing {
+            Cow::Borrowed(verts)
+        } else {
+            Cow::Owned(verts.iter().flat_map(|&v| [v; 4]).collect())
+        };
+        let start = (self.offset + first) * per_glyph;
+        self.vertex_buffer
+            .slice(start..start + data.len())
+            .unwrap()
+            .write(&data);
+    }
+
+    /// Whether the glyphs are drawn instanced instead of as indexed triangles.
+    pub(crate) fn is_instanced(&self) -> bool {
+        self.instancing
+    }
+
+    /// Number of glyphs in the buffers.
+    pub(crate) fn glyphs(&self) -> usize {
+        self.len
+    }
+
+    /// Size of the buffers in bytes.
+    pub(crate) fn size(&self) -> usize {
+        self.vertex_buffer.get_size()
+            + self.corners.get_size()
+            + self.index_buffer.as_ref().map_or(0, |b| b.get_size())
+    }
+
+    /// Quads of the glyphs in `range`, clamped to the buffer.
+    pub(crate) fn quads(&self, range: Range<usize>) -> Quads<'_> {
+        let end = range.end.min(self.glyphs());
+        Quads {
+            buffer: self,
+            glyphs: (range.start.min(end), end),
+        }
+    }
+
+    /// Quads of all glyphs in the buffer.
+    pub(crate) fn all(&self) -> Quads<'_> {
+        self.quads(0..self.glyphs())
+    }
+}
+
+/// A range of glyph quads to draw.
+#[derive(Copy, Clone)]
+pub(crate) struct Quads<'a> {
+    buffer: &'a QuadBuffer,
+    glyphs: (usize, usize),
+}
+
+impl<'a> Quads<'a> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.glyphs.0 == self.glyphs.1
+    }
+
+    pub(crate) fn draw<S: Surface, U: Uniforms>(
+        self,
+        surface: &mut S,
+        program: &Program,
+        uniforms: &U,
+        params: &glium::DrawParameters,
+    ) -> Result<(), glium::DrawError> {
+        let (start, end) = self.glyphs;
+        let QuadBuffer {
+            vertex_buffer,
+            corners,
+            offset,
+            max_glyphs_per_draw,
+            ..
+        } = self.buffer;
+        for first in (start + offset..end + offset).step_by(*max_glyphs_per_draw) {
+            let last = (end + offset).min(first + max_glyphs_per_draw);
+            match &self.buffer.index_buffer {
+                None => surface.draw(
+                    (
+                        corners,
+                        vertex_buffer
+                            .slice(first..last)
+                            .unwrap()
+                            .per_instance()
+                            .unwrap(),
+                    ),
+                    glium::index::NoIndices(PrimitiveType::TriangleStrip),
+                    program,
+                    uniforms,
+                    params,
+                )?,
+                // the vertex slices start at the first glyph, so the indices start at 0
+                Some(index_buffer) => surface.draw(
+                    (
+                        corners.slice(..4 * (last - first)).unwrap(),
+                        vertex_buffer.slice(4 * first..4 * last).unwrap(),
+                    ),
+                    index_buffer.slice(..6 * (last - first)).unwrap(),
+                    program,
+                    uniforms,
+                    params,
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
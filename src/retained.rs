@@ -0,0 +1,192 @@
+use super::*;
+
+/// A section queued for every draw.
+struct RetainedSection<X> {
+    name: String,
+    /// tags the glyphs of the section to find their vertices
+    id: u32,
+    section: OwnedSection<X>,
+    /// color replacing the colors of the glyphs, see `update_color`
+    color: Option<[f32; 4]>,
+}
+
+/// Retained sections of a brush and where their glyphs were uploaded.
+pub(crate) struct Retained<X> {
+    sections: Vec<RetainedSection<X>>,
+    next_id: u32,
+    /// retained section id, index in the quad buffer and vertex of the uploaded
+    /// glyphs of retained sections, sorted by id
+    glyphs: Vec<(u32, usize, GlyphVertex)>,
+}
+
+impl<X> Default for Retained<X> {
+    fn default() -> Self {
+        Retained {
+            sections: Vec::new(),
+            next_id: 0,
+            glyphs: Vec::new(),
+        }
+    }
+}
+
+impl<X> Retained<X> {
+    fn get_mut(&mut self, name: &str) -> Option<&mut RetainedSection<X>> {
+        self.sections.iter_mut().find(|r| r.name == name)
+    }
+
+    /// Applies the colors of retained sections to their glyphs in sorted vertices about
+    /// to be uploaded, and records where they end up.
+    pub(crate) fn uploading(&mut self, verts: &mut [(Batch, GlyphVertex)]) {
+        self.glyphs.clear();
+        for (i, (batch, v)) in verts.iter_mut().enumerate() {
+            let id = match batch.retained {
+                Some(id) => id,
+                None => continue,
+            };
+            if let Some(color) = self
+                .sections
+                .iter()
+                .find(|r| r.id == id)
+                .and_then(|r| r.color)
+            {
+                v.color = color;
+            }
+            self.glyphs.push((id, i, *v));
+        }
+        self.glyphs.sort_by_key(|&(id, _, _)| id);
+    }
+
+    /// Forgets where the glyphs were uploaded, e.g. to custom vertices.
+    pub(crate) fn not_uploaded(&mut self) {
+        self.glyphs.clear();
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Keeps a section queued for every draw under `name`, e.g. a status line, instead
+    /// of queueing it again each frame. Replaces a section of the same name.
+    ///
+    /// Every draw queues the retained sections after the sections queued for the frame,
+    /// in the order they were first inserted. Unchanged ones benefit from
+    /// [caching](#caching-behaviour) like any section queued again.
+    ///
+    /// ```no_run
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::GlyphBrush;
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # use glium_glyph::glyph_brush::{Section, Text};
+    /// # fn f(brush: &mut GlyphBrush<FontArc>, fps: f32) {
+    /// brush.insert_section("fps", Section::default().add_text(Text::new("FPS: -")));
+    /// // every frame
+    /// brush.update_text("fps", &format!("FPS: {:.0}", fps));
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn insert_section<'a, S>(&mut self, name: &str, section: S)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        let section: Cow<Section<X>> = section.into();
+        let section = section.as_ref().to_owned();
+        match self.retained.get_mut(name) {
+            Some(retained) => {
+                retained.section = section;
+                retained.color = None;
+            }
+            None => {
+                let id = self.retained.next_id;
+                self.retained.next_id += 1;
+                self.retained.sections.push(RetainedSection {
+                    name: name.to_owned(),
+                    id,
+                    section,
+                    color: None,
+                });
+            }
+        }
+    }
+
+    /// Returns a retained section to change it in place, see
+    /// [`insert_section`](#method.insert_section).
+    pub fn retained_section_mut(&mut self, name: &str) -> Option<&mut OwnedSection<X>> {
+        self.retained.get_mut(name).map(|r| &mut r.section)
+    }
+
+    /// Replaces the text of a retained section's first text, keeping its scale, font
+    /// and data. Returns `false` if there's no such section or it has no text.
+    pub fn update_text(&mut self, name: &str, text: &str) -> bool {
+        match self
+            .retained_section_mut(name)
+            .and_then(|section| section.text.first_mut())
+        {
+            Some(owned) => {
+                if owned.text != text {
+                    owned.text.clear();
+                    owned.text.push_str(text);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draws all glyphs of a retained section in `color`, e.g. to highlight a hovered
+    /// label, until it's replaced by [`insert_section`](#method.insert_section). Returns
+    /// `false` if there's no such section.
+    ///
+    /// Unlike changing the section's text colors, the section isn't laid out again and
+    /// only the colors of its drawn glyphs are written to the vertex buffer, so the next
+    /// draw redraws the previous vertices if nothing else changed. Not applied to
+    /// [custom vertices](struct.GlyphBrushBuilder.html#method.custom_vertex), whose
+    /// colors come from the glyph data.
+    pub fn update_color(&mut self, name: &str, color: [f32; 4]) -> bool {
+        let id = match self.retained.get_mut(name) {
+            Some(retained) => {
+                retained.color = Some(color);
+                retained.id
+            }
+            None => return false,
+        };
+        let start = self.retained.glyphs.partition_point(|&(i, _, _)| i < id);
+        let end = self.retained.glyphs.partition_point(|&(i, _, _)| i <= id);
+        let glyphs = &mut self.retained.glyphs[start..end];
+        for (_, _, v) in glyphs.iter_mut() {
+            v.color = color;
+        }
+        let stretch = match self.subpixel {
+            Some(_) => 3.0,
+            None => 1.0,
+        };
+        self.damage
+            .recolored(glyphs.iter().map(|(_, _, v)| v), stretch);
+        // write runs of consecutive glyphs at once
+        let mut run: Vec<GlyphVertex> = Vec::new();
+        let mut first = 0;
+        for &(_, index, v) in glyphs.iter() {
+            if first + run.len() != index {
+                self.quads.write_glyphs(first, &run);
+                run.clear();
+                first = index;
+            }
+            run.push(v);
+        }
+        self.quads.write_glyphs(first, &run);
+        true
+    }
+
+    /// Stops queueing a retained section, returning it.
+    pub fn remove_section(&mut self, name: &str) -> Option<OwnedSection<X>> {
+        let i = self.retained.sections.iter().position(|r| r.name == name)?;
+        Some(self.retained.sections.remove(i).section)
+    }
+
+    /// Queues the retained sections for the next processing of the queue.
+    pub(crate) fn queue_retained(&mut self) {
+        let retained = std::mem::take(&mut self.retained.sections);
+        for r in &retained {
+            let style = SectionStyle::default().retained(r.id);
+            self.queue_styled(r.section.to_borrowed(), &style);
+        }
+        self.retained.sections = retained;
+    }
+}
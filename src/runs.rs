@@ -0,0 +1,92 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{PxScale, Rect, ScaleFont};
+use glyph_brush::SectionGlyph;
+
+/// A run of laid out glyphs from the same text of a section on the same line, from
+/// [`GlyphBrush::text_runs`](struct.GlyphBrush.html#method.text_runs).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    /// Index of the text in the section's `text`.
+    pub text_index: usize,
+    /// Byte range of the run in the text.
+    pub byte_range: Range<usize>,
+    pub font_id: FontId,
+    pub scale: PxScale,
+    pub color: [f32; 4],
+    pub z: f32,
+    /// Baseline of the run's line, in pixels.
+    pub baseline: f32,
+    /// Box around the run, from its first glyph origin to its last glyph's advance and
+    /// from the ascent to the descent of its font, in pixels.
+    pub bounds: Rect,
+}
+
+impl<F: Font, H: BuildHasher> GlyphBrush<F, H> {
+    /// Lays out `section` and returns its glyphs grouped into runs with their resolved
+    /// style, e.g. to draw underlines, build hit zones or describe the text to
+    /// accessibility tools.
+    ///
+    /// A run ends at the end of a text or line. Runs are ordered like the laid out glyphs.
+    pub fn text_runs<'a, S>(&mut self, section: S) -> Vec<TextRun>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+        self.text_runs_custom_layout(section, &layout)
+    }
+
+    /// Returns the runs of `section` laid out with `custom_layout`, see
+    /// [`text_runs`](#method.text_runs).
+    pub fn text_runs_custom_layout<'a, S, L>(
+        &mut self,
+        section: S,
+        custom_layout: &L,
+    ) -> Vec<TextRun>
+    where
+        L: GlyphPositioner + Hash,
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let glyphs: Vec<SectionGlyph> = self
+            .glyphs_custom_layout(section.as_ref(), custom_layout)
+            .cloned()
+            .collect();
+        let fonts = self.glyph_brush.fonts();
+
+        let mut runs: Vec<TextRun> = Vec::new();
+        for g in glyphs {
+            let text = &section.text[g.section_index];
+            let font = fonts[g.font_id.0].as_scaled(g.glyph.scale);
+            let (x, baseline) = (g.glyph.position.x, g.glyph.position.y);
+            let right = x + font.h_advance(g.glyph.id);
+            let end = g.byte_index
+                + text.text[g.byte_index..]
+                    .chars()
+                    .next()
+                    .map_or(0, char::len_utf8);
+            match runs.last_mut() {
+                Some(run) if run.text_index == g.section_index && run.baseline == baseline => {
+                    run.byte_range.end = end;
+                    run.bounds.min.x = run.bounds.min.x.min(x);
+                    run.bounds.max.x = run.bounds.max.x.max(right);
+                }
+                _ => runs.push(TextRun {
+                    text_index: g.section_index,
+                    byte_range: g.byte_index..end,
+                    font_id: g.font_id,
+                    scale: g.glyph.scale,
+                    color: text.extra.color,
+                    z: text.extra.z,
+                    baseline,
+                    bounds: Rect {
+                        min: point(x, baseline - font.ascent()),
+                        max: point(right, baseline - font.descent()),
+                    },
+                }),
+            }
+        }
+        runs
+    }
+}
@@ -0,0 +1,101 @@
+/// How glyphs are stored in the cache texture and turned into pixels.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum RasterMode {
+    /// Glyph coverage is stored as an alpha mask, which is blended directly.
+    ///
+    /// Gives the best results when text is drawn at the size it was laid out with.
+    #[default]
+    AlphaMask,
+    /// Glyphs are stored as signed distance fields, which stay sharp when text
+    /// is magnified, e.g. by a zooming camera transform.
+    ///
+    /// `spread` is the distance in texels covered by the distance field on either
+    /// side of the glyph outline. Distances are derived from the rasterized coverage,
+    /// so outwards they're limited by the one texel padding of the cache texture.
+    Sdf { spread: f32 },
+}
+
+/// Converts a coverage bitmap into a signed distance field of the same size.
+///
+/// Each texel's distance to the glyph outline is estimated from the closest texel
+/// on the other side of, or on, the outline, using that texel's coverage to place
+/// the outline within it. Values are mapped so that `0.5` lies on the outline.
+pub(crate) fn coverage_to_sdf(width: u32, height: u32, coverage: &[u8], spread: f32) -> Vec<u8> {
+    let (w, h) = (width as i32, height as i32);
+    let spread = spread.max(1.0);
+    let radius = spread.ceil() as i32;
+    let value = |x: i32, y: i32| -> f32 {
+        if x < 0 || y < 0 || x >= w || y >= h {
+            0.0
+        } else {
+            f32::from(coverage[(y * w + x) as usize]) / 255.0
+        }
+    };
+
+    let mut out = Vec::with_capacity(coverage.len());
+    for y in 0..h {
+        for x in 0..w {
+            let c = value(x, y);
+            let distance = if c > 0.0 && c < 1.0 {
+                c - 0.5
+            } else {
+                let inside = c >= 0.5;
+                let sign = if inside { 1.0 } else { -1.0 };
+                let mut nearest = spread;
+                for qy in y - radius..=y + radius {
+                    for qx in x - radius..=x + radius {
+                        let qc = value(qx, qy);
+                        let edge_or_opposite = (qc > 0.0 && qc < 1.0) || (qc >= 0.5) != inside;
+                        if edge_or_opposite {
+                            let (dx, dy) = ((qx - x) as f32, (qy - y) as f32);
+                            let d = (dx * dx + dy * dy).sqrt() + sign * (qc - 0.5);
+                            nearest = nearest.min(d.max(0.0));
+                        }
+                    }
+                }
+                sign * nearest
+            };
+            let normalized = 0.5 + distance / (2.0 * spread);
+            out.push((normalized.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solid_square() {
+        // a 2x2 covered square in the middle of a 6x6 bitmap
+        let mut coverage = vec![0; 36];
+        for &i in &[14, 15, 20, 21] {
+            coverage[i] = 255;
+        }
+        let sdf = coverage_to_sdf(6, 6, &coverage, 2.0);
+        // inside above the outline, outside below, further outside lower
+        assert!(sdf[14] > 128);
+        assert!(sdf[13] < 128);
+        assert!(sdf[12] < sdf[13]);
+        // out of the spread
+        assert_eq!(sdf[0], 0);
+        // symmetric
+        assert_eq!(sdf[13], sdf[16]);
+        assert_eq!(sdf[8], sdf[26]);
+    }
+
+    #[test]
+    fn partial_coverage_is_the_outline() {
+        let coverage = [0, 128, 255];
+        let sdf = coverage_to_sdf(3, 1, &coverage, 4.0);
+        assert_eq!(sdf[1], 128);
+        assert!(sdf[0] < 128 && sdf[2] > 128);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(coverage_to_sdf(2, 2, &[0; 4], 1.0), [0; 4]);
+        assert!(coverage_to_sdf(0, 0, &[], 1.0).is_empty());
+    }
+}
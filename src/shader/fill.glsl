@@ -0,0 +1,50 @@
+// Fills and hollow glyphs shared by the glyph fragment shaders, inserted in place of
+// their `#include "fill.glsl"` line when the programs are built.
+
+uniform sampler2D font_tex;
+uniform sampler2D fill_tex;
+uniform bool fill_enabled;
+uniform float time;
+
+in vec2 f_tex_pos;
+in vec4 f_color;
+in vec2 f_fill_pos;
+in vec2 f_pixel_pos;
+flat in vec4 f_procedural;
+flat in vec4 f_procedural_color;
+flat in vec4 f_tex_rect;
+flat in float f_hollow;
+
+// coverage of the glyph at a position of the cache texture, defined by every shader
+float coverage_at(vec2 pos);
+
+// applies the texture and procedural fills to the text color
+vec4 fill_color() {
+    vec4 color = fill_enabled ? f_color * texture(fill_tex, f_fill_pos) : f_color;
+    if (f_procedural.x == 1.0) {
+        // rainbow
+        float hue = fract(f_pixel_pos.x / f_procedural.y - time * f_procedural.z);
+        vec3 rainbow = clamp(abs(mod(hue * 6.0 + vec3(0.0, 4.0, 2.0), 6.0) - 3.0) - 1.0, 0.0, 1.0);
+        color.rgb *= rainbow;
+    } else if (f_procedural.x == 2.0) {
+        // shimmer
+        float spacing = f_procedural.z;
+        float d = mod(f_pixel_pos.x - time * f_procedural.w, spacing);
+        float band = 1.0 - smoothstep(0.0, 0.5 * f_procedural.y, min(d, spacing - d));
+        color.rgb = mix(color.rgb, f_procedural_color.rgb, band * f_procedural_color.a);
+    }
+    return color;
+}
+
+// minimum coverage within `f_hollow` pixels, the glyph shrunk by the stroke width
+float inner_coverage(vec2 texel_size) {
+    float inner = 1.0;
+    for (int i = 0; i < 8; i++) {
+        float angle = float(i) * 0.7853982;
+        vec2 pos = f_tex_pos + vec2(cos(angle), sin(angle)) * f_hollow * texel_size;
+        // stay within the glyph, the cache texture holds other glyphs around it
+        bool inside = all(greaterThanEqual(pos, f_tex_rect.xy)) && all(lessThanEqual(pos, f_tex_rect.zw));
+        inner = min(inner, inside ? coverage_at(pos) : 0.0);
+    }
+    return inner;
+}
@@ -1,16 +1,24 @@
 #version 150
 
-uniform sampler2D font_tex;
+#include "fill.glsl"
 
-in vec2 f_tex_pos;
-in vec4 f_color;
+uniform float gamma;
 
 out vec4 Target0;
 
+float coverage_at(vec2 pos) {
+    return textureLod(font_tex, pos, 0.0).r;
+}
+
 void main() {
+    vec4 color = fill_color();
     float alpha = texture(font_tex, f_tex_pos).r;
+    if (f_hollow > 0.0) {
+        alpha *= 1.0 - inner_coverage(1.0 / vec2(textureSize(font_tex, 0)));
+    }
     if (alpha <= 0.0) {
         discard;
     }
-    Target0 = f_color * vec4(1.0, 1.0, 1.0, alpha);
+    // linearize the color for blending in linear space
+    Target0 = vec4(pow(color.rgb, vec3(gamma)), color.a * alpha);
 }
@@ -0,0 +1,15 @@
+#version 150
+
+uniform sampler2D font_tex;
+uniform float gamma;
+
+in vec2 f_tex_pos;
+in vec4 f_color;
+
+out vec4 Target0;
+
+void main() {
+    vec4 texel = texture(font_tex, f_tex_pos);
+    // linearize the color for blending in linear space
+    Target0 = vec4(pow(texel.rgb, vec3(gamma)), texel.a * f_color.a);
+}
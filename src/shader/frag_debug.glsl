@@ -0,0 +1,17 @@
+#version 150
+
+uniform sampler2D font_tex;
+uniform bool outline;
+
+in vec2 f_tex_pos;
+
+out vec4 Target0;
+
+void main() {
+    if (outline) {
+        Target0 = vec4(0.0, 1.0, 0.0, 1.0);
+    } else {
+        float coverage = texture(font_tex, f_tex_pos).r;
+        Target0 = vec4(vec3(coverage), 1.0);
+    }
+}
@@ -0,0 +1,27 @@
+#version 150
+
+uniform sampler2D font_tex;
+uniform float distance_range;
+
+in vec2 f_tex_pos;
+in vec4 f_color;
+
+out vec4 Target0;
+
+float median(float r, float g, float b) {
+    return max(min(r, g), min(max(r, g), b));
+}
+
+void main() {
+    vec3 texel = texture(font_tex, f_tex_pos).rgb;
+    float distance = median(texel.r, texel.g, texel.b);
+    // distance range in screen pixels at the current magnification
+    vec2 unit_range = vec2(distance_range) / vec2(textureSize(font_tex, 0));
+    vec2 screen_tex_size = vec2(1.0) / fwidth(f_tex_pos);
+    float screen_range = max(0.5 * dot(unit_range, screen_tex_size), 1.0);
+    float alpha = clamp(screen_range * (distance - 0.5) + 0.5, 0.0, 1.0);
+    if (alpha <= 0.0) {
+        discard;
+    }
+    Target0 = f_color * vec4(1.0, 1.0, 1.0, alpha);
+}
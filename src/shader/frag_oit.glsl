@@ -0,0 +1,27 @@
+#version 150
+
+uniform sampler2D font_tex;
+uniform float gamma;
+
+in vec2 f_tex_pos;
+in vec4 f_color;
+
+out vec4 accum;
+out vec4 weight;
+
+void main() {
+    float alpha = f_color.a * texture(font_tex, f_tex_pos).r;
+    if (alpha <= 0.0) {
+        discard;
+    }
+    // weighted blended order-independent transparency (McGuire and Bavoil 2013),
+    // nearer and more opaque fragments weigh more
+    float w = clamp(
+        pow(min(1.0, alpha * 10.0) + 0.01, 3.0) * 1e8 * pow(1.0 - gl_FragCoord.z * 0.9, 3.0),
+        1e-2,
+        3e3
+    );
+    // the color channels are summed up, the alpha channel multiplied into the revealage
+    accum = vec4(pow(f_color.rgb, vec3(gamma)) * alpha * w, alpha);
+    weight = vec4(alpha * w, 0.0, 0.0, alpha);
+}
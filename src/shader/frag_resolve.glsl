@@ -0,0 +1,17 @@
+#version 150
+
+uniform sampler2D accum_tex;
+uniform sampler2D weight_tex;
+
+out vec4 Target0;
+
+void main() {
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    vec4 accum = texelFetch(accum_tex, texel, 0);
+    float revealage = accum.a;
+    if (revealage >= 1.0) {
+        discard;
+    }
+    float weight = texelFetch(weight_tex, texel, 0).r;
+    Target0 = vec4(accum.rgb / max(weight, 1e-5), 1.0 - revealage);
+}
@@ -0,0 +1,29 @@
+#version 150
+
+#include "fill.glsl"
+
+uniform float gamma;
+
+out vec4 Target0;
+
+// width of the edge between inside and outside of the glyph, in distance units
+float edge_width;
+
+float coverage_at(vec2 pos) {
+    return smoothstep(0.5 - edge_width, 0.5 + edge_width, textureLod(font_tex, pos, 0.0).r);
+}
+
+void main() {
+    vec4 color = fill_color();
+    float distance = texture(font_tex, f_tex_pos).r;
+    edge_width = fwidth(distance);
+    float alpha = smoothstep(0.5 - edge_width, 0.5 + edge_width, distance);
+    if (f_hollow > 0.0) {
+        alpha *= 1.0 - inner_coverage(1.0 / vec2(textureSize(font_tex, 0)));
+    }
+    if (alpha <= 0.0) {
+        discard;
+    }
+    // linearize the color for blending in linear space
+    Target0 = vec4(pow(color.rgb, vec3(gamma)), color.a * alpha);
+}
@@ -0,0 +1,35 @@
+#version 330
+
+#include "fill.glsl"
+
+uniform float subpixel_offset;
+uniform float gamma;
+
+layout(location = 0, index = 0) out vec4 Target0;
+layout(location = 0, index = 1) out vec4 Target1;
+
+float coverage_at(vec2 pos) {
+    return textureLod(font_tex, pos, 0.0).r;
+}
+
+void main() {
+    vec4 color = fill_color();
+    // the cache texture holds glyphs rasterized at three times the horizontal
+    // resolution, so neighbouring texels are the neighbouring subpixels
+    vec2 texel = vec2(subpixel_offset / float(textureSize(font_tex, 0).x), 0.0);
+    vec3 coverage = vec3(
+        texture(font_tex, f_tex_pos - texel).r,
+        texture(font_tex, f_tex_pos).r,
+        texture(font_tex, f_tex_pos + texel).r
+    ) * color.a;
+    if (f_hollow > 0.0) {
+        // a pixel is three texels wide
+        coverage *= 1.0 - inner_coverage(vec2(3.0, 1.0) / vec2(textureSize(font_tex, 0)));
+    }
+    float alpha = max(coverage.r, max(coverage.g, coverage.b));
+    if (alpha <= 0.0) {
+        discard;
+    }
+    Target0 = vec4(pow(color.rgb, vec3(gamma)) * coverage, alpha);
+    Target1 = vec4(coverage, alpha);
+}
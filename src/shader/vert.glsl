@@ -8,43 +8,50 @@ const mat4 INVERT_Y_AXIS = mat4(
 );
 
 uniform mat4 transform;
+// multiplies the alpha of every glyph
+uniform float opacity;
+// multiplies the color of every glyph in a draw
+uniform vec4 tint;
 
+// per corner of the unit quad, from (0, 0) at the left top to (1, 1)
+in vec2 corner;
+
+// per glyph
 in vec3 left_top;
 in vec2 right_bottom;
 in vec2 tex_left_top;
 in vec2 tex_right_bottom;
 in vec4 color;
+in vec3 transform_x;
+in vec3 transform_y;
+in vec3 fill_x;
+in vec3 fill_y;
+in vec4 procedural;
+in vec4 procedural_color;
+in float hollow;
 
 out vec2 f_tex_pos;
 out vec4 f_color;
+out vec2 f_fill_pos;
+out vec2 f_pixel_pos;
+flat out vec4 f_procedural;
+flat out vec4 f_procedural_color;
+flat out vec4 f_tex_rect;
+flat out float f_hollow;
 
-// generate positional data based on vertex ID
 void main() {
-    vec2 pos = vec2(0.0);
-    float left = left_top.x;
-    float right = right_bottom.x;
-    float top = left_top.y;
-    float bottom = right_bottom.y;
-
-    switch (gl_VertexID) {
-        case 0:
-            pos = vec2(left, top);
-            f_tex_pos = tex_left_top;
-            break;
-        case 1:
-            pos = vec2(right, top);
-            f_tex_pos = vec2(tex_right_bottom.x, tex_left_top.y);
-            break;
-        case 2:
-            pos = vec2(left, bottom);
-            f_tex_pos = vec2(tex_left_top.x, tex_right_bottom.y);
-            break;
-        case 3:
-            pos = vec2(right, bottom);
-            f_tex_pos = tex_right_bottom;
-            break;
-    }
-
-    f_color = color;
+    vec2 pos = mix(left_top.xy, right_bottom, corner);
+    f_tex_pos = mix(tex_left_top, tex_right_bottom, corner);
+
+    // the fill moves along with transformed glyphs
+    f_fill_pos = vec2(dot(fill_x, vec3(pos, 1.0)), dot(fill_y, vec3(pos, 1.0)));
+    f_pixel_pos = pos;
+    f_procedural = procedural;
+    f_procedural_color = procedural_color;
+    f_tex_rect = vec4(tex_left_top.x, tex_right_bottom.y, tex_right_bottom.x, tex_left_top.y);
+    f_hollow = hollow;
+    pos = vec2(dot(transform_x, vec3(pos, 1.0)), dot(transform_y, vec3(pos, 1.0)));
+
+    f_color = color * tint * vec4(1.0, 1.0, 1.0, opacity);
     gl_Position = INVERT_Y_AXIS * transform * vec4(pos, left_top.z, 1.0);
 }
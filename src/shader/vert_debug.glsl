@@ -0,0 +1,13 @@
+#version 150
+
+uniform mat4 transform;
+
+in vec2 position;
+in vec2 tex_pos;
+
+out vec2 f_tex_pos;
+
+void main() {
+    f_tex_pos = tex_pos;
+    gl_Position = transform * vec4(position, 0.0, 1.0);
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+use std::cell::RefCell;
+
+/// Text shader programs shared by several brushes drawing into the same context, e.g. a
+/// UI brush and a brush for labels in the world, see
+/// [`GlyphBrushBuilder::shared`](struct.GlyphBrushBuilder.html#method.shared).
+///
+/// The program is compiled once by the first brush built with the handle and reused by
+/// all others with the same raster mode. Cloning the handle is cheap, all clones refer
+/// to the same programs.
+///
+/// Only programs are shared, not the glyph cache: every brush rasterizes and packs its
+/// glyphs with its own draw cache. Several brushes can cache their glyphs in disjoint
+/// regions of one texture with
+/// [`GlyphBrushBuilder::cache_texture`](struct.GlyphBrushBuilder.html#method.cache_texture).
+/// To use a single cache, draw several layers with one brush, queueing and drawing each
+/// layer in turn.
+#[derive(Clone, Default)]
+pub struct GlyphBrushShared {
+    programs: Rc<RefCell<Programs>>,
+}
+
+/// Text programs per raster mode, created on first use.
+#[derive(Default)]
+struct Programs {
+    alpha_mask: Option<Rc<Program>>,
+    sdf: Option<Rc<Program>>,
+}
+
+impl GlyphBrushShared {
+    pub fn new() -> Self {
+        GlyphBrushShared::default()
+    }
+
+    /// Returns the shared program for `raster_mode`, compiling it on first use.
+    pub(crate) fn program<C: Facade>(
+        &self,
+        facade: &C,
+        raster_mode: RasterMode,
+    ) -> Result<Rc<Program>, ProgramCreationError> {
+        let mut programs = self.programs.borrow_mut();
+        let program = match raster_mode {
+            RasterMode::AlphaMask => &mut programs.alpha_mask,
+            RasterMode::Sdf { .. } => &mut programs.sdf,
+        };
+        if let Some(program) = program {
+            return Ok(program.clone());
+        }
+        let created = Rc::new(create_program(facade, raster_mode)?);
+        *program = Some(created.clone());
+        Ok(created)
+    }
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+/// Moves glyph quads so that their left top corners lie on whole pixels, keeping their
+/// size. In subpixel mode x coordinates are stretched, so they snap to whole subpixels.
+pub(crate) fn snap_to_pixels<'a, I: Iterator<Item = &'a mut GlyphVertex>>(verts: I) {
+    for vertex in verts {
+        let dx = vertex.left_top[0].round() - vertex.left_top[0];
+        let dy = vertex.right_bottom[1].round() - vertex.right_bottom[1];
+        vertex.left_top[0] += dx;
+        vertex.right_bottom[0] += dx;
+        vertex.left_top[1] += dy;
+        vertex.right_bottom[1] += dy;
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Rounds glyph positions to whole pixels before uploading them, so that sections at
+    /// fractional coordinates, e.g. centered or animated ones, don't look blurry from
+    /// sampling the cache texture between texels.
+    ///
+    /// Positions are rounded in the pixel coordinates of the queued sections, before the
+    /// glyph and draw transforms, which are device pixels with the default transform of
+    /// [`draw_queued`](#method.draw_queued). With subpixel antialiasing, glyphs snap to
+    /// whole subpixels instead.
+    pub fn set_pixel_snapping(&mut self, enabled: bool) {
+        if enabled != self.pixel_snapping {
+            self.pixel_snapping = enabled;
+            self.invalidate_vertices();
+        }
+    }
+}
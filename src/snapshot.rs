@@ -0,0 +1,216 @@
+use super::*;
+
+use bake::{put_u32, Reader};
+use glyph_brush::ab_glyph::{GlyphId, Rect};
+use glyph_brush::{SectionGeometry, SectionGlyph};
+
+const MAGIC: &[u8; 8] = b"GGLAYOUT";
+const FORMAT_VERSION: u32 = 1;
+
+/// A positioned glyph of a [`LayoutSnapshot`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SnapshotGlyph {
+    pub font_id: FontId,
+    pub glyph_id: GlyphId,
+    /// Pixel rectangle of the rasterized glyph, clipped to its section's bounds.
+    pub pixel_rect: Rect,
+    pub z: f32,
+    /// Texture coordinates of the glyph in the cache texture, from 0 to 1.
+    pub uv_rect: Rect,
+    pub color: [f32; 4],
+}
+
+/// The fully positioned glyphs of a set of sections, e.g. for a headless layout process
+/// shipping them to a separate rendering process.
+///
+/// Taken with [`GlyphBrush::snapshot`](struct.GlyphBrush.html#method.snapshot), stored with
+/// [`to_bytes`](#method.to_bytes) and loaded again with [`from_bytes`](#method.from_bytes).
+/// The format stays readable by later versions of this crate.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LayoutSnapshot {
+    /// Dimensions `(width, height)` of the cache texture the texture coordinates refer to.
+    pub texture_size: (u32, u32),
+    pub glyphs: Vec<SnapshotGlyph>,
+}
+
+impl LayoutSnapshot {
+    /// Serializes the snapshot into a compact binary representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(24 + self.glyphs.len() * 60);
+        out.extend_from_slice(MAGIC);
+        put_u32(&mut out, FORMAT_VERSION);
+        put_u32(&mut out, self.texture_size.0);
+        put_u32(&mut out, self.texture_size.1);
+        put_u32(&mut out, self.glyphs.len() as u32);
+        for g in &self.glyphs {
+            put_u32(&mut out, g.font_id.0 as u32);
+            put_u32(&mut out, u32::from(g.glyph_id.0));
+            let (p, uv) = (g.pixel_rect, g.uv_rect);
+            let c = g.color;
+            let fields = [
+                p.min.x, p.min.y, p.max.x, p.max.y, uv.min.x, uv.min.y, uv.max.x, uv.max.y, g.z,
+                c[0], c[1], c[2], c[3],
+            ];
+            for f in &fields {
+                put_u32(&mut out, f.to_bits());
+            }
+        }
+        out
+    }
+
+    /// Parses a snapshot previously serialized with [`to_bytes`](#method.to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AtlasParseError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(AtlasParseError::InvalidHeader);
+        }
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(AtlasParseError::UnsupportedVersion(version));
+        }
+        let texture_size = (reader.u32()?, reader.u32()?);
+        let glyph_count = reader.u32()?;
+        let mut glyphs = Vec::new();
+        for _ in 0..glyph_count {
+            let font_id = FontId(reader.u32()? as usize);
+            let glyph_id = GlyphId(reader.u32()? as u16);
+            let mut f = [0.0; 13];
+            for f in f.iter_mut() {
+                *f = f32::from_bits(reader.u32()?);
+            }
+            let rect = |f: &[f32]| Rect {
+                min: point(f[0], f[1]),
+                max: point(f[2], f[3]),
+            };
+            glyphs.push(SnapshotGlyph {
+                font_id,
+                glyph_id,
+                pixel_rect: rect(&f[0..4]),
+                uv_rect: rect(&f[4..8]),
+                z: f[8],
+                color: [f[9], f[10], f[11], f[12]],
+            });
+        }
+        Ok(LayoutSnapshot {
+            texture_size,
+            glyphs,
+        })
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher> GlyphBrush<F, H> {
+    /// Lays out `sections` and rasterizes their glyphs into the cache texture, returning
+    /// the positioned glyphs instead of drawing them.
+    ///
+    /// Sections queued for drawing are discarded. Later draws may move glyphs within
+    /// the cache texture, so the texture coordinates are only valid until then.
+    pub fn snapshot<'a, C, I, S>(
+        &mut self,
+        facade: &C,
+        sections: I,
+    ) -> Result<LayoutSnapshot, DrawError>
+    where
+        C: Facade,
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        self.discard_queued();
+        self.overflow.forget_queued();
+        // Every glyph is queued with its own extra, whose `z` is replaced with the glyph's
+        // index to find its font, id and actual `z` again in the vertices.
+        let mut positioned = Vec::new();
+        for section in sections {
+            let section = section.into();
+            let bounds = section
+                .layout
+                .bounds_rect(&SectionGeometry::from(section.as_ref()));
+            let glyphs: Vec<SectionGlyph> = self
+                .glyphs_custom_layout(section.as_ref(), &section.layout)
+                .cloned()
+                .collect();
+            let mut extras = Vec::with_capacity(glyphs.len());
+            let glyphs = glyphs
+                .into_iter()
+                .enumerate()
+                .map(|(i, glyph)| {
+                    let mut extra = GlyphExtra::from(section.text[glyph.section_index].extra);
+                    positioned.push((glyph.font_id, glyph.glyph.id, extra.extra.z));
+                    extra.extra.z = (positioned.len() - 1) as f32;
+                    extras.push(extra);
+                    SectionGlyph {
+                        section_index: i,
+                        ..glyph
+                    }
+                })
+                .collect();
+            self.glyph_brush
+                .queue_pre_positioned(glyphs, extras, bounds);
+        }
+
+        // make sure the vertices are generated now and again by the next draw
+        self.invalidate_vertices();
+        let brush_action = loop {
+            let brush_action = {
+                let tex = &self.texture;
+                let raster_mode = self.raster_mode;
+                let uploader = &mut self.texture_uploader;
+                let regions = &mut self.cache_regions;
+                let region = self.cache_region;
+                self.glyph_brush.process_queued(
+                    |rect, tex_data| {
+                        let rect = in_region(rect, region);
+                        upload_glyph(tex, raster_mode, uploader, rect, tex_data);
+                        regions.track(rect);
+                    },
+                    |v| to_filled_vertex(v, false),
+                )
+            };
+            match brush_action {
+                Ok(action) => break action,
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    let size = self.budgeted_texture_size(suggested);
+                    if let Err(e) = self.grow_texture(facade, size) {
+                        self.discard_queued();
+                        return Err(e);
+                    }
+                }
+            }
+        };
+        self.invalidate_vertices();
+
+        let mut verts = match brush_action {
+            BrushAction::Draw(verts) => verts,
+            BrushAction::ReDraw => Vec::new(),
+        };
+        let tex_size = self.texture.dimensions();
+        map_tex_coords(
+            verts.iter_mut().map(|(_, v, _)| v),
+            self.cache_region,
+            tex_size,
+        );
+        let glyphs = verts
+            .into_iter()
+            .map(|(_, v, _)| {
+                let (font_id, glyph_id, z) = positioned[v.left_top[2] as usize];
+                SnapshotGlyph {
+                    font_id,
+                    glyph_id,
+                    pixel_rect: Rect {
+                        min: point(v.left_top[0], v.right_bottom[1]),
+                        max: point(v.right_bottom[0], v.left_top[1]),
+                    },
+                    z,
+                    uv_rect: Rect {
+                        min: point(v.tex_left_top[0], v.tex_right_bottom[1]),
+                        max: point(v.tex_right_bottom[0], v.tex_left_top[1]),
+                    },
+                    color: v.color,
+                }
+            })
+            .collect();
+        Ok(LayoutSnapshot {
+            texture_size: self.texture.dimensions(),
+            glyphs,
+        })
+    }
+}
@@ -0,0 +1,443 @@
+use super::*;
+
+use std::hash::Hasher;
+
+/// A fill computed in the shader from the pixel position and the brush's
+/// [time](struct.GlyphBrush.html#method.set_time), multiplied with the text color.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ProceduralFill {
+    /// Hues cycling from left to right, `wavelength` pixels per cycle,
+    /// moving by `speed` cycles per second.
+    Rainbow { wavelength: f32, speed: f32 },
+    /// Highlight bands of `color` sweeping from left to right, `width` pixels wide,
+    /// `spacing` pixels apart and moving by `speed` pixels per second.
+    Shimmer {
+        color: [f32; 4],
+        width: f32,
+        spacing: f32,
+        speed: f32,
+    },
+}
+
+impl ProceduralFill {
+    /// Returns the parameters as passed to the shader, the kind followed by up to three
+    /// values, and a color.
+    pub(crate) fn to_params(self) -> ([f32; 4], [f32; 4]) {
+        match self {
+            ProceduralFill::Rainbow { wavelength, speed } => {
+                ([1.0, wavelength, speed, 0.0], [0.0; 4])
+            }
+            ProceduralFill::Shimmer {
+                color,
+                width,
+                spacing,
+                speed,
+            } => ([2.0, width, spacing, speed], color),
+        }
+    }
+
+    /// Scales horizontal lengths and speeds, for subpixel mode's stretched coordinates.
+    pub(crate) fn stretched(self, factor: f32) -> Self {
+        match self {
+            ProceduralFill::Rainbow { wavelength, speed } => ProceduralFill::Rainbow {
+                wavelength: wavelength * factor,
+                speed,
+            },
+            ProceduralFill::Shimmer {
+                color,
+                width,
+                spacing,
+                speed,
+            } => ProceduralFill::Shimmer {
+                color,
+                width: width * factor,
+                spacing: spacing * factor,
+                speed: speed * factor,
+            },
+        }
+    }
+}
+
+impl Hash for ProceduralFill {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        let (params, color) = self.to_params();
+        for v in params.iter().chain(color.iter()) {
+            v.to_bits().hash(state);
+        }
+    }
+}
+
+/// Most rings of offsets covering a disc.
+const MAX_DISC_RINGS: u32 = 4;
+/// Most offsets on a ring covering a disc.
+const MAX_RING_OFFSETS: u32 = 32;
+
+/// Widest outline in pixels drawn in hardened mode.
+const HARDENED_MAX_OUTLINE: f32 = 64.0;
+/// Largest shadow blur radius in pixels drawn in hardened mode.
+const HARDENED_MAX_BLUR: f32 = 64.0;
+
+/// Returns a finite width between 0 and `max`.
+fn clamp_width(width: f32, max: f32) -> f32 {
+    if width.is_nan() {
+        0.0
+    } else {
+        width.clamp(0.0, max)
+    }
+}
+
+/// Returns `offset`, or no offset if it isn't finite.
+fn finite_offset(offset: (f32, f32)) -> (f32, f32) {
+    if offset.0.is_finite() && offset.1.is_finite() {
+        offset
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// Offsets covering a disc on concentric rings, one pixel apart, so that copies
+/// of glyphs moved by them leave no gaps. Doesn't include the center.
+///
+/// There are at most `MAX_DISC_RINGS` rings of `MAX_RING_OFFSETS` offsets, larger discs
+/// have them further apart.
+fn disc_offsets(radius: f32) -> Vec<(f32, f32)> {
+    let rings = radius.ceil().clamp(1.0, MAX_DISC_RINGS as f32) as u32;
+    let mut offsets = Vec::new();
+    for ring in 1..=rings {
+        let ring_radius = radius * ring as f32 / rings as f32;
+        let count = (2.0 * std::f32::consts::PI * ring_radius)
+            .ceil()
+            .clamp(8.0, MAX_RING_OFFSETS as f32) as u32;
+        for i in 0..count {
+            let angle = 2.0 * std::f32::consts::PI * i as f32 / count as f32;
+            offsets.push((ring_radius * angle.cos(), ring_radius * angle.sin()));
+        }
+    }
+    offsets
+}
+
+/// An outline drawn around the glyphs by repeating them with offsets behind the text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Outline {
+    color: [f32; 4],
+    width: f32,
+    offset: (f32, f32),
+}
+
+/// A drop shadow drawn behind the glyphs and their outline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Shadow {
+    offset: (f32, f32),
+    color: [f32; 4],
+    blur: f32,
+}
+
+/// Effects applied to a queued section, see
+/// [`GlyphBrush::queue_styled`](struct.GlyphBrush.html#method.queue_styled).
+///
+/// A section is drawn in up to three passes, each with its own color: the shadow,
+/// the outline, then the glyphs themselves on top, e.g. for sticker-style text
+/// with a white fill, a colored border and a dark shadow.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SectionStyle {
+    pub(crate) fill_color: Option<[f32; 4]>,
+    pub(crate) fill: Option<TextFill>,
+    pub(crate) procedural: Option<ProceduralFill>,
+    pub(crate) outline: Option<Outline>,
+    pub(crate) shadow: Option<Shadow>,
+    pub(crate) hollow: f32,
+    pub(crate) transform: GlyphTransform,
+    pub(crate) depth: Option<f32>,
+    pub(crate) params: Option<DrawParamsId>,
+    /// index of the layer in the brush
+    pub(crate) layer: usize,
+    /// id of the retained section the glyphs belong to
+    pub(crate) retained: Option<u32>,
+}
+
+impl SectionStyle {
+    /// Draws the glyphs in `color` instead of the section's text colors.
+    ///
+    /// Defaults to the text colors.
+    pub fn fill_color(mut self, color: [f32; 4]) -> Self {
+        self.fill_color = Some(color);
+        self
+    }
+
+    /// Fills the glyphs with a texture, see [`TextFill`](struct.TextFill.html).
+    ///
+    /// Defaults to no fill.
+    pub fn fill(mut self, fill: TextFill) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+
+    /// Fills the glyphs with a procedural pattern, on top of a texture fill.
+    ///
+    /// Defaults to no procedural fill.
+    pub fn procedural(mut self, procedural: ProceduralFill) -> Self {
+        self.procedural = Some(procedural);
+        self
+    }
+
+    /// Draws an outline `width` pixels wide around the glyphs, e.g. for contrast on
+    /// arbitrary backgrounds.
+    ///
+    /// The outline is made of copies of the section drawn behind it, so translucent
+    /// colors overlap and opaque colors work best. Its cost grows with the width up to
+    /// 128 copies, outlines wider than about 4 pixels get uneven edges. The copies are drawn in
+    /// the outline color without the [`fill`](#method.fill),
+    /// [`procedural`](#method.procedural) fill and [`hollow`](#method.hollow) of the
+    /// glyphs, and keep their color when a retained section is recolored with
+    /// [`GlyphBrush::update_color`](struct.GlyphBrush.html#method.update_color).
+    ///
+    /// In [hardened mode](struct.GlyphBrush.html#method.set_hardened) the width is
+    /// clamped to 64 pixels.
+    ///
+    /// Defaults to no outline.
+    pub fn outline(self, color: [f32; 4], width: f32) -> Self {
+        self.offset_outline(color, width, (0.0, 0.0))
+    }
+
+    /// Draws an outline like [`outline`](#method.outline), moved by `offset` pixels,
+    /// e.g. for a thick border that doubles as an extruded edge.
+    pub fn offset_outline(mut self, color: [f32; 4], width: f32, offset: (f32, f32)) -> Self {
+        self.outline = Some(Outline {
+            color,
+            width,
+            offset,
+        });
+        self
+    }
+
+    /// Draws only the stroke of the glyphs, `width` pixels wide inside their outline,
+    /// e.g. for watermarks and stencils.
+    ///
+    /// Not supported by color glyphs. Defaults to filled glyphs.
+    pub fn hollow(mut self, width: f32) -> Self {
+        self.hollow = width;
+        self
+    }
+
+    /// Transforms the glyphs and the passes behind them, e.g. to scale or move a section
+    /// independently of the other text drawn in the same call. The transform maps pixel
+    /// coordinates before the draw transform.
+    ///
+    /// Not supported by color glyphs. Defaults to the identity.
+    pub fn transform(mut self, transform: GlyphTransform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Rotates the glyphs clockwise on screen by `angle` radians around `pivot` in
+    /// pixels, after the [`transform`](#method.transform), e.g. for tilted signs or
+    /// vertical axis labels.
+    pub fn rotate(mut self, angle: f32, pivot: (f32, f32)) -> Self {
+        self.transform = self
+            .transform
+            .then(GlyphTransform::rotation(angle).around(pivot));
+        self
+    }
+
+    /// Draws the glyphs and the passes behind them at depth `z` instead of the **z** of
+    /// their text, see [`DepthPreset`](enum.DepthPreset.html) for using it with the
+    /// depth buffer.
+    ///
+    /// Not supported by color glyphs. Defaults to the **z** of the text.
+    pub fn depth(mut self, z: f32) -> Self {
+        self.depth = Some(z);
+        self
+    }
+
+    /// Draws the glyphs and the passes behind them with draw parameters added with
+    /// [`GlyphBrush::add_draw_params`](struct.GlyphBrush.html#method.add_draw_params),
+    /// see [`GlyphBrush::queue_with_params`](struct.GlyphBrush.html#method.queue_with_params).
+    ///
+    /// Defaults to the brush's draw parameters.
+    pub fn draw_params(mut self, params: DrawParamsId) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Puts the section on the layer with the given index in the brush.
+    pub(crate) fn layer(mut self, layer: usize) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// Returns the style with effects clamped for hardened mode.
+    pub(crate) fn hardened(mut self) -> Self {
+        if let Some(outline) = &mut self.outline {
+            outline.width = clamp_width(outline.width, HARDENED_MAX_OUTLINE);
+            outline.offset = finite_offset(outline.offset);
+        }
+        if let Some(shadow) = &mut self.shadow {
+            shadow.blur = clamp_width(shadow.blur, HARDENED_MAX_BLUR);
+            shadow.offset = finite_offset(shadow.offset);
+        }
+        self
+    }
+
+    /// Tags the glyphs as those of the retained section with the given id.
+    pub(crate) fn retained(mut self, id: u32) -> Self {
+        self.retained = Some(id);
+        self
+    }
+
+    /// Draws a shadow moved by `offset` pixels behind the glyphs, see
+    /// [`blurred_shadow`](#method.blurred_shadow) for a soft one.
+    ///
+    /// Defaults to no shadow.
+    pub fn shadow(self, offset: (f32, f32), color: [f32; 4]) -> Self {
+        self.blurred_shadow(offset, color, 0.0)
+    }
+
+    /// Draws a shadow moved by `offset` pixels behind the glyphs, blurred over
+    /// `blur` pixels.
+    ///
+    /// The blur is approximated with translucent copies of the section, so its cost
+    /// grows with the blur radius up to 129 copies, blurs wider than about 4 pixels get
+    /// banded. Like the [outline](#method.outline), the copies are drawn in the shadow
+    /// color only.
+    ///
+    /// In [hardened mode](struct.GlyphBrush.html#method.set_hardened) the blur is
+    /// clamped to 64 pixels.
+    pub fn blurred_shadow(mut self, offset: (f32, f32), color: [f32; 4], blur: f32) -> Self {
+        self.shadow = Some(Shadow {
+            offset,
+            color,
+            blur,
+        });
+        self
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Queues a section like [`queue`](#method.queue), with effects applied to it.
+    pub fn queue_styled<'a, S>(&mut self, section: S, style: &SectionStyle)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+        self.queue_section(section, &layout, style)
+    }
+
+    /// Queues a copy of the section moved by `offset` pixels in the given color,
+    /// e.g. as part of an outline. The copy isn't filled, hollow or tagged as a retained
+    /// section, it's drawn in `color` only.
+    fn queue_offset_copy<G: GlyphPositioner>(
+        &mut self,
+        section: &Section<X>,
+        custom_layout: &G,
+        (dx, dy): (f32, f32),
+        color: [f32; 4],
+        style: &SectionStyle,
+    ) {
+        let stretch = if self.subpixel.is_some() { 3.0 } else { 1.0 };
+        let mut copy = with_glyph_extra(section);
+        copy.screen_position.0 += dx * stretch;
+        copy.screen_position.1 += dy;
+        for text in &mut copy.text {
+            text.extra.extra = text.extra.extra.with_color(color);
+            text.extra.transform = style.transform.stretched(stretch);
+            text.extra.depth = style.depth;
+            text.extra.params = style.params;
+            text.extra.layer = style.layer;
+        }
+        self.queue_laid_out(copy, custom_layout);
+    }
+
+    /// Queues the passes drawn behind a section, expects a stretched section
+    /// in subpixel mode.
+    pub(crate) fn queue_background_passes<G: GlyphPositioner>(
+        &mut self,
+        section: &Section<X>,
+        custom_layout: &G,
+        style: &SectionStyle,
+    ) {
+        let style = &if self.hardened {
+            style.hardened()
+        } else {
+            *style
+        };
+        if let Some(shadow) = style.shadow {
+            let mut offsets = vec![(0.0, 0.0)];
+            if shadow.blur > 0.0 {
+                offsets.extend(disc_offsets(shadow.blur));
+            }
+            // where all copies overlap, they add up to the shadow's alpha
+            let mut color = shadow.color;
+            color[3] = 1.0 - (1.0 - color[3]).powf(1.0 / offsets.len() as f32);
+            for (dx, dy) in offsets {
+                let offset = (shadow.offset.0 + dx, shadow.offset.1 + dy);
+                self.queue_offset_copy(section, custom_layout, offset, color, style);
+            }
+        }
+        if let Some(outline) = style.outline {
+            for (dx, dy) in disc_offsets(outline.width) {
+                let offset = (outline.offset.0 + dx, outline.offset.1 + dy);
+                self.queue_offset_copy(section, custom_layout, offset, outline.color, style);
+            }
+        }
+    }
+
+    /// Sets the time in seconds animating [procedural fills](enum.ProceduralFill.html).
+    ///
+    /// Custom shaders receive it as the `time` uniform.
+    pub fn set_time(&mut self, seconds: f32) {
+        self.time = seconds;
+    }
+
+    /// Multiplies the alpha of all glyphs drawn by `alpha`, e.g. to fade a menu in and
+    /// out without queueing its sections with other colors. Applies to the next draws
+    /// without processing the queue again.
+    ///
+    /// Custom shaders receive it as the `opacity` uniform.
+    ///
+    /// Defaults to 1.
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.opacity = alpha;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disc_offsets_are_capped() {
+        assert_eq!(disc_offsets(1.0).len(), 8);
+        let wide = disc_offsets(50.0);
+        assert_eq!(wide.len(), (MAX_DISC_RINGS * MAX_RING_OFFSETS) as usize);
+        let outer = wide
+            .iter()
+            .map(|&(x, y)| (x * x + y * y).sqrt())
+            .fold(0.0, f32::max);
+        assert!((outer - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn hardened_outline() {
+        let style = SectionStyle::default()
+            .offset_outline([0.0; 4], 1e6, (f32::NAN, 1.0))
+            .hardened();
+        let outline = style.outline.unwrap();
+        assert_eq!(outline.width, HARDENED_MAX_OUTLINE);
+        assert_eq!(outline.offset, (0.0, 0.0));
+        let style = SectionStyle::default()
+            .outline([0.0; 4], f32::NAN)
+            .hardened();
+        assert_eq!(style.outline.unwrap().width, 0.0);
+    }
+
+    #[test]
+    fn hardened_shadow() {
+        let style = SectionStyle::default()
+            .blurred_shadow((1.0, f32::INFINITY), [0.0; 4], f32::INFINITY)
+            .hardened();
+        let shadow = style.shadow.unwrap();
+        assert_eq!(shadow.blur, HARDENED_MAX_BLUR);
+        assert_eq!(shadow.offset, (0.0, 0.0));
+    }
+}
@@ -0,0 +1,186 @@
+use super::*;
+
+use glium::{Blend, BlendingFunction, LinearBlendingFactor};
+use glyph_brush::ab_glyph::PxScale;
+use glyph_brush::{OwnedSection, OwnedText};
+
+/// Order of the color subpixels of an LCD, used by
+/// [`GlyphBrushBuilder::subpixel_aa`](struct.GlyphBrushBuilder.html#method.subpixel_aa).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SubpixelOrder {
+    /// Red on the left, the most common layout.
+    Rgb,
+    /// Blue on the left.
+    Bgr,
+}
+
+/// Subpixel antialiasing state of a brush.
+///
+/// Glyphs are laid out and rasterized at three times the horizontal resolution,
+/// so that every cache texel covers one color subpixel, and squeezed back when drawn.
+pub(crate) struct Subpixel {
+    order: SubpixelOrder,
+    program: Program,
+    /// whether `program` blends with dual source blending instead of two passes
+    dual_source: bool,
+}
+
+impl Subpixel {
+    pub(crate) fn new<C: Facade>(
+        facade: &C,
+        order: SubpixelOrder,
+    ) -> Result<Self, ProgramCreationError> {
+        static VERTEX_SHADER: &str = include_str!("shader/vert.glsl");
+        static FRAGMENT_SHADER: &str = include_str!("shader/frag_subpixel.glsl");
+        static DUAL_SOURCE_FRAGMENT_SHADER: &str = include_str!("shader/frag_subpixel_dual.glsl");
+        // Dual source blending needs GLSL 3.30, fall back to two passes without it.
+        if let Ok(program) = Program::from_source(
+            facade,
+            VERTEX_SHADER,
+            &include_fill(DUAL_SOURCE_FRAGMENT_SHADER),
+            None,
+        ) {
+            return Ok(Subpixel {
+                order,
+                program,
+                dual_source: true,
+            });
+        }
+        Ok(Subpixel {
+            order,
+            program: Program::from_source(
+                facade,
+                VERTEX_SHADER,
+                &include_fill(FRAGMENT_SHADER),
+                None,
+            )?,
+            dual_source: false,
+        })
+    }
+
+    /// Stretches a section horizontally to be laid out at subpixel resolution.
+    pub(crate) fn stretch_section<X: Clone>(section: &Section<X>) -> OwnedSection<X> {
+        OwnedSection {
+            screen_position: (section.screen_position.0 * 3.0, section.screen_position.1),
+            bounds: (section.bounds.0 * 3.0, section.bounds.1),
+            layout: section.layout,
+            text: section
+                .text
+                .iter()
+                .map(|t| OwnedText {
+                    text: t.text.to_owned(),
+                    scale: PxScale {
+                        x: t.scale.x * 3.0,
+                        y: t.scale.y,
+                    },
+                    font_id: t.font_id,
+                    extra: t.extra.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Adjusts a position transform to squeeze stretched sections back.
+    pub(crate) fn squeeze_transform(mut transform: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+        for v in transform[0].iter_mut() {
+            *v /= 3.0;
+        }
+        transform
+    }
+
+    pub(crate) fn draw<S: Surface>(
+        &self,
+        surface: &mut S,
+        texture: &Texture2d,
+        quads: Quads,
+        draw_uniforms: DrawUniforms,
+        params: &glium::DrawParameters,
+    ) -> Result<(), glium::DrawError> {
+        let sampler = glium::uniforms::Sampler::new(texture)
+            .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+            .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest)
+            .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest);
+        let fill_sampler = fill_sampler(&draw_uniforms, texture);
+        let subpixel_offset: f32 = match self.order {
+            SubpixelOrder::Rgb => 1.0,
+            SubpixelOrder::Bgr => -1.0,
+        };
+
+        // Blends every color channel with its own coverage:
+        // dst = text color * coverage + dst * (1 - coverage)
+        if self.dual_source {
+            let uniforms = WithUserUniforms {
+                uniforms: uniform! {
+                    font_tex: sampler,
+                    fill_tex: fill_sampler,
+                    fill_enabled: draw_uniforms.fill_tex.is_some(),
+                    time: draw_uniforms.time,
+                    opacity: draw_uniforms.opacity,
+                    tint: draw_uniforms.tint,
+                    transform: draw_uniforms.transform,
+                    subpixel_offset: subpixel_offset,
+                    gamma: draw_uniforms.gamma,
+                },
+                user: draw_uniforms.user,
+            };
+            let params = glium::DrawParameters {
+                blend: Blend {
+                    color: BlendingFunction::Addition {
+                        source: LinearBlendingFactor::One,
+                        destination: LinearBlendingFactor::OneMinusSourceOneColor,
+                    },
+                    alpha: BlendingFunction::Addition {
+                        source: LinearBlendingFactor::One,
+                        destination: LinearBlendingFactor::OneMinusSourceAlpha,
+                    },
+                    constant_value: (0.0, 0.0, 0.0, 0.0),
+                },
+                ..params.clone()
+            };
+            return quads.draw(surface, &self.program, &uniforms, &params);
+        }
+
+        // First darken the destination by the coverage, then add the text color.
+        for &coverage_pass in &[true, false] {
+            let uniforms = WithUserUniforms {
+                uniforms: uniform! {
+                    font_tex: sampler,
+                    fill_tex: fill_sampler,
+                    fill_enabled: draw_uniforms.fill_tex.is_some(),
+                    time: draw_uniforms.time,
+                    opacity: draw_uniforms.opacity,
+                    tint: draw_uniforms.tint,
+                    transform: draw_uniforms.transform,
+                    subpixel_offset: subpixel_offset,
+                    gamma: draw_uniforms.gamma,
+                    coverage_pass: coverage_pass,
+                },
+                user: draw_uniforms.user,
+            };
+            let (source, destination) = if coverage_pass {
+                (
+                    LinearBlendingFactor::Zero,
+                    LinearBlendingFactor::OneMinusSourceColor,
+                )
+            } else {
+                (LinearBlendingFactor::One, LinearBlendingFactor::One)
+            };
+            let params = glium::DrawParameters {
+                blend: Blend {
+                    color: BlendingFunction::Addition {
+                        source,
+                        destination,
+                    },
+                    alpha: BlendingFunction::Addition {
+                        source: LinearBlendingFactor::One,
+                        destination: LinearBlendingFactor::OneMinusSourceAlpha,
+                    },
+                    constant_value: (0.0, 0.0, 0.0, 0.0),
+                },
+                ..params.clone()
+            };
+            quads.draw(surface, &self.program, &uniforms, &params)?;
+        }
+        Ok(())
+    }
+}
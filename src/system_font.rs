@@ -0,0 +1,87 @@
+use super::*;
+
+use fontdb::{Database, Family, Query, Style, Weight};
+
+/// Finds the installed face closest to the query and parses it, returning it with its
+/// weight and whether it is italic. Scans the system's font directories on every call.
+pub(crate) fn load_system_font(query: &FontQuery) -> Result<(FontVec, u16, bool), SystemFontError> {
+    let mut db = Database::new();
+    db.load_system_fonts();
+    let not_found = || SystemFontError::NotFound(query.family.to_owned());
+    let id = db
+        .query(&Query {
+            families: &[Family::Name(query.family)],
+            weight: Weight(query.weight),
+            style: match query.italic {
+                true => Style::Italic,
+                false => Style::Normal,
+            },
+            ..Query::default()
+        })
+        .ok_or_else(not_found)?;
+    let face = db.face(id).ok_or_else(not_found)?;
+    let font = db
+        .with_face_data(id, |data, index| {
+            FontVec::try_from_vec_and_index(data.to_vec(), index)
+        })
+        .ok_or_else(not_found)?
+        .map_err(SystemFontError::InvalidFont)?;
+    Ok((font, face.weight.0, face.style != Style::Normal))
+}
+
+impl<F: Font> GlyphBrushBuilder<F> {
+    /// Specifies an installed font family, e.g. `"DejaVu Sans"`, as the default font
+    /// like [`using_font`](#method.using_font), loading its regular face.
+    ///
+    /// Returns an error if no such family is installed or its font file can't be
+    /// parsed. Further families are added to the built brush with
+    /// [`add_system_font`](struct.GlyphBrush.html#method.add_system_font). Unlike those,
+    /// the default font has to be
+    /// [set up for queries](struct.GlyphBrush.html#method.set_font_style) by hand.
+    pub fn using_system_font(family: &str) -> Result<Self, SystemFontError>
+    where
+        F: From<FontVec>,
+    {
+        load_system_font(&FontQuery::new(family)).map(|(font, ..)| Self::using_font(font.into()))
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Finds an installed font family, e.g. `"Noto Sans CJK JP"`, and adds its regular
+    /// face like [`add_font`](#method.add_font), e.g. as a
+    /// [fallback font](#method.set_font_fallbacks). The font is found by
+    /// [`query_font`](#method.query_font) afterwards.
+    ///
+    /// Returns an error if no such family is installed or its font file can't be
+    /// parsed. Scans the system's font directories on every call.
+    ///
+    /// ```no_run
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::GlyphBrush;
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # fn f(brush: &mut GlyphBrush<FontArc>) {
+    /// if let Ok(cjk) = brush.add_system_font("Noto Sans CJK JP") {
+    ///     brush.set_font_fallbacks(vec![cjk]);
+    /// }
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn add_system_font(&mut self, family: &str) -> Result<FontId, SystemFontError>
+    where
+        F: From<FontVec>,
+    {
+        self.add_system_font_matching(&FontQuery::new(family))
+    }
+
+    /// Finds the installed face of a family closest to the query, e.g. its bold face,
+    /// and adds it like [`add_system_font`](#method.add_system_font).
+    pub fn add_system_font_matching(&mut self, query: &FontQuery) -> Result<FontId, SystemFontError>
+    where
+        F: From<FontVec>,
+    {
+        let (font, weight, italic) = load_system_font(query)?;
+        let id = self.glyph_brush.add_font(font);
+        self.font_faces.set(id, query.family, weight, italic);
+        Ok(id)
+    }
+}
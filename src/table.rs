@@ -0,0 +1,186 @@
+use super::*;
+
+use glyph_brush::{HorizontalAlign, Layout, Text};
+
+/// Width of a [`Table`](struct.Table.html) column.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ColumnWidth {
+    /// Fixed width in pixels, longer cells wrap.
+    Fixed(f32),
+    /// As wide as the widest cell of the column.
+    Auto,
+}
+
+/// Width and alignment of a [`Table`](struct.Table.html) column.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Column {
+    width: ColumnWidth,
+    align: HorizontalAlign,
+}
+
+impl Column {
+    /// A left aligned column of the given width.
+    pub fn new(width: ColumnWidth) -> Self {
+        Column {
+            width,
+            align: HorizontalAlign::Left,
+        }
+    }
+
+    /// A left aligned column as wide as its widest cell.
+    pub fn auto() -> Self {
+        Self::new(ColumnWidth::Auto)
+    }
+
+    /// A left aligned column of fixed width.
+    pub fn fixed(width: f32) -> Self {
+        Self::new(ColumnWidth::Fixed(width))
+    }
+
+    /// Defaults to `HorizontalAlign::Left`
+    pub fn align(mut self, align: HorizontalAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// Lays out rows of text cells into aligned columns, e.g. for debug overlays
+/// or scoreboards.
+///
+/// Cells are measured with a `glyph_brush::GlyphCruncher`, so tables
+/// can be laid out with a `GlyphBrush` as well as a `GlyphCalculator`, and turned
+/// into one section per cell.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::{Column, Table};
+/// # use glium_glyph::glyph_brush::{HorizontalAlign, Text};
+/// # fn main() {
+/// let table = Table::new(vec![Column::auto(), Column::fixed(60.0).align(HorizontalAlign::Right)])
+///     .row(vec![Text::new("Player"), Text::new("Score")])
+///     .row(vec![Text::new("est31"), Text::new("1337")]);
+/// # let _ = table;
+/// // for section in table.sections(&mut glyph_brush, (10.0, 10.0)) {
+/// //     glyph_brush.queue(section);
+/// // }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Table<'a> {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Text<'a>>>,
+    column_spacing: f32,
+    row_spacing: f32,
+}
+
+impl<'a> Table<'a> {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Table {
+            columns,
+            rows: Vec::new(),
+            column_spacing: 8.0,
+            row_spacing: 0.0,
+        }
+    }
+
+    /// Appends a row. Cells beyond the number of columns are ignored.
+    pub fn row(mut self, cells: Vec<Text<'a>>) -> Self {
+        self.rows.push(cells);
+        self
+    }
+
+    /// Sets the horizontal space between columns in pixels.
+    ///
+    /// Defaults to 8.
+    pub fn column_spacing(mut self, spacing: f32) -> Self {
+        self.column_spacing = spacing;
+        self
+    }
+
+    /// Sets the vertical space between rows in pixels.
+    ///
+    /// Defaults to 0.
+    pub fn row_spacing(mut self, spacing: f32) -> Self {
+        self.row_spacing = spacing;
+        self
+    }
+
+    fn cell_section(&self, cell: &Text<'a>, column: usize, (x, y): (f32, f32)) -> Section<'a> {
+        let Column { width, align } = self.columns[column];
+        let width = match width {
+            ColumnWidth::Fixed(width) => width,
+            ColumnWidth::Auto => f32::INFINITY,
+        };
+        Section::default()
+            .with_screen_position((x, y))
+            .with_bounds((width, f32::INFINITY))
+            .with_layout(Layout::default_wrap().h_align(align))
+            .add_text(*cell)
+    }
+
+    /// Measures the cells and returns one section per cell, with the top left corner
+    /// of the table at `position`.
+    pub fn sections<F: Font, G: GlyphCruncher<F>>(
+        &self,
+        cruncher: &mut G,
+        (x, y): (f32, f32),
+    ) -> Vec<Section<'a>> {
+        let cells = || {
+            self.rows.iter().enumerate().flat_map(|(row, cells)| {
+                cells
+                    .iter()
+                    .take(self.columns.len())
+                    .enumerate()
+                    .map(move |(column, cell)| (row, column, cell))
+            })
+        };
+
+        let mut widths: Vec<f32> = self
+            .columns
+            .iter()
+            .map(|c| match c.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Auto => 0.0,
+            })
+            .collect();
+        let mut heights: Vec<f32> = self
+            .rows
+            .iter()
+            .map(|cells| cells.iter().map(|c| c.scale.y).fold(0.0, f32::max))
+            .collect();
+        for (row, column, cell) in cells() {
+            let bounds = match cruncher.glyph_bounds(self.cell_section(cell, column, (0.0, 0.0))) {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            if self.columns[column].width == ColumnWidth::Auto {
+                widths[column] = widths[column].max(bounds.width());
+            }
+            heights[row] = heights[row].max(bounds.height());
+        }
+
+        let mut column_x = Vec::with_capacity(widths.len());
+        let mut next_x = x;
+        for width in &widths {
+            column_x.push(next_x);
+            next_x += width + self.column_spacing;
+        }
+        let mut row_y = Vec::with_capacity(heights.len());
+        let mut next_y = y;
+        for height in &heights {
+            row_y.push(next_y);
+            next_y += height + self.row_spacing;
+        }
+
+        cells()
+            .map(|(row, column, cell)| {
+                let offset = match self.columns[column].align {
+                    HorizontalAlign::Left => 0.0,
+                    HorizontalAlign::Center => widths[column] / 2.0,
+                    HorizontalAlign::Right => widths[column],
+                };
+                self.cell_section(cell, column, (column_x[column] + offset, row_y[row]))
+            })
+            .collect()
+    }
+}
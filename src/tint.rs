@@ -0,0 +1,38 @@
+use super::*;
+
+/// Tint leaving the glyph colors unchanged.
+pub(crate) const NO_TINT: [f32; 4] = [1.0; 4];
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Draws the queued sections like [`draw_queued`](#method.draw_queued), with the
+    /// colors of all glyphs multiplied by `color`, e.g. to flash damage numbers red or
+    /// dim a disabled panel, without queueing its sections with other colors.
+    ///
+    /// Tinting doesn't change the queued sections, so drawing them tinted and untinted
+    /// in turn redraws the same vertices. Color glyphs only take the alpha of the tint.
+    pub fn draw_queued_tinted<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        color: [f32; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError> {
+        let transform = screen_transform(surface.get_dimensions());
+        self.draw_queued_tinted_with_transform(color, transform, facade, surface)
+    }
+
+    /// Draws the queued sections tinted like
+    /// [`draw_queued_tinted`](#method.draw_queued_tinted), applying a position transform
+    /// like [`draw_queued_with_transform`](#method.draw_queued_with_transform).
+    pub fn draw_queued_tinted_with_transform<C: Facade + Deref<Target = Context>, S: Surface>(
+        &mut self,
+        color: [f32; 4],
+        transform: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError> {
+        if self.process_for_draw(facade)? {
+            self.draw_batches(&|_| true, transform, color, facade, surface)?;
+        }
+        Ok(self.metrics)
+    }
+}
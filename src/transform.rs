@@ -0,0 +1,168 @@
+use super::*;
+
+use std::hash::Hasher;
+
+/// A 2×3 affine transform applied to a single glyph quad, e.g. to rotate,
+/// scale or skew it.
+///
+/// Maps a point `(x, y)` to `(m[0][0] * x + m[0][1] * y + m[0][2], m[1][0] * x + m[1][1] * y + m[1][2])`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlyphTransform(pub [[f32; 3]; 2]);
+
+impl Default for GlyphTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl GlyphTransform {
+    pub const IDENTITY: GlyphTransform = GlyphTransform([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        GlyphTransform([[1.0, 0.0, x], [0.0, 1.0, y]])
+    }
+
+    /// Rotation clockwise on screen by `angle` radians.
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        GlyphTransform([[cos, -sin, 0.0], [sin, cos, 0.0]])
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        GlyphTransform([[x, 0.0, 0.0], [0.0, y, 0.0]])
+    }
+
+    /// Horizontal skew, e.g. for a fake italic, shifting by `factor` pixels per pixel
+    /// above the origin.
+    pub fn skew(factor: f32) -> Self {
+        GlyphTransform([[1.0, -factor, 0.0], [0.0, 1.0, 0.0]])
+    }
+
+    /// Returns the transform applying `self` first, then `next`.
+    pub fn then(self, next: GlyphTransform) -> Self {
+        let (a, b) = (next.0, self.0);
+        let mut m = [[0.0; 3]; 2];
+        for (row, a) in m.iter_mut().zip(a.iter()) {
+            row[0] = a[0] * b[0][0] + a[1] * b[1][0];
+            row[1] = a[0] * b[0][1] + a[1] * b[1][1];
+            row[2] = a[0] * b[0][2] + a[1] * b[1][2] + a[2];
+        }
+        GlyphTransform(m)
+    }
+
+    /// Returns the transform applied around `(x, y)` instead of the origin.
+    pub fn around(self, (x, y): (f32, f32)) -> Self {
+        GlyphTransform::translation(-x, -y)
+            .then(self)
+            .then(GlyphTransform::translation(x, y))
+    }
+
+    /// Conjugates the transform with a horizontal stretch by `factor`, for subpixel
+    /// mode's stretched coordinates.
+    pub(crate) fn stretched(self, factor: f32) -> Self {
+        let [[a, b, tx], [c, d, ty]] = self.0;
+        GlyphTransform([[a, b * factor, tx * factor], [c / factor, d, ty]])
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Queues a section like [`queue`](#method.queue), with its glyphs transformed,
+    /// see [`SectionStyle::transform`](struct.SectionStyle.html#method.transform).
+    pub fn queue_transformed<'a, S>(&mut self, section: S, transform: GlyphTransform)
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        self.queue_styled(section, &SectionStyle::default().transform(transform))
+    }
+
+    /// Queues a section like [`queue`](#method.queue), rotated clockwise on screen by
+    /// `angle` radians around `pivot` in pixels, e.g. its `screen_position`.
+    pub fn queue_rotated<'a, S>(&mut self, section: S, angle: f32, pivot: (f32, f32))
+    where
+        S: Into<Cow<'a, Section<'a, X>>>,
+    {
+        self.queue_styled(section, &SectionStyle::default().rotate(angle, pivot))
+    }
+}
+
+/// Per glyph data passed through glyph_brush for vertex generation.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct GlyphExtra<X = Extra> {
+    pub(crate) extra: X,
+    /// in absolute pixel coordinates
+    pub(crate) transform: GlyphTransform,
+    pub(crate) fill: Option<TextFill>,
+    pub(crate) procedural: Option<ProceduralFill>,
+    /// stroke width of hollow glyphs, 0 for filled ones
+    pub(crate) hollow: f32,
+    /// depth replacing the **z** of `extra`
+    pub(crate) depth: Option<f32>,
+    pub(crate) params: Option<DrawParamsId>,
+    /// index of the layer, 0 for the default one
+    pub(crate) layer: usize,
+    /// sampled with nearest filtering, for pixel fonts
+    pub(crate) nearest: bool,
+    /// only kept in the cache, not drawn
+    pub(crate) pinned: bool,
+    /// id of the retained section of the glyph
+    pub(crate) retained: Option<u32>,
+}
+
+impl<X> From<X> for GlyphExtra<X> {
+    fn from(extra: X) -> Self {
+        GlyphExtra {
+            extra,
+            transform: GlyphTransform::IDENTITY,
+            fill: None,
+            procedural: None,
+            hollow: 0.0,
+            depth: None,
+            params: None,
+            layer: 0,
+            nearest: false,
+            pinned: false,
+            retained: None,
+        }
+    }
+}
+
+impl<X: Hash> Hash for GlyphExtra<X> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.extra.hash(state);
+        for row in &self.transform.0 {
+            for v in row {
+                v.to_bits().hash(state);
+            }
+        }
+        self.fill.hash(state);
+        self.procedural.hash(state);
+        self.hollow.to_bits().hash(state);
+        self.depth.map(f32::to_bits).hash(state);
+        self.params.hash(state);
+        self.layer.hash(state);
+        self.nearest.hash(state);
+        self.pinned.hash(state);
+        self.retained.hash(state);
+    }
+}
+
+/// Converts a section to the internal extra type, with untransformed glyphs.
+pub(crate) fn with_glyph_extra<'a, X: Clone>(
+    section: &Section<'a, X>,
+) -> Section<'a, GlyphExtra<X>> {
+    Section {
+        screen_position: section.screen_position,
+        bounds: section.bounds,
+        layout: section.layout,
+        text: section
+            .text
+            .iter()
+            .map(|t| glyph_brush::Text {
+                text: t.text,
+                scale: t.scale,
+                font_id: t.font_id,
+                extra: GlyphExtra::from(t.extra.clone()),
+            })
+            .collect(),
+    }
+}
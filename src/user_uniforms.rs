@@ -0,0 +1,75 @@
+use super::*;
+
+use glium::uniforms::Uniforms;
+
+/// Uniforms the brush sets itself, which can't be replaced.
+const BUILT_IN_UNIFORMS: [&str; 10] = [
+    "font_tex",
+    "fill_tex",
+    "fill_enabled",
+    "time",
+    "opacity",
+    "tint",
+    "transform",
+    "gamma",
+    "subpixel_offset",
+    "coverage_pass",
+];
+
+/// Built-in uniforms followed by the uniforms set by the user.
+pub(crate) struct WithUserUniforms<'a, U> {
+    pub(crate) uniforms: U,
+    pub(crate) user: &'a [(String, UniformValue<'static>)],
+}
+
+impl<'a, U: Uniforms> Uniforms for WithUserUniforms<'a, U> {
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut f: F) {
+        for (name, value) in self.user {
+            f(name, *value);
+        }
+        self.uniforms.visit_values(f);
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Passes an extra uniform to the glyph shaders, e.g. a dissolve threshold or a
+    /// highlight color for effects implemented in
+    /// [custom shaders](struct.GlyphBrushBuilder.html#method.with_shaders), animated along
+    /// with the `time` uniform of [`set_time`](#method.set_time). Replaces a uniform of
+    /// the same name.
+    ///
+    /// Returns `false` without setting it if `name` is one of the uniforms the brush sets
+    /// itself. Shaders without the uniform ignore it, a shader declaring it with another
+    /// type fails to draw.
+    ///
+    /// ```no_run
+    /// # extern crate glium;
+    /// # extern crate glium_glyph;
+    /// # use glium::uniforms::UniformValue;
+    /// # use glium_glyph::GlyphBrush;
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # fn f(brush: &mut GlyphBrush<FontArc>, seconds: f32) {
+    /// brush.set_time(seconds);
+    /// brush.set_uniform("dissolve", UniformValue::Float((seconds / 2.0).min(1.0)));
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn set_uniform(&mut self, name: &str, value: UniformValue<'static>) -> bool {
+        if BUILT_IN_UNIFORMS.contains(&name) {
+            return false;
+        }
+        match self.user_uniforms.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value,
+            None => self.user_uniforms.push((name.to_owned(), value)),
+        }
+        true
+    }
+
+    /// Stops passing a uniform set with [`set_uniform`](#method.set_uniform), returning
+    /// whether it was set.
+    pub fn remove_uniform(&mut self, name: &str) -> bool {
+        let len = self.user_uniforms.len();
+        self.user_uniforms.retain(|(n, _)| n != name);
+        self.user_uniforms.len() != len
+    }
+}
@@ -0,0 +1,107 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{FontRef, VariableFont, VariationAxis};
+
+/// An instance of a variable font added to the brush.
+struct Instance {
+    source: FontId,
+    /// axis tags and the bits of their values
+    axes: Vec<([u8; 4], u32)>,
+    id: FontId,
+}
+
+/// Variable fonts of a brush and the instances created from them.
+#[derive(Default)]
+pub(crate) struct VariableFonts {
+    /// font data of the variable fonts, by the id of their default instance
+    sources: Vec<(FontId, Vec<u8>)>,
+    instances: Vec<Instance>,
+}
+
+impl VariableFonts {
+    fn source(&self, font_id: FontId) -> Option<&[u8]> {
+        self.sources
+            .iter()
+            .find(|(id, _)| *id == font_id)
+            .map(|(_, data)| &data[..])
+    }
+
+    /// Forgets the font at `font_id` as a variable font or an instance, after it was
+    /// replaced.
+    pub(crate) fn forget(&mut self, font_id: FontId) {
+        self.sources.retain(|(id, _)| *id != font_id);
+        self.instances
+            .retain(|i| i.source != font_id && i.id != font_id);
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Parses a variable font and adds it like
+    /// [`try_add_font_bytes`](#method.try_add_font_bytes) at its default axis values,
+    /// keeping a copy of `font_data` to create [instances](#method.font_instance) of it.
+    pub fn try_add_variable_font_bytes(&mut self, font_data: Vec<u8>) -> Result<FontId, FontError>
+    where
+        F: From<FontVec> + Clone,
+    {
+        let id = self.try_add_font_bytes(font_data.clone())?;
+        self.variable_fonts.sources.push((id, font_data));
+        Ok(id)
+    }
+
+    /// Returns the variation axes, e.g. weight `wght` or width `wdth`, of a font added with
+    /// [`try_add_variable_font_bytes`](#method.try_add_variable_font_bytes), or `None` for
+    /// other fonts.
+    pub fn variation_axes(&self, font_id: FontId) -> Option<Vec<VariationAxis>> {
+        let data = self.variable_fonts.source(font_id)?;
+        FontRef::try_from_slice(data).ok().map(|f| f.variations())
+    }
+
+    /// Returns the id of an instance of a variable font with the given axis values, e.g.
+    /// `&[(*b"wght", 700.0)]` for bold, adding the instance on first use. Texts use it
+    /// with `Text::with_font_id`, the glyphs of every instance are cached separately.
+    ///
+    /// Returns `None` if `font_id` wasn't added with
+    /// [`try_add_variable_font_bytes`](#method.try_add_variable_font_bytes) or lacks
+    /// one of the axes. Each instance holds its own copy of the font data.
+    ///
+    /// ```no_run
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::GlyphBrush;
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # use glium_glyph::glyph_brush::{Section, Text};
+    /// # fn f(brush: &mut GlyphBrush<FontArc>, font_data: Vec<u8>) {
+    /// let font = brush.try_add_variable_font_bytes(font_data).unwrap();
+    /// let bold = brush.font_instance(font, &[(*b"wght", 700.0)]).unwrap();
+    /// brush.queue(Section::default().add_text(Text::new("Warning").with_font_id(bold)));
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn font_instance(&mut self, font_id: FontId, axes: &[([u8; 4], f32)]) -> Option<FontId>
+    where
+        F: From<FontVec>,
+    {
+        let key: Vec<([u8; 4], u32)> = axes.iter().map(|&(tag, v)| (tag, v.to_bits())).collect();
+        let existing = self
+            .variable_fonts
+            .instances
+            .iter()
+            .find(|i| i.source == font_id && i.axes == key);
+        if let Some(instance) = existing {
+            return Some(instance.id);
+        }
+        let data = self.variable_fonts.source(font_id)?;
+        let mut font = FontVec::try_from_vec(data.to_vec()).ok()?;
+        for (tag, value) in axes {
+            if !font.set_variation(tag, *value) {
+                return None;
+            }
+        }
+        let id = self.glyph_brush.add_font(font);
+        self.variable_fonts.instances.push(Instance {
+            source: font_id,
+            axes: key,
+            id,
+        });
+        Some(id)
+    }
+}
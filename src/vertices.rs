@@ -0,0 +1,121 @@
+use super::*;
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Lays out the queued sections and updates the cache texture like
+    /// [`draw_queued`](#method.draw_queued), but returns the glyph quads instead of
+    /// drawing them, e.g. to merge them into a vertex buffer of a custom renderer.
+    /// The quads sample the cache texture returned by [`texture`](#method.texture).
+    ///
+    /// Returns `None` if the quads are unchanged since the previous call, the previous
+    /// quads can be drawn again then. Pixel rectangles are unstretched in subpixel mode.
+    /// Color glyphs and pinned glyphs aren't returned. Drawing with the brush in between
+    /// makes the next call regenerate all quads. Unlike drawing, a queue whose glyphs
+    /// don't fit into the largest cache texture at once isn't split into batches,
+    /// `DrawError::CacheFull` is returned instead.
+    ///
+    /// ```no_run
+    /// # extern crate glium;
+    /// # extern crate glium_glyph;
+    /// # use glium_glyph::GlyphBrush;
+    /// # use glium_glyph::glyph_brush::ab_glyph::FontArc;
+    /// # fn f(display: &glium::Display, brush: &mut GlyphBrush<FontArc>) {
+    /// let mut quads = Vec::new();
+    /// if let Some(new_quads) = brush.process_queued_to_vertices(display).unwrap() {
+    ///     quads = new_quads;
+    /// }
+    /// // draw `quads` sampling `brush.texture()`
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn process_queued_to_vertices<C: Facade>(
+        &mut self,
+        facade: &C,
+    ) -> Result<Option<Vec<GlyphQuad<X>>>, DrawError> {
+        self.queue_retained();
+        if self.trim_pending {
+            self.trim_memory(facade)?;
+        }
+        if !self.vertices_taken {
+            // the previous vertices were drawn by the brush, not handed out
+            self.invalidate_vertices();
+            self.vertices_taken = true;
+        }
+        self.queued_glyphs = 0;
+        self.truncated_glyphs = 0;
+        self.metrics = Metrics {
+            sections_processed: self.queued_sections,
+            ..Metrics::default()
+        };
+        self.queued_sections = 0;
+        #[cfg(feature = "color-glyphs")]
+        self.color_glyphs.take_queue();
+        self.queue_pinned();
+        self.overflow.forget_queued();
+
+        let brush_action = loop {
+            let brush_action = {
+                let tex = &self.texture;
+                let raster_mode = self.raster_mode;
+                let uploader = &mut self.texture_uploader;
+                let metrics = &mut self.metrics;
+                let regions = &mut self.cache_regions;
+                let region = self.cache_region;
+                self.glyph_brush.process_queued(
+                    |rect, tex_data| {
+                        let rect = in_region(rect, region);
+                        upload_glyph(tex, raster_mode, uploader, rect, tex_data);
+                        metrics.texture_uploads += 1;
+                        metrics.uploaded_bytes += tex_data.len();
+                        regions.track(rect);
+                    },
+                    |v| to_filled_vertex(v, true),
+                )
+            };
+            match brush_action {
+                Ok(action) => break action,
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    let size = self.budgeted_texture_size(suggested);
+                    if let Err(e) = self.grow_texture(facade, size) {
+                        self.discard_queued();
+                        return Err(e);
+                    }
+                    self.metrics.texture_resized = true;
+                }
+            }
+        };
+
+        let (width, height) = self.cache_size();
+        self.metrics.cache_area = width as u64 * height as u64;
+        self.metrics.cache_used_area = self.cache_regions.area();
+
+        let mut verts = match brush_action {
+            BrushAction::Draw(verts) => verts,
+            BrushAction::ReDraw => return Ok(None),
+        };
+        self.metrics.regenerated_vertices = true;
+        verts.retain(|(batch, _, _)| !batch.pinned);
+        if self.pixel_snapping {
+            snap_to_pixels(verts.iter_mut().map(|(_, v, _)| v));
+        }
+        let tex_size = self.texture.dimensions();
+        map_tex_coords(
+            verts.iter_mut().map(|(_, v, _)| v),
+            self.cache_region,
+            tex_size,
+        );
+        let stretch = match self.subpixel {
+            Some(_) => 3.0,
+            None => 1.0,
+        };
+        let quads = verts
+            .into_iter()
+            .filter_map(|(_, v, extra)| {
+                let mut quad = GlyphQuad::new(&v, extra?);
+                quad.pixel_rect.min.x /= stretch;
+                quad.pixel_rect.max.x /= stretch;
+                Some(quad)
+            })
+            .collect();
+        Ok(Some(quads))
+    }
+}
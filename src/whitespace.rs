@@ -0,0 +1,152 @@
+use super::*;
+
+use glyph_brush::ab_glyph::{Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, HorizontalAlign, Layout, SectionGeometry, SectionGlyph, ToSectionText,
+};
+
+/// Wraps a [`Layout`](enum.Layout.html), trimming the whitespace at the start and end
+/// of every visual line, e.g. spaces that wrapped to the start of a line, so that lines
+/// align by their visible text.
+///
+/// Both sides are trimmed by default, disable trimming to keep the whitespace, e.g. for
+/// indented code.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::WhitespaceLayout;
+/// # use glium_glyph::glyph_brush::{Layout, Section};
+/// # fn main() {
+/// let text = Section::default().with_bounds((200.0, f32::INFINITY));
+/// let layout = WhitespaceLayout::new(Layout::default()).trim_trailing(false);
+/// # let _ = (text, layout);
+/// // glyph_brush.queue_custom_layout(text, &layout);
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WhitespaceLayout {
+    layout: Layout<BuiltInLineBreaker>,
+    trim_leading: bool,
+    trim_trailing: bool,
+}
+
+impl WhitespaceLayout {
+    pub fn new(layout: Layout<BuiltInLineBreaker>) -> Self {
+        WhitespaceLayout {
+            layout,
+            trim_leading: true,
+            trim_trailing: true,
+        }
+    }
+
+    /// Sets whether whitespace at the start of lines is trimmed.
+    ///
+    /// Defaults to `true`.
+    pub fn trim_leading(mut self, trim: bool) -> Self {
+        self.trim_leading = trim;
+        self
+    }
+
+    /// Sets whether whitespace at the end of lines is trimmed.
+    ///
+    /// Defaults to `true`.
+    pub fn trim_trailing(mut self, trim: bool) -> Self {
+        self.trim_trailing = trim;
+        self
+    }
+
+    fn h_align(&self) -> HorizontalAlign {
+        match self.layout {
+            Layout::SingleLine { h_align, .. } | Layout::Wrap { h_align, .. } => h_align,
+        }
+    }
+}
+
+impl GlyphPositioner for WhitespaceLayout {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let glyphs = self.layout.calculate_glyphs(fonts, geometry, sections);
+        if !self.trim_leading && !self.trim_trailing {
+            return glyphs;
+        }
+        let texts: Vec<&str> = sections.iter().map(|s| s.to_section_text().text).collect();
+        let is_whitespace = |g: &SectionGlyph| {
+            texts[g.section_index][g.byte_index..].starts_with(char::is_whitespace)
+        };
+        let right = |g: &SectionGlyph| {
+            let font = fonts[g.font_id.0].as_scaled(g.glyph.scale);
+            g.glyph.position.x + font.h_advance(g.glyph.id)
+        };
+
+        // glyphs come line by line, the glyphs of a line share their baseline
+        let mut trimmed = Vec::with_capacity(glyphs.len());
+        let mut rest = &glyphs[..];
+        while let Some(first) = rest.first() {
+            let len = rest
+                .iter()
+                .position(|g| g.glyph.position.y != first.glyph.position.y)
+                .unwrap_or(rest.len());
+            let (line, next) = rest.split_at(len);
+            rest = next;
+
+            let visible = match (
+                line.iter().position(|g| !is_whitespace(g)),
+                line.iter().rposition(|g| !is_whitespace(g)),
+            ) {
+                (Some(start), Some(end)) => start..end + 1,
+                // nothing visible to align
+                _ => {
+                    if !self.trim_leading || !self.trim_trailing {
+                        trimmed.extend_from_slice(line);
+                    }
+                    continue;
+                }
+            };
+            let line_left = line
+                .iter()
+                .map(|g| g.glyph.position.x)
+                .fold(f32::INFINITY, f32::min);
+            let line_right = line.iter().map(&right).fold(f32::NEG_INFINITY, f32::max);
+            let left = match self.trim_leading {
+                true => line[visible.start].glyph.position.x,
+                false => line_left,
+            };
+            let right = match self.trim_trailing {
+                true => right(&line[visible.end - 1]),
+                false => line_right,
+            };
+            let x = geometry.screen_position.0;
+            let offset = match self.h_align() {
+                HorizontalAlign::Left if self.trim_leading => x - left,
+                HorizontalAlign::Right if self.trim_trailing => x - right,
+                HorizontalAlign::Center => x - (left + right) / 2.0,
+                _ => 0.0,
+            };
+
+            let start = if self.trim_leading { visible.start } else { 0 };
+            let end = if self.trim_trailing {
+                visible.end
+            } else {
+                line.len()
+            };
+            trimmed.extend(line[start..end].iter().map(|g| {
+                let mut g = g.clone();
+                g.glyph.position.x += offset;
+                g
+            }));
+        }
+        trimmed
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
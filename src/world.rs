@@ -0,0 +1,163 @@
+use super::*;
+
+const IDENTITY_MATRIX: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// Flips the y axis, undoing the flip the vertex shader applies to the draw transform.
+pub(crate) const INVERT_Y_AXIS: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, -1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// Multiplies two column major matrices.
+pub(crate) fn mat_mul(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    let mut m = [[0.0; 4]; 4];
+    for (col, b) in m.iter_mut().zip(b.iter()) {
+        for (row, v) in col.iter_mut().enumerate() {
+            *v = (0..4).map(|k| a[k][row] * b[k]).sum();
+        }
+    }
+    m
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalized(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len == 0.0 {
+        return v;
+    }
+    [v[0] / len, v[1] / len, v[2] / len]
+}
+
+/// Returns the draw transform of text in world space for column major view and
+/// projection matrices.
+pub(crate) fn world_transform(
+    model: [[f32; 4]; 4],
+    view: [[f32; 4]; 4],
+    projection: [[f32; 4]; 4],
+) -> [[f32; 4]; 4] {
+    mat_mul(INVERT_Y_AXIS, mat_mul(projection, mat_mul(view, model)))
+}
+
+/// A plane in world space to lay out text on, e.g. a sign on a wall.
+///
+/// The pixel coordinates of queued sections are mapped onto the plane: `origin` is where
+/// pixel `(0, 0)` ends up, `right` and `down` are the world space vectors spanned by one
+/// pixel along the x and y axis. Section **z** values move glyphs along the unit normal
+/// `right × down`.
+///
+/// ```no_run
+/// # extern crate glium_glyph;
+/// # use glium_glyph::TextPlane;
+/// # fn main() {
+/// // text facing +z, 100 pixels of it are one world unit wide
+/// let plane = TextPlane::upright([0.0, 2.0, -5.0], [0.0, 0.0, 1.0], 0.01);
+/// // glyph_brush.queue(Section::default().add_text(Text::new("Exit")));
+/// // glyph_brush.draw_queued_on_plane(&plane, view, projection, &display, &mut target)?;
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextPlane {
+    pub origin: [f32; 3],
+    pub right: [f32; 3],
+    pub down: [f32; 3],
+}
+
+impl TextPlane {
+    pub fn new(origin: [f32; 3], right: [f32; 3], down: [f32; 3]) -> Self {
+        TextPlane {
+            origin,
+            right,
+            down,
+        }
+    }
+
+    /// A plane upright in a world with +y up, with the text readable when looking
+    /// against `facing`, and `pixel_size` world units per pixel.
+    pub fn upright(origin: [f32; 3], facing: [f32; 3], pixel_size: f32) -> Self {
+        let right = normalized(cross([0.0, 1.0, 0.0], facing));
+        TextPlane {
+            origin,
+            right: [
+                right[0] * pixel_size,
+                right[1] * pixel_size,
+                right[2] * pixel_size,
+            ],
+            down: [0.0, -pixel_size, 0.0],
+        }
+    }
+
+    /// Returns the column major matrix mapping pixel coordinates to world space.
+    pub fn model(&self) -> [[f32; 4]; 4] {
+        let normal = normalized(cross(self.right, self.down));
+        let column = |v: [f32; 3], w| [v[0], v[1], v[2], w];
+        [
+            column(self.right, 0.0),
+            column(self.down, 0.0),
+            column(normal, 0.0),
+            column(self.origin, 1.0),
+        ]
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher, X: GlyphData> GlyphBrush<F, H, X> {
+    /// Draws the queued sections in world space, taking their pixel coordinates and
+    /// **z** values as world coordinates, for column major `view` and `projection`
+    /// matrices as used by glium.
+    ///
+    /// Pixel y coordinates grow downwards, so text reads upside down in a world with
+    /// +y up, see [`draw_queued_on_plane`](#method.draw_queued_on_plane) to place it.
+    /// Glyphs are rasterized at their section's scale in pixels, use a scale close to
+    /// the size on screen or [`RasterMode::Sdf`](enum.RasterMode.html#variant.Sdf) to
+    /// keep magnified text sharp. Set a [depth test](struct.GlyphBrushBuilder.html#method.depth)
+    /// to have scene geometry occlude the text.
+    pub fn draw_queued_with_view_projection<C, S>(
+        &mut self,
+        view: [[f32; 4]; 4],
+        projection: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError>
+    where
+        C: Facade + Deref<Target = Context>,
+        S: Surface,
+    {
+        let transform = world_transform(IDENTITY_MATRIX, view, projection);
+        self.try_draw_queued_with_transform(transform, facade, surface)
+    }
+
+    /// Draws the queued sections like
+    /// [`draw_queued_with_view_projection`](#method.draw_queued_with_view_projection),
+    /// laid out on `plane`.
+    ///
+    /// Sections on different planes are drawn by queueing and drawing the sections of
+    /// each plane in turn.
+    pub fn draw_queued_on_plane<C, S>(
+        &mut self,
+        plane: &TextPlane,
+        view: [[f32; 4]; 4],
+        projection: [[f32; 4]; 4],
+        facade: &C,
+        surface: &mut S,
+    ) -> Result<Metrics, DrawError>
+    where
+        C: Facade + Deref<Target = Context>,
+        S: Surface,
+    {
+        let transform = world_transform(plane.model(), view, projection);
+        self.try_draw_queued_with_transform(transform, facade, surface)
+    }
+}
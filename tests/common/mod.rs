@@ -0,0 +1,46 @@
+use glium::glutin;
+use glium::glutin::dpi::PhysicalSize;
+use glium::glutin::event_loop::EventLoopBuilder;
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+use glium::glutin::platform::unix::EventLoopBuilderExtUnix;
+#[cfg(target_os = "windows")]
+use glium::glutin::platform::windows::EventLoopBuilderExtWindows;
+use glium::HeadlessRenderer;
+
+pub const DEJAVU: &[u8] = include_bytes!("../../fonts/DejaVuSans-2.37.ttf");
+
+/// Creates a headless context like `examples/headless.rs`. Only one can be created per
+/// test binary.
+///
+/// Tests using it are ignored by default, as it needs a display to create the context
+/// on. Run them with `cargo test -- --ignored`.
+pub fn renderer() -> HeadlessRenderer {
+    let mut event_loop = EventLoopBuilder::new();
+    // the tests don't run on the main thread
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "windows"
+    ))]
+    event_loop.with_any_thread(true);
+    let event_loop = event_loop.build();
+    let context = glutin::ContextBuilder::new()
+        .build_headless(&event_loop, PhysicalSize::new(1, 1))
+        .unwrap();
+    let context = unsafe { context.treat_as_current() };
+    HeadlessRenderer::new(context).unwrap()
+}
+
+/// Returns the number of pixels with any coverage.
+pub fn covered(pixels: &[u8]) -> usize {
+    pixels.chunks(4).filter(|p| p[3] > 0).count()
+}
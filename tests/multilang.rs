@@ -0,0 +1,55 @@
+//! Draws the paragraphs of `examples/multilang.rs` headless and checks the pixels.
+
+extern crate glium;
+extern crate glium_glyph;
+
+mod common;
+
+use glium_glyph::glyph_brush::ab_glyph::FontRef;
+use glium_glyph::glyph_brush::{Section, Text};
+use glium_glyph::{GlyphBrush, GlyphBrushBuilder, MissingGlyph};
+
+const PARAGRAPHS: &[&str] = &[
+    "Latin: The quick brown fox jumps over the lazy dog.",
+    "CJK: 日本語のテキストと中文文本，还有한국어 텍스트。",
+    "Arabic: مرحبا بالعالم",
+    "Emoji: 😀 🎉 🦀 👍🏽",
+    "Mixed: glium-glyph 描画 نص 🚀 done.",
+];
+
+const SIZE: (u32, u32) = (1024, 48);
+
+#[test]
+#[ignore = "needs a display to create a headless context on"]
+fn multilang() {
+    let renderer = common::renderer();
+    let font = FontRef::try_from_slice(common::DEJAVU).unwrap();
+    let mut brush = GlyphBrushBuilder::using_font(font).build(&renderer);
+    // the bundled font has no CJK glyphs and few emoji
+    brush.set_missing_glyph(MissingGlyph::Skip);
+
+    let render = |brush: &mut GlyphBrush<FontRef>, text: &str| {
+        let section = Section::default()
+            .add_text(
+                Text::new(text)
+                    .with_scale(32.0)
+                    .with_color([1.0, 1.0, 1.0, 1.0]),
+            )
+            .with_screen_position((8.0, 8.0));
+        brush
+            .render_to_pixels(&renderer, Some(section), SIZE)
+            .unwrap()
+    };
+    for paragraph in PARAGRAPHS {
+        let pixels = render(&mut brush, paragraph);
+        assert!(
+            common::covered(&pixels) > 0,
+            "nothing drawn of {}",
+            paragraph
+        );
+    }
+
+    // characters no font has a glyph for are skipped
+    let label = render(&mut brush, "Emoji:");
+    assert_eq!(render(&mut brush, "Emoji:🚀"), label);
+}
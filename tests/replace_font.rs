@@ -0,0 +1,48 @@
+extern crate glium;
+extern crate glium_glyph;
+
+mod common;
+
+use glium_glyph::glyph_brush::ab_glyph::FontRef;
+use glium_glyph::glyph_brush::{FontId, Section, Text};
+use glium_glyph::{FontQuery, GlyphBrushBuilder};
+
+const SIZE: (u32, u32) = (256, 64);
+
+fn section() -> Section<'static> {
+    Section::default()
+        .add_text(Text::new("Replaced").with_scale(32.0))
+        .with_screen_position((8.0, 8.0))
+}
+
+#[test]
+#[ignore = "needs a display to create a headless context on"]
+fn replace_font() {
+    let renderer = common::renderer();
+    let font = FontRef::try_from_slice(common::DEJAVU).unwrap();
+    let mut brush = GlyphBrushBuilder::using_font(font.clone()).build(&renderer);
+    brush.set_font_style(FontId(0), "DejaVu Sans", 400, false);
+    let before = brush
+        .render_to_pixels(&renderer, Some(section()), SIZE)
+        .unwrap();
+    assert!(common::covered(&before) > 0);
+
+    assert!(brush.replace_font(FontId(0), font.clone()));
+    // the style was set for the old font
+    assert_eq!(brush.query_font(&FontQuery::new("DejaVu Sans")), None);
+    brush.set_font_style(FontId(0), "DejaVu Sans", 400, false);
+    assert_eq!(
+        brush.query_font(&FontQuery::new("DejaVu Sans")),
+        Some(FontId(0))
+    );
+
+    // the glyphs are rasterized again from the new font
+    let after = brush
+        .render_to_pixels(&renderer, Some(section()), SIZE)
+        .unwrap();
+    assert_eq!(after, before);
+
+    // the queue would be lost
+    brush.queue(section());
+    assert!(!brush.replace_font(FontId(0), font));
+}